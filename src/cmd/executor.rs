@@ -6,24 +6,39 @@
 //! - Notifying the render loop when tasks complete
 //! - Supporting graceful shutdown
 
-use super::Cmd;
+use super::{Cmd, TypedCmd};
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::mpsc;
 
+/// Receives the messages produced by a command's tasks
+type MsgSink<M> = Arc<dyn Fn(M) + Send + Sync>;
+
 /// Handle for requesting renders from background tasks
 ///
 /// This is a lightweight, cloneable handle that can be passed to
 /// async tasks to request UI re-renders when they complete.
 #[derive(Clone)]
 pub struct RenderHandle {
-    tx: mpsc::UnboundedSender<()>,
+    notify: Arc<dyn Fn() + Send + Sync>,
 }
 
 impl RenderHandle {
     /// Create a new render handle
     pub fn new(tx: mpsc::UnboundedSender<()>) -> Self {
-        Self { tx }
+        Self::from_fn(move || {
+            let _ = tx.send(());
+        })
+    }
+
+    /// Create a render handle that calls `notify` for each request
+    pub fn from_fn<F>(notify: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        Self {
+            notify: Arc::new(notify),
+        }
     }
 
     /// Request a render
@@ -31,7 +46,7 @@ impl RenderHandle {
     /// This is a non-blocking operation that signals the event loop
     /// to re-render the UI on the next frame.
     pub fn request(&self) {
-        let _ = self.tx.send(());
+        (self.notify)();
     }
 }
 
@@ -74,6 +89,15 @@ impl CmdExecutor {
     ///
     /// Panics if the Tokio runtime cannot be created.
     pub fn new(render_tx: mpsc::UnboundedSender<()>) -> Self {
+        Self::with_render_handle(RenderHandle::new(render_tx))
+    }
+
+    /// Create a new command executor that requests renders through `render_handle`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Tokio runtime cannot be created.
+    pub fn with_render_handle(render_handle: RenderHandle) -> Self {
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .worker_threads(2) // Lightweight runtime for UI tasks
             .thread_name("rnk-cmd-executor")
@@ -83,7 +107,7 @@ impl CmdExecutor {
 
         Self {
             runtime: Some(Arc::new(runtime)),
-            render_handle: RenderHandle::new(render_tx),
+            render_handle,
         }
     }
 
@@ -113,23 +137,60 @@ impl CmdExecutor {
     /// executor.shutdown();
     /// ```
     pub fn execute(&self, cmd: Cmd) {
-        self.execute_internal(cmd, true);
+        self.execute_internal(cmd, Arc::new(|()| {}), true);
+    }
+
+    /// Execute a command whose tasks resolve to messages
+    ///
+    /// Works like [`execute`](Self::execute), but every value produced by
+    /// the command's `Perform`, `Tick` and `Every` tasks is passed to
+    /// `on_msg` (on an executor thread) before the render request is sent.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use rnk::cmd::{CmdExecutor, TypedCmd};
+    /// use tokio::sync::mpsc;
+    ///
+    /// let (tx, mut rx) = mpsc::unbounded_channel();
+    /// let executor = CmdExecutor::new(tx);
+    /// let (msg_tx, msg_rx) = std::sync::mpsc::channel();
+    ///
+    /// executor.execute_with(TypedCmd::perform(|| async { 42 }), move |msg| {
+    ///     let _ = msg_tx.send(msg);
+    /// });
+    ///
+    /// rx.blocking_recv();
+    /// assert_eq!(msg_rx.recv().unwrap(), 42);
+    /// executor.shutdown();
+    /// ```
+    pub fn execute_with<M, F>(&self, cmd: TypedCmd<M>, on_msg: F)
+    where
+        M: Send + 'static,
+        F: Fn(M) + Send + Sync + 'static,
+    {
+        self.execute_internal(cmd, Arc::new(on_msg), true);
     }
 
     /// Internal execute method with optional render notification
-    fn execute_internal(&self, cmd: Cmd, notify_render: bool) {
+    fn execute_internal<M: Send + 'static>(
+        &self,
+        cmd: TypedCmd<M>,
+        on_msg: MsgSink<M>,
+        notify_render: bool,
+    ) {
         let runtime = self.runtime.as_ref().expect("executor was shutdown");
         let render_handle = self.render_handle.clone();
 
         match cmd {
-            Cmd::None => {
+            TypedCmd::None => {
                 // No-op, don't notify render
             }
 
-            Cmd::Batch(cmds) => {
+            TypedCmd::Batch(cmds) => {
                 // Execute all commands in parallel (no ordering guarantees)
                 for cmd in cmds {
-                    self.execute_internal(cmd, false);
+                    self.execute_internal(cmd, on_msg.clone(), false);
                 }
                 // Only notify once for the entire batch
                 if notify_render {
@@ -137,7 +198,7 @@ impl CmdExecutor {
                 }
             }
 
-            Cmd::Sequence(cmds) => {
+            TypedCmd::Sequence(cmds) => {
                 // Execute commands sequentially (in order)
                 if cmds.is_empty() {
                     if notify_render {
@@ -159,7 +220,7 @@ impl CmdExecutor {
 
                         // Execute and wait for completion using a oneshot channel
                         let (tx, rx) = tokio::sync::oneshot::channel();
-                        temp_executor.execute_with_completion(cmd, tx);
+                        temp_executor.execute_with_completion(cmd, on_msg.clone(), tx);
 
                         // Prevent shutdown on drop
                         std::mem::forget(temp_executor);
@@ -175,16 +236,16 @@ impl CmdExecutor {
                 });
             }
 
-            Cmd::Perform { future } => {
+            TypedCmd::Perform { future } => {
                 runtime.spawn(async move {
-                    future.await;
+                    on_msg(future.await);
                     if notify_render {
                         render_handle.request();
                     }
                 });
             }
 
-            Cmd::Sleep { duration, then } => {
+            TypedCmd::Sleep { duration, then } => {
                 // Clone the runtime Arc for the spawned task
                 let runtime_clone = Arc::clone(runtime);
                 let render_handle_clone = render_handle.clone();
@@ -194,7 +255,7 @@ impl CmdExecutor {
 
                     // Execute the 'then' command
                     match *then {
-                        Cmd::None => {
+                        TypedCmd::None => {
                             // If 'then' is None, still notify if requested
                             if notify_render {
                                 render_handle_clone.request();
@@ -207,7 +268,7 @@ impl CmdExecutor {
                                 render_handle: render_handle_clone,
                             };
 
-                            temp_executor.execute_internal(other, notify_render);
+                            temp_executor.execute_internal(other, on_msg, notify_render);
 
                             // Prevent shutdown on drop
                             std::mem::forget(temp_executor);
@@ -216,18 +277,18 @@ impl CmdExecutor {
                 });
             }
 
-            Cmd::Tick { duration, callback } => {
+            TypedCmd::Tick { duration, callback } => {
                 runtime.spawn(async move {
                     tokio::time::sleep(duration).await;
                     let timestamp = Instant::now();
-                    callback(timestamp);
+                    on_msg(callback(timestamp));
                     if notify_render {
                         render_handle.request();
                     }
                 });
             }
 
-            Cmd::Every { duration, callback } => {
+            TypedCmd::Every { duration, callback } => {
                 runtime.spawn(async move {
                     // Calculate time until next aligned boundary
                     // For example, if duration is 1 second and current time is 12:34:56.789,
@@ -254,7 +315,7 @@ impl CmdExecutor {
                     tokio::time::sleep(wait_duration).await;
 
                     let timestamp = Instant::now();
-                    callback(timestamp);
+                    on_msg(callback(timestamp));
                     if notify_render {
                         render_handle.request();
                     }
@@ -264,16 +325,21 @@ impl CmdExecutor {
     }
 
     /// Execute a command and signal completion via a oneshot channel
-    fn execute_with_completion(&self, cmd: Cmd, completion: tokio::sync::oneshot::Sender<()>) {
+    fn execute_with_completion<M: Send + 'static>(
+        &self,
+        cmd: TypedCmd<M>,
+        on_msg: MsgSink<M>,
+        completion: tokio::sync::oneshot::Sender<()>,
+    ) {
         let runtime = self.runtime.as_ref().expect("executor was shutdown");
         let render_handle = self.render_handle.clone();
 
         match cmd {
-            Cmd::None => {
+            TypedCmd::None => {
                 let _ = completion.send(());
             }
 
-            Cmd::Batch(cmds) => {
+            TypedCmd::Batch(cmds) => {
                 // Execute all commands in parallel and wait for all to complete
                 let runtime_clone = Arc::clone(runtime);
                 let render_handle_clone = render_handle.clone();
@@ -284,6 +350,7 @@ impl CmdExecutor {
                     for cmd in cmds {
                         let rt = Arc::clone(&runtime_clone);
                         let rh = render_handle_clone.clone();
+                        let on_msg = on_msg.clone();
 
                         let handle = tokio::spawn(async move {
                             let temp_executor = CmdExecutor {
@@ -291,7 +358,7 @@ impl CmdExecutor {
                                 render_handle: rh,
                             };
                             let (tx, rx) = tokio::sync::oneshot::channel();
-                            temp_executor.execute_with_completion(cmd, tx);
+                            temp_executor.execute_with_completion(cmd, on_msg, tx);
                             std::mem::forget(temp_executor);
                             let _ = rx.await;
                         });
@@ -307,7 +374,7 @@ impl CmdExecutor {
                 });
             }
 
-            Cmd::Sequence(cmds) => {
+            TypedCmd::Sequence(cmds) => {
                 let runtime_clone = Arc::clone(runtime);
                 let render_handle_clone = render_handle.clone();
 
@@ -318,7 +385,7 @@ impl CmdExecutor {
                             render_handle: render_handle_clone.clone(),
                         };
                         let (tx, rx) = tokio::sync::oneshot::channel();
-                        temp_executor.execute_with_completion(cmd, tx);
+                        temp_executor.execute_with_completion(cmd, on_msg.clone(), tx);
                         std::mem::forget(temp_executor);
                         let _ = rx.await;
                     }
@@ -326,14 +393,14 @@ impl CmdExecutor {
                 });
             }
 
-            Cmd::Perform { future } => {
+            TypedCmd::Perform { future } => {
                 runtime.spawn(async move {
-                    future.await;
+                    on_msg(future.await);
                     let _ = completion.send(());
                 });
             }
 
-            Cmd::Sleep { duration, then } => {
+            TypedCmd::Sleep { duration, then } => {
                 let runtime_clone = Arc::clone(runtime);
                 let render_handle_clone = render_handle.clone();
 
@@ -341,7 +408,7 @@ impl CmdExecutor {
                     tokio::time::sleep(duration).await;
 
                     match *then {
-                        Cmd::None => {
+                        TypedCmd::None => {
                             let _ = completion.send(());
                         }
                         other => {
@@ -350,7 +417,7 @@ impl CmdExecutor {
                                 render_handle: render_handle_clone,
                             };
                             let (tx, rx) = tokio::sync::oneshot::channel();
-                            temp_executor.execute_with_completion(other, tx);
+                            temp_executor.execute_with_completion(other, on_msg, tx);
                             std::mem::forget(temp_executor);
                             let _ = rx.await;
                             let _ = completion.send(());
@@ -359,16 +426,16 @@ impl CmdExecutor {
                 });
             }
 
-            Cmd::Tick { duration, callback } => {
+            TypedCmd::Tick { duration, callback } => {
                 runtime.spawn(async move {
                     tokio::time::sleep(duration).await;
                     let timestamp = Instant::now();
-                    callback(timestamp);
+                    on_msg(callback(timestamp));
                     let _ = completion.send(());
                 });
             }
 
-            Cmd::Every { duration, callback } => {
+            TypedCmd::Every { duration, callback } => {
                 runtime.spawn(async move {
                     let now = std::time::SystemTime::now();
                     let since_epoch = now
@@ -388,7 +455,7 @@ impl CmdExecutor {
                     tokio::time::sleep(wait_duration).await;
 
                     let timestamp = Instant::now();
-                    callback(timestamp);
+                    on_msg(callback(timestamp));
                     let _ = completion.send(());
                 });
             }
@@ -441,6 +508,29 @@ mod tests {
     use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
     use std::time::Duration;
 
+    #[tokio::test]
+    async fn test_execute_with_delivers_messages() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let executor = CmdExecutor::new(tx);
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+
+        executor.execute_with(
+            TypedCmd::sequence(vec![
+                TypedCmd::perform(|| async { 1 }),
+                TypedCmd::tick(Duration::from_millis(10), |_| 2),
+                TypedCmd::sleep(Duration::from_millis(10))
+                    .and_then(TypedCmd::perform(|| async { 3 })),
+            ]),
+            move |msg| sink.lock().unwrap().push(msg),
+        );
+
+        tokio::time::timeout(Duration::from_secs(1), rx.recv())
+            .await
+            .expect("render request");
+        assert_eq!(*received.lock().unwrap(), vec![1, 2, 3]);
+    }
+
     #[tokio::test]
     async fn test_executor_creation() {
         let (tx, _rx) = mpsc::unbounded_channel();
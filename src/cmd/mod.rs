@@ -14,6 +14,9 @@
 //! - [`Cmd::tick()`] - Execute callback after a duration
 //! - [`Cmd::every()`] - Execute callback aligned to system clock
 //!
+//! [`TypedCmd<M>`] has the same constructors, but its tasks resolve to
+//! messages of type `M`. It is used by [`program`](crate::program).
+//!
 //! # Example
 //!
 //! ```rust
@@ -52,28 +55,37 @@ pub use tasks::{HttpRequest, HttpResponse, ProcessOutput};
 
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// A command represents a side effect to be executed.
 ///
 /// Commands are descriptions of side effects, not the execution itself.
 /// This allows for better control, composition, and testing.
+pub type Cmd = TypedCmd<()>;
+
+/// A command whose tasks resolve to messages of type `M`.
+///
+/// [`Cmd`] is the message-less form used by components and hooks. Programs
+/// built with [`program`](crate::program) return `TypedCmd<Msg>` from
+/// `init` and `update`, and each value the command produces is fed back
+/// into `update`.
 #[derive(Default)]
-pub enum Cmd {
+pub enum TypedCmd<M> {
     /// No-op command that does nothing
     #[default]
     None,
 
     /// Execute multiple commands concurrently (no ordering guarantees)
-    Batch(Vec<Cmd>),
+    Batch(Vec<TypedCmd<M>>),
 
     /// Execute multiple commands sequentially (in order)
-    Sequence(Vec<Cmd>),
+    Sequence(Vec<TypedCmd<M>>),
 
     /// Execute an async task
     Perform {
         /// The future to execute
-        future: Pin<Box<dyn Future<Output = ()> + Send + 'static>>,
+        future: Pin<Box<dyn Future<Output = M> + Send + 'static>>,
     },
 
     /// Sleep for a duration, then execute another command
@@ -81,7 +93,7 @@ pub enum Cmd {
         /// Duration to sleep
         duration: Duration,
         /// Command to execute after sleeping
-        then: Box<Cmd>,
+        then: Box<TypedCmd<M>>,
     },
 
     /// Timer tick - executes callback after duration with timestamp
@@ -89,7 +101,7 @@ pub enum Cmd {
         /// Duration to wait
         duration: Duration,
         /// Callback that receives the tick timestamp
-        callback: Box<dyn FnOnce(Instant) + Send + 'static>,
+        callback: Box<dyn FnOnce(Instant) -> M + Send + 'static>,
     },
 
     /// System clock aligned tick - executes callback aligned to clock boundaries
@@ -97,11 +109,11 @@ pub enum Cmd {
         /// Duration interval (aligned to system clock)
         duration: Duration,
         /// Callback that receives the tick timestamp
-        callback: Box<dyn FnOnce(Instant) + Send + 'static>,
+        callback: Box<dyn FnOnce(Instant) -> M + Send + 'static>,
     },
 }
 
-impl Cmd {
+impl<M: 'static> TypedCmd<M> {
     /// Create a no-op command
     ///
     /// # Example
@@ -112,7 +124,7 @@ impl Cmd {
     /// let cmd = Cmd::none();
     /// ```
     pub fn none() -> Self {
-        Cmd::None
+        TypedCmd::None
     }
 
     /// Create a batch command that executes multiple commands in parallel
@@ -129,16 +141,16 @@ impl Cmd {
     ///     Cmd::none(),
     /// ]);
     /// ```
-    pub fn batch(cmds: impl IntoIterator<Item = Cmd>) -> Self {
-        let mut cmds: Vec<Cmd> = cmds
+    pub fn batch(cmds: impl IntoIterator<Item = TypedCmd<M>>) -> Self {
+        let mut cmds: Vec<TypedCmd<M>> = cmds
             .into_iter()
-            .filter(|cmd| !matches!(cmd, Cmd::None))
+            .filter(|cmd| !matches!(cmd, TypedCmd::None))
             .collect();
 
         match cmds.len() {
-            0 => Cmd::None,
+            0 => TypedCmd::None,
             1 => cmds.pop().unwrap(),
-            _ => Cmd::Batch(cmds),
+            _ => TypedCmd::Batch(cmds),
         }
     }
 
@@ -167,16 +179,16 @@ impl Cmd {
     ///     }),
     /// ]);
     /// ```
-    pub fn sequence(cmds: impl IntoIterator<Item = Cmd>) -> Self {
-        let mut cmds: Vec<Cmd> = cmds
+    pub fn sequence(cmds: impl IntoIterator<Item = TypedCmd<M>>) -> Self {
+        let mut cmds: Vec<TypedCmd<M>> = cmds
             .into_iter()
-            .filter(|cmd| !matches!(cmd, Cmd::None))
+            .filter(|cmd| !matches!(cmd, TypedCmd::None))
             .collect();
 
         match cmds.len() {
-            0 => Cmd::None,
+            0 => TypedCmd::None,
             1 => cmds.pop().unwrap(),
-            _ => Cmd::Sequence(cmds),
+            _ => TypedCmd::Sequence(cmds),
         }
     }
 
@@ -187,16 +199,16 @@ impl Cmd {
     /// ```rust
     /// use rnk::cmd::Cmd;
     ///
-    /// let cmd = Cmd::perform(async {
+    /// let cmd = Cmd::perform(|| async {
     ///     println!("Hello from async!");
     /// });
     /// ```
     pub fn perform<F, Fut>(f: F) -> Self
     where
         F: FnOnce() -> Fut + Send + 'static,
-        Fut: Future<Output = ()> + Send + 'static,
+        Fut: Future<Output = M> + Send + 'static,
     {
-        Cmd::Perform {
+        TypedCmd::Perform {
             future: Box::pin(async move { f().await }),
         }
     }
@@ -212,9 +224,9 @@ impl Cmd {
     /// let cmd = Cmd::sleep(Duration::from_secs(1));
     /// ```
     pub fn sleep(duration: Duration) -> Self {
-        Cmd::Sleep {
+        TypedCmd::Sleep {
             duration,
-            then: Box::new(Cmd::None),
+            then: Box::new(TypedCmd::None),
         }
     }
 
@@ -243,9 +255,9 @@ impl Cmd {
     /// ```
     pub fn tick<F>(duration: Duration, callback: F) -> Self
     where
-        F: FnOnce(Instant) + Send + 'static,
+        F: FnOnce(Instant) -> M + Send + 'static,
     {
-        Cmd::Tick {
+        TypedCmd::Tick {
             duration,
             callback: Box::new(callback),
         }
@@ -283,9 +295,9 @@ impl Cmd {
     /// ```
     pub fn every<F>(duration: Duration, callback: F) -> Self
     where
-        F: FnOnce(Instant) + Send + 'static,
+        F: FnOnce(Instant) -> M + Send + 'static,
     {
-        Cmd::Every {
+        TypedCmd::Every {
             duration,
             callback: Box::new(callback),
         }
@@ -302,21 +314,21 @@ impl Cmd {
     /// use std::time::Duration;
     ///
     /// let cmd = Cmd::sleep(Duration::from_secs(1))
-    ///     .and_then(Cmd::perform(async {
+    ///     .and_then(Cmd::perform(|| async {
     ///         println!("After 1 second");
     ///     }));
     /// ```
-    pub fn and_then(self, next: Cmd) -> Self {
+    pub fn and_then(self, next: TypedCmd<M>) -> Self {
         match self {
-            Cmd::None => next,
-            Cmd::Sleep { duration, then } => {
+            TypedCmd::None => next,
+            TypedCmd::Sleep { duration, then } => {
                 let chained = then.and_then(next);
-                Cmd::Sleep {
+                TypedCmd::Sleep {
                     duration,
                     then: Box::new(chained),
                 }
             }
-            other => Cmd::batch(vec![other, next]),
+            other => TypedCmd::batch(vec![other, next]),
         }
     }
 
@@ -328,10 +340,10 @@ impl Cmd {
     /// use rnk::cmd::Cmd;
     ///
     /// assert!(Cmd::none().is_none());
-    /// assert!(!Cmd::perform(async {}).is_none());
+    /// assert!(!Cmd::perform(|| async {}).is_none());
     /// ```
     pub fn is_none(&self) -> bool {
-        matches!(self, Cmd::None)
+        matches!(self, TypedCmd::None)
     }
 
     /// Map over a command, transforming it
@@ -345,9 +357,9 @@ impl Cmd {
     ///
     /// let cmd = Cmd::none().map(|c| {
     ///     Cmd::batch(vec![
-    ///         Cmd::perform(async { println!("Before"); }),
+    ///         Cmd::perform(|| async { println!("Before"); }),
     ///         c,
-    ///         Cmd::perform(async { println!("After"); }),
+    ///         Cmd::perform(|| async { println!("After"); }),
     ///     ])
     /// });
     /// ```
@@ -357,26 +369,54 @@ impl Cmd {
     {
         f(self)
     }
+
+    /// Convert every message this command produces with `f`
+    pub(crate) fn map_msg<N: 'static>(self, f: Arc<dyn Fn(M) -> N + Send + Sync>) -> TypedCmd<N> {
+        match self {
+            TypedCmd::None => TypedCmd::None,
+            TypedCmd::Batch(cmds) => {
+                TypedCmd::Batch(cmds.into_iter().map(|cmd| cmd.map_msg(f.clone())).collect())
+            }
+            TypedCmd::Sequence(cmds) => {
+                TypedCmd::Sequence(cmds.into_iter().map(|cmd| cmd.map_msg(f.clone())).collect())
+            }
+            TypedCmd::Perform { future } => TypedCmd::Perform {
+                future: Box::pin(async move { f(future.await) }),
+            },
+            TypedCmd::Sleep { duration, then } => TypedCmd::Sleep {
+                duration,
+                then: Box::new(then.map_msg(f)),
+            },
+            TypedCmd::Tick { duration, callback } => TypedCmd::Tick {
+                duration,
+                callback: Box::new(move |at| f(callback(at))),
+            },
+            TypedCmd::Every { duration, callback } => TypedCmd::Every {
+                duration,
+                callback: Box::new(move |at| f(callback(at))),
+            },
+        }
+    }
 }
 
-impl std::fmt::Debug for Cmd {
+impl<M> std::fmt::Debug for TypedCmd<M> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Cmd::None => write!(f, "Cmd::None"),
-            Cmd::Batch(cmds) => f.debug_tuple("Cmd::Batch").field(cmds).finish(),
-            Cmd::Sequence(cmds) => f.debug_tuple("Cmd::Sequence").field(cmds).finish(),
-            Cmd::Perform { .. } => write!(f, "Cmd::Perform {{ ... }}"),
-            Cmd::Sleep { duration, then } => f
-                .debug_struct("Cmd::Sleep")
+            TypedCmd::None => write!(f, "TypedCmd::None"),
+            TypedCmd::Batch(cmds) => f.debug_tuple("TypedCmd::Batch").field(cmds).finish(),
+            TypedCmd::Sequence(cmds) => f.debug_tuple("TypedCmd::Sequence").field(cmds).finish(),
+            TypedCmd::Perform { .. } => write!(f, "TypedCmd::Perform {{ ... }}"),
+            TypedCmd::Sleep { duration, then } => f
+                .debug_struct("TypedCmd::Sleep")
                 .field("duration", duration)
                 .field("then", then)
                 .finish(),
-            Cmd::Tick { duration, .. } => f
-                .debug_struct("Cmd::Tick")
+            TypedCmd::Tick { duration, .. } => f
+                .debug_struct("TypedCmd::Tick")
                 .field("duration", duration)
                 .finish(),
-            Cmd::Every { duration, .. } => f
-                .debug_struct("Cmd::Every")
+            TypedCmd::Every { duration, .. } => f
+                .debug_struct("TypedCmd::Every")
                 .field("duration", duration)
                 .finish(),
         }
@@ -541,19 +581,19 @@ mod tests {
     fn test_cmd_debug() {
         let cmd = Cmd::none();
         let debug_str = format!("{:?}", cmd);
-        assert_eq!(debug_str, "Cmd::None");
+        assert_eq!(debug_str, "TypedCmd::None");
 
         let cmd = Cmd::batch(vec![Cmd::none(), Cmd::none()]);
         let debug_str = format!("{:?}", cmd);
-        assert_eq!(debug_str, "Cmd::None");
+        assert_eq!(debug_str, "TypedCmd::None");
 
         let cmd = Cmd::sleep(Duration::from_secs(1));
         let debug_str = format!("{:?}", cmd);
-        assert!(debug_str.contains("Cmd::Sleep"));
+        assert!(debug_str.contains("TypedCmd::Sleep"));
 
         let cmd = Cmd::perform(|| async {});
         let debug_str = format!("{:?}", cmd);
-        assert!(debug_str.contains("Cmd::Perform"));
+        assert!(debug_str.contains("TypedCmd::Perform"));
     }
 
     #[test]
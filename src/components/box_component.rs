@@ -1,9 +1,11 @@
-//! Box component - Flexbox container
+//! Box component - Flexbox and grid container
 
 use crate::core::{
     AlignItems, AlignSelf, BorderStyle, Color, Dimension, Display, Edges, Element, ElementType,
-    FlexDirection, JustifyContent, Overflow, Position, Style,
+    FlexDirection, GridLine, GridTemplate, JustifyContent, Overflow, Position, Style,
+    TextDirection,
 };
+use crate::hooks::ElementRef;
 
 /// Box component builder
 #[derive(Debug, Clone, Default)]
@@ -13,6 +15,7 @@ pub struct Box {
     key: Option<String>,
     scroll_offset_x: Option<u16>,
     scroll_offset_y: Option<u16>,
+    element_ref: Option<ElementRef>,
 }
 
 impl Box {
@@ -24,6 +27,7 @@ impl Box {
             key: None,
             scroll_offset_x: None,
             scroll_offset_y: None,
+            element_ref: None,
         }
     }
 
@@ -33,6 +37,15 @@ impl Box {
         self
     }
 
+    /// Attach an element ref
+    ///
+    /// After each render the ref reports this box's layout, scroll offset
+    /// and focus state.
+    pub fn ref_(mut self, element_ref: &ElementRef) -> Self {
+        self.element_ref = Some(element_ref.clone());
+        self
+    }
+
     // === Display ===
 
     /// Set display type
@@ -110,6 +123,44 @@ impl Box {
         self
     }
 
+    // === Grid ===
+
+    /// Lay out children in a grid (`display: grid`)
+    pub fn grid(mut self) -> Self {
+        self.style.display = Display::Grid;
+        self
+    }
+
+    /// Set the grid's column tracks
+    pub fn grid_template_columns(
+        mut self,
+        tracks: impl IntoIterator<Item = impl Into<GridTemplate>>,
+    ) -> Self {
+        self.style.grid_template_columns = tracks.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the grid's row tracks
+    pub fn grid_template_rows(
+        mut self,
+        tracks: impl IntoIterator<Item = impl Into<GridTemplate>>,
+    ) -> Self {
+        self.style.grid_template_rows = tracks.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Place this box in the columns of its parent grid
+    pub fn grid_column(mut self, line: GridLine) -> Self {
+        self.style.grid_column = line;
+        self
+    }
+
+    /// Place this box in the rows of its parent grid
+    pub fn grid_row(mut self, line: GridLine) -> Self {
+        self.style.grid_row = line;
+        self
+    }
+
     // === Spacing ===
 
     /// Set padding (all sides)
@@ -256,6 +307,25 @@ impl Box {
         self
     }
 
+    /// Set aspect ratio (width / height, in cells)
+    ///
+    /// Terminal cells are roughly twice as tall as they are wide, so a
+    /// visually square box has a ratio of about 2.0.
+    pub fn aspect_ratio(mut self, ratio: f32) -> Self {
+        self.style.aspect_ratio = Some(ratio);
+        self
+    }
+
+    /// Set the base direction of text inside this box
+    ///
+    /// Text in right-to-left scripts is reordered for display either way;
+    /// the direction decides the order of mixed runs and aligns wrapped rows
+    /// to the right. Nested boxes can override it.
+    pub fn direction(mut self, direction: TextDirection) -> Self {
+        self.style.direction = Some(direction);
+        self
+    }
+
     // === Border ===
 
     /// Set border style
@@ -402,6 +472,16 @@ impl Box {
         self
     }
 
+    /// Set the z-index
+    ///
+    /// A nonzero z-index makes the element a stacking context: it paints
+    /// above or below the other contexts inside its parent's context,
+    /// together with its children. 0 paints with the parent.
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.style.z_index = z_index;
+        self
+    }
+
     // === Children ===
 
     /// Add a child element
@@ -423,9 +503,13 @@ impl Box {
         element.key = self.key;
         element.scroll_offset_x = self.scroll_offset_x;
         element.scroll_offset_y = self.scroll_offset_y;
+        element.element_ref = self.element_ref.as_ref().map(ElementRef::id);
         for child in self.children {
             element.add_child(child);
         }
+        if let Some(direction) = element.style.direction {
+            element.inherit_direction(direction);
+        }
         element
     }
 }
@@ -465,4 +549,66 @@ mod tests {
         assert_eq!(element.style.border_style, BorderStyle::Round);
         assert_eq!(element.style.border_color, Some(Color::Cyan));
     }
+
+    #[test]
+    fn test_box_grid() {
+        use crate::core::{GridTrack, TrackSize};
+        use crate::layout::LayoutEngine;
+
+        let cell = |text: &str| Box::new().child(Element::text(text));
+        let mut element = Box::new()
+            .grid()
+            .width(30)
+            .grid_template_columns([
+                GridTemplate::from(GridTrack::points(10.0)),
+                GridTemplate::repeat(2, [TrackSize::Fr(1.0)]),
+            ])
+            .grid_template_rows([GridTrack::auto(), GridTrack::auto()])
+            .child(cell("a").grid_column(GridLine::span(3)).into_element())
+            .child(cell("b").into_element())
+            .child(cell("c").grid_column(GridLine::line(3)).into_element())
+            .into_element();
+
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(&mut element, 30, 10);
+        let layout = |i: usize| {
+            engine
+                .get_layout(element.children.get(i).unwrap().id)
+                .unwrap()
+        };
+
+        assert_eq!(
+            (layout(0).x, layout(0).y, layout(0).width),
+            (0.0, 0.0, 30.0)
+        );
+        assert_eq!(
+            (layout(1).x, layout(1).y, layout(1).width),
+            (0.0, 1.0, 10.0)
+        );
+        assert_eq!(
+            (layout(2).x, layout(2).y, layout(2).width),
+            (20.0, 1.0, 10.0)
+        );
+    }
+
+    #[test]
+    fn test_box_direction_inherited() {
+        use crate::components::Text;
+
+        let element = Box::new()
+            .direction(TextDirection::Rtl)
+            .child(Text::new("a").into_element())
+            .child(
+                Box::new()
+                    .direction(TextDirection::Ltr)
+                    .child(Text::new("b").into_element())
+                    .into_element(),
+            )
+            .into_element();
+
+        let direction = |e: Option<&Element>| e.unwrap().style.direction;
+        assert_eq!(direction(element.children.get(0)), Some(TextDirection::Rtl));
+        let nested = element.children.get(1).unwrap();
+        assert_eq!(direction(nested.children.get(0)), Some(TextDirection::Ltr));
+    }
 }
@@ -0,0 +1,261 @@
+//! Function components with their own hook state
+//!
+//! A [`Component`] pairs a render function with its props. When rendered
+//! inside another component it is mounted as a child instance with its own
+//! [`HookContext`], so hooks called inside it never share an index sequence
+//! with the parent or with sibling components.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[derive(Clone)]
+//! struct CounterProps {
+//!     label: String,
+//! }
+//!
+//! fn counter(props: &CounterProps) -> Element {
+//!     let count = use_signal(|| 0);
+//!     Text::new(format!("{}: {}", props.label, count.get())).into_element()
+//! }
+//!
+//! fn app() -> Element {
+//!     let show_first = use_signal(|| true);
+//!
+//!     let mut root = Box::new().flex_direction(FlexDirection::Column);
+//!     if show_first.get() {
+//!         root = root.child(
+//!             Component::new(counter, CounterProps { label: "First".into() })
+//!                 .key("first")
+//!                 .into_element(),
+//!         );
+//!     }
+//!     root.child(
+//!         Component::new(counter, CounterProps { label: "Second".into() })
+//!             .key("second")
+//!             .into_element(),
+//!     )
+//!     .into_element()
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::core::Element;
+use crate::hooks::context::{ComponentId, HookContext, current_context, with_hooks};
+
+/// A function component: a render function plus its props
+///
+/// Each mounted instance is identified by the type of its render function
+/// and its key (or, without a key, its position among siblings rendered by
+/// the same function). When an instance is not rendered during a parent
+/// render, it is unmounted and all of its effect cleanups run.
+pub struct Component<P, F>
+where
+    P: 'static,
+    F: Fn(&P) -> Element + 'static,
+{
+    render: F,
+    props: P,
+    key: Option<String>,
+}
+
+impl<P, F> Component<P, F>
+where
+    P: 'static,
+    F: Fn(&P) -> Element + 'static,
+{
+    /// Create a new component from a render function and props
+    pub fn new(render: F, props: P) -> Self {
+        Self {
+            render,
+            props,
+            key: None,
+        }
+    }
+
+    /// Set key for reconciliation
+    ///
+    /// Keys keep hook state attached to the right instance when siblings
+    /// rendered by the same function are reordered, inserted or removed.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Render the component and convert it to an Element
+    ///
+    /// Inside a render cycle the component is mounted as a child of the
+    /// current component. Outside of one (e.g. in `render_to_string`), it is
+    /// rendered once with a temporary hook context that is unmounted again
+    /// immediately.
+    pub fn into_element(self) -> Element {
+        let id = ComponentId::of_render(&self.render);
+
+        let mut element = match current_context() {
+            Some(parent) => {
+                let child = parent.borrow_mut().child_context(id, self.key.as_deref());
+                let element = with_hooks(child.clone(), || (self.render)(&self.props));
+
+                // Commands queued by the child run with the rest of the tree
+                let cmds = child.borrow_mut().take_cmds();
+                let mut parent = parent.borrow_mut();
+                for cmd in cmds {
+                    parent.queue_cmd(cmd);
+                }
+                element
+            }
+            None => {
+                let ctx = Rc::new(RefCell::new(HookContext::new()));
+                let element = with_hooks(ctx.clone(), || (self.render)(&self.props));
+                ctx.borrow_mut().unmount();
+                element
+            }
+        };
+
+        if element.key.is_none() {
+            element.key = self.key;
+        }
+        element
+    }
+}
+
+/// Convenience function for creating a component
+///
+/// Equivalent to `Component::new(render, props)`.
+pub fn component<P, F>(render: F, props: P) -> Component<P, F>
+where
+    P: 'static,
+    F: Fn(&P) -> Element + 'static,
+{
+    Component::new(render, props)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Text;
+    use crate::hooks::{use_effect_once, use_signal};
+
+    fn counter(label: &&'static str) -> Element {
+        let count = use_signal(|| 0i32);
+        count.set_silent(count.get() + 1);
+        Text::new(format!("{}:{}", label, count.get())).into_element()
+    }
+
+    fn other(_: &()) -> Element {
+        let name = use_signal(|| "other".to_string());
+        Text::new(name.get()).into_element()
+    }
+
+    #[test]
+    fn test_components_have_separate_state() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+
+        let render = |show_other: bool| {
+            with_hooks(root.clone(), || {
+                let mut texts = Vec::new();
+                if show_other {
+                    texts.push(Component::new(other, ()).into_element());
+                }
+                texts.push(Component::new(counter, "a").into_element());
+                texts.push(Component::new(counter, "b").into_element());
+                texts
+                    .iter()
+                    .map(|e| e.get_text().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(render(true), vec!["other", "a:1", "b:1"]);
+        // Removing a component of another type must not disturb the counters
+        assert_eq!(render(false), vec!["a:2", "b:2"]);
+        assert_eq!(render(true), vec!["other", "a:3", "b:3"]);
+    }
+
+    #[test]
+    fn test_keyed_component_state_follows_key() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+
+        let render = |keys: &[&'static str]| {
+            with_hooks(root.clone(), || {
+                keys.iter()
+                    .map(|k| {
+                        Component::new(counter, *k)
+                            .key(*k)
+                            .into_element()
+                            .get_text()
+                            .unwrap()
+                            .to_string()
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(render(&["x", "y"]), vec!["x:1", "y:1"]);
+        assert_eq!(render(&["y", "x"]), vec!["y:2", "x:2"]);
+        assert_eq!(render(&["z", "x"]), vec!["z:1", "x:3"]);
+    }
+
+    #[test]
+    fn test_unmount_runs_effect_cleanup() {
+        fn tracked(flag: &Rc<RefCell<Vec<&'static str>>>) -> Element {
+            let flag = flag.clone();
+            use_effect_once(move || {
+                flag.borrow_mut().push("mount");
+                Some(Box::new(move || flag.borrow_mut().push("unmount")))
+            });
+            Text::new("tracked").into_element()
+        }
+
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        for show in [true, true, false] {
+            let log = log.clone();
+            with_hooks(root.clone(), move || {
+                if show {
+                    Component::new(tracked, log).into_element();
+                }
+            });
+        }
+
+        assert_eq!(*log.borrow(), vec!["mount", "unmount"]);
+        assert_eq!(root.borrow().child_count(), 0);
+    }
+
+    #[test]
+    fn test_fn_pointer_components_are_distinct() {
+        fn first(_: &()) -> Element {
+            let name = use_signal(|| "first");
+            Text::new(name.get()).into_element()
+        }
+        fn second(_: &()) -> Element {
+            let name = use_signal(|| "second");
+            Text::new(name.get()).into_element()
+        }
+
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let render = |render: fn(&()) -> Element| {
+            with_hooks(root.clone(), || {
+                Component::new(render, ())
+                    .into_element()
+                    .get_text()
+                    .unwrap()
+                    .to_string()
+            })
+        };
+
+        assert_eq!(render(first), "first");
+        // Swapping in another fn pointer mounts a fresh instance
+        assert_eq!(render(second), "second");
+        assert_eq!(root.borrow().child_count(), 1);
+    }
+
+    #[test]
+    fn test_component_outside_render() {
+        let element = component(counter, "solo").key("k").into_element();
+        assert_eq!(element.get_text(), Some("solo:1"));
+        assert_eq!(element.key.as_deref(), Some("k"));
+    }
+}
@@ -0,0 +1,378 @@
+//! Error boundaries that contain failures in a subtree
+//!
+//! An [`ErrorBoundary`] renders its children and, if they panic or return an
+//! error, renders a fallback element in their place instead of taking the
+//! whole app down. The boundary keeps showing the fallback until it is reset.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn app() -> Element {
+//!     Box::new()
+//!         .child(
+//!             ErrorBoundary::new(|| Component::new(metrics_panel, ()).into_element())
+//!                 .fallback(|error, reset| {
+//!                     let reset = reset.clone();
+//!                     use_input(move |_, key| {
+//!                         if key.return_key {
+//!                             reset.reset();
+//!                         }
+//!                     });
+//!                     Text::new(format!("Panel crashed: {} (Enter to retry)", error))
+//!                         .color(Color::Red)
+//!                         .into_element()
+//!                 })
+//!                 .into_element(),
+//!         )
+//!         .into_element()
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::components::Text;
+use crate::core::{Color, Element};
+use crate::hooks::context::{ComponentId, HookContext, current_context, with_hooks};
+use crate::hooks::{Signal, use_signal};
+use crate::runtime::catch_panic;
+
+/// Error caught by an [`ErrorBoundary`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenderError {
+    message: String,
+    panicked: bool,
+}
+
+impl RenderError {
+    /// Error message (the panic message for panics)
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Whether the error was a panic rather than a returned `Err`
+    pub fn is_panic(&self) -> bool {
+        self.panicked
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+/// Handle for resetting an [`ErrorBoundary`]
+///
+/// Resetting clears the caught error and schedules a re-render, which tries
+/// the children again with fresh hook state.
+#[derive(Clone)]
+pub struct BoundaryReset {
+    error: Signal<Option<RenderError>>,
+}
+
+impl BoundaryReset {
+    /// Clear the error and render the children again
+    pub fn reset(&self) {
+        self.error.set(None);
+    }
+}
+
+type Children = Box<dyn FnOnce() -> Result<Element, String>>;
+type Fallback = Box<dyn FnOnce(&RenderError, &BoundaryReset) -> Element>;
+type ErrorCallback = Box<dyn FnOnce(&RenderError)>;
+
+/// Marker type identifying the children's hook context
+struct BoundaryContent;
+
+/// Element that contains panics and errors raised while rendering a subtree
+///
+/// The children are rendered with their own hook state. When they fail,
+/// that state is discarded (running effect cleanups) and the fallback is
+/// rendered until [`BoundaryReset::reset`] is called.
+pub struct ErrorBoundary {
+    children: Children,
+    fallback: Option<Fallback>,
+    on_error: Option<ErrorCallback>,
+    key: Option<String>,
+}
+
+impl ErrorBoundary {
+    /// Create a boundary around children that may panic
+    pub fn new<F>(children: F) -> Self
+    where
+        F: FnOnce() -> Element + 'static,
+    {
+        Self::from_children(Box::new(move || Ok(children())))
+    }
+
+    /// Create a boundary around children that may panic or return an error
+    pub fn try_new<F, E>(children: F) -> Self
+    where
+        F: FnOnce() -> Result<Element, E> + 'static,
+        E: fmt::Display,
+    {
+        Self::from_children(Box::new(move || children().map_err(|e| e.to_string())))
+    }
+
+    fn from_children(children: Children) -> Self {
+        Self {
+            children,
+            fallback: None,
+            on_error: None,
+            key: None,
+        }
+    }
+
+    /// Set the element rendered while the boundary holds an error
+    ///
+    /// Without a fallback, the error message is shown in red.
+    pub fn fallback<F>(mut self, fallback: F) -> Self
+    where
+        F: FnOnce(&RenderError, &BoundaryReset) -> Element + 'static,
+    {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+
+    /// Set a callback invoked once when an error is caught
+    pub fn on_error<F>(mut self, on_error: F) -> Self
+    where
+        F: FnOnce(&RenderError) + 'static,
+    {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Set key for reconciliation
+    ///
+    /// Keys keep each boundary's error state attached to the right instance
+    /// when sibling boundaries are reordered, inserted or removed.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Render the boundary and convert it to an Element
+    pub fn into_element(self) -> Element {
+        let key = self.key.clone();
+
+        let mut element = match current_context() {
+            Some(parent) => {
+                let ctx = parent
+                    .borrow_mut()
+                    .child_context(ComponentId::of::<ErrorBoundary>(), key.as_deref());
+                let element = with_hooks(ctx.clone(), || self.render());
+                forward_cmds(&ctx, &parent);
+                element
+            }
+            None => {
+                let ctx = Rc::new(RefCell::new(HookContext::new()));
+                let element = with_hooks(ctx.clone(), || self.render());
+                ctx.borrow_mut().unmount();
+                element
+            }
+        };
+
+        if element.key.is_none() {
+            element.key = key;
+        }
+        element
+    }
+
+    /// Render inside the boundary's own hook context
+    fn render(self) -> Element {
+        let error = use_signal(|| None::<RenderError>);
+        let reset = BoundaryReset {
+            error: error.clone(),
+        };
+
+        if let Some(caught) = error.get() {
+            return render_fallback(self.fallback, &caught, &reset);
+        }
+
+        let ctx = current_context().expect("boundary renders inside its own context");
+        let content = ctx
+            .borrow_mut()
+            .child_context(ComponentId::of::<BoundaryContent>(), None);
+
+        let children = self.children;
+        let result = catch_panic(|| with_hooks(content.clone(), children));
+        forward_cmds(&content, &ctx);
+
+        let caught = match result {
+            Ok(Ok(element)) => return element,
+            Ok(Err(message)) => RenderError {
+                message,
+                panicked: false,
+            },
+            Err(message) => RenderError {
+                message,
+                panicked: true,
+            },
+        };
+
+        // Drop the failed subtree's state so a reset starts from scratch
+        content.borrow_mut().unmount();
+
+        log::error!("ErrorBoundary caught: {}", caught);
+        if let Some(on_error) = self.on_error {
+            on_error(&caught);
+        }
+
+        error.set_silent(Some(caught.clone()));
+        render_fallback(self.fallback, &caught, &reset)
+    }
+}
+
+fn render_fallback(
+    fallback: Option<Fallback>,
+    error: &RenderError,
+    reset: &BoundaryReset,
+) -> Element {
+    match fallback {
+        Some(fallback) => fallback(error, reset),
+        None => Text::new(format!("Error: {}", error))
+            .color(Color::Red)
+            .into_element(),
+    }
+}
+
+/// Move commands queued by a child context to its parent
+fn forward_cmds(child: &Rc<RefCell<HookContext>>, parent: &Rc<RefCell<HookContext>>) {
+    let cmds = child.borrow_mut().take_cmds();
+    let mut parent = parent.borrow_mut();
+    for cmd in cmds {
+        parent.queue_cmd(cmd);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Box;
+    use crate::hooks::use_effect_once;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_panic_renders_fallback() {
+        let element = ErrorBoundary::new(|| panic!("broken panel")).into_element();
+        let text = element.get_text().unwrap();
+        assert!(text.starts_with("Error: broken panel"));
+    }
+
+    #[test]
+    fn test_result_error_renders_fallback() {
+        let caught = Rc::new(RefCell::new(None));
+        let on_error = caught.clone();
+
+        let element = ErrorBoundary::try_new(|| Err::<Element, _>("no data"))
+            .fallback(|error, _| Text::new(format!("fallback: {}", error)).into_element())
+            .on_error(move |error| *on_error.borrow_mut() = Some(error.clone()))
+            .into_element();
+
+        assert_eq!(element.get_text(), Some("fallback: no data"));
+        let caught = caught.borrow().clone().unwrap();
+        assert!(!caught.is_panic());
+        assert_eq!(caught.message(), "no data");
+    }
+
+    #[test]
+    fn test_success_renders_children() {
+        let element = ErrorBoundary::new(|| Text::new("ok").into_element())
+            .key("panel")
+            .into_element();
+        assert_eq!(element.get_text(), Some("ok"));
+        assert_eq!(element.key.as_deref(), Some("panel"));
+    }
+
+    #[test]
+    fn test_error_persists_until_reset() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let failing = Rc::new(Cell::new(true));
+        let attempts = Rc::new(Cell::new(0));
+        let reset_handle: Rc<RefCell<Option<BoundaryReset>>> = Rc::new(RefCell::new(None));
+
+        let render = || {
+            let failing = failing.clone();
+            let attempts = attempts.clone();
+            let reset_handle = reset_handle.clone();
+            with_hooks(root.clone(), move || {
+                let before = use_signal(|| "before");
+                let element = Box::new()
+                    .child(Text::new(before.get()).into_element())
+                    .child(
+                        ErrorBoundary::new(move || {
+                            attempts.set(attempts.get() + 1);
+                            if failing.get() {
+                                panic!("render failed");
+                            }
+                            Text::new("recovered").into_element()
+                        })
+                        .fallback(move |_, reset| {
+                            *reset_handle.borrow_mut() = Some(reset.clone());
+                            Text::new("fallback").into_element()
+                        })
+                        .into_element(),
+                    )
+                    .into_element();
+                // Hooks after the boundary keep their slots
+                let after = use_signal(|| "after");
+                assert_eq!(after.get(), "after");
+                element
+                    .children
+                    .get(1)
+                    .unwrap()
+                    .get_text()
+                    .unwrap()
+                    .to_string()
+            })
+        };
+
+        assert_eq!(render(), "fallback");
+        assert_eq!(render(), "fallback");
+        // The children are not retried while the error is held
+        assert_eq!(attempts.get(), 1);
+
+        failing.set(false);
+        reset_handle.borrow().as_ref().unwrap().reset();
+        assert_eq!(render(), "recovered");
+        assert_eq!(attempts.get(), 2);
+    }
+
+    #[test]
+    fn test_failed_subtree_is_unmounted() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let render = |fail: bool| {
+            let log = log.clone();
+            with_hooks(root.clone(), move || {
+                ErrorBoundary::new(move || {
+                    let cleanup_log = log.clone();
+                    use_effect_once(move || {
+                        cleanup_log.borrow_mut().push("mount");
+                        Some(std::boxed::Box::new(move || {
+                            cleanup_log.borrow_mut().push("cleanup")
+                        }))
+                    });
+                    if fail {
+                        panic!("fail");
+                    }
+                    Text::new("x").into_element()
+                })
+                .into_element()
+            })
+        };
+
+        render(false);
+        render(false);
+        assert_eq!(*log.borrow(), vec!["mount"]);
+
+        render(true);
+        assert_eq!(*log.borrow(), vec!["mount", "cleanup"]);
+    }
+}
@@ -0,0 +1,368 @@
+//! Memoized subtrees that skip re-rendering when their props are unchanged
+//!
+//! Every render calls the root component and rebuilds the whole element
+//! tree. Wrapping an expensive part of the tree in [`memo`] keeps the
+//! element it produced and returns it again while the props compare equal,
+//! so only changed regions are regenerated. Because the cached subtree is
+//! structurally identical to the previous frame, the layout engine keeps its
+//! nodes clean and reuses their computed layout as well.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn chat(messages: &[ChatMessage]) -> Element {
+//!     Box::new()
+//!         .flex_direction(FlexDirection::Column)
+//!         .children(messages.iter().map(|message| {
+//!             // Markdown is only re-rendered for messages that changed
+//!             memo(message.clone(), |message| render_markdown(&message.body))
+//!                 .key(message.id.to_string())
+//!                 .into_element()
+//!         }))
+//!         .into_element()
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::core::Element;
+use crate::hooks::context::{ComponentId, HookContext, current_context, with_hooks};
+use crate::hooks::registrations::{RegistrationMark, Registrations};
+
+/// A subtree rendered from props and reused while the props are equal
+///
+/// The render function gets its own hook state, like a
+/// [`Component`](crate::components::Component). Besides a change in props,
+/// the subtree is re-rendered when state inside it changes (a signal set by
+/// one of its hooks, for example). Handlers and other per-frame registrations
+/// made by the subtree stay active while it is reused.
+///
+/// Context values read with [`use_context`](crate::hooks::use_context) are
+/// not tracked; pass anything the subtree depends on through the props.
+pub struct Memo<P, F>
+where
+    P: PartialEq + 'static,
+    F: Fn(&P) -> Element + 'static,
+{
+    props: P,
+    render: F,
+    key: Option<String>,
+}
+
+impl<P, F> Memo<P, F>
+where
+    P: PartialEq + 'static,
+    F: Fn(&P) -> Element + 'static,
+{
+    /// Create a memoized subtree from props and a render function
+    pub fn new(props: P, render: F) -> Self {
+        Self {
+            props,
+            render,
+            key: None,
+        }
+    }
+
+    /// Set key for reconciliation
+    ///
+    /// Keys keep each cached subtree attached to the right instance when
+    /// siblings rendered by the same function are reordered.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Render (or reuse) the subtree and convert it to an Element
+    ///
+    /// Outside a render cycle there is nothing to cache across, so the
+    /// render function is simply called.
+    pub fn into_element(self) -> Element {
+        let Some(parent) = current_context() else {
+            let ctx = Rc::new(RefCell::new(HookContext::new()));
+            let element = with_hooks(ctx.clone(), || (self.render)(&self.props));
+            ctx.borrow_mut().unmount();
+            return element;
+        };
+
+        let key = self.key.clone();
+        let ctx = parent
+            .borrow_mut()
+            .child_context(ComponentId::of_render(&self.render), key.as_deref());
+        let mut element = with_hooks(ctx.clone(), || self.render_cached());
+
+        let cmds = ctx.borrow_mut().take_cmds();
+        let mut parent = parent.borrow_mut();
+        for cmd in cmds {
+            parent.queue_cmd(cmd);
+        }
+
+        if element.key.is_none() {
+            element.key = key;
+        }
+        element
+    }
+
+    /// Render inside the memo's own context, which holds the cache
+    fn render_cached(self) -> Element {
+        let ctx = current_context().expect("memo renders inside its own context");
+        let cache = ctx
+            .borrow_mut()
+            .use_hook(|| Rc::new(RefCell::new(MemoCache::<P>::default())))
+            .get::<Rc<RefCell<MemoCache<P>>>>()
+            .expect("memo cache has a fixed hook slot");
+
+        let (content, created) = {
+            let mut ctx = ctx.borrow_mut();
+            let existing = ctx.child_count();
+            let content = ctx.child_context(ComponentId::of::<MemoContent>(), None);
+            (content, ctx.child_count() > existing)
+        };
+
+        let mut cache = cache.borrow_mut();
+        if created {
+            // State changes inside the subtree invalidate the cache
+            let dirty = cache.dirty.clone();
+            let mut content = content.borrow_mut();
+            match content.get_sync_render_callback() {
+                Some(parent_callback) => content.set_sync_render_callback(Arc::new(move || {
+                    dirty.store(true, Ordering::SeqCst);
+                    parent_callback();
+                })),
+                None => {
+                    let parent_callback = content.get_render_callback();
+                    content.set_render_callback(Rc::new(move || {
+                        dirty.store(true, Ordering::SeqCst);
+                        if let Some(callback) = &parent_callback {
+                            callback();
+                        }
+                    }));
+                }
+            }
+        }
+
+        let dirty = cache.dirty.swap(false, Ordering::SeqCst);
+        if !dirty
+            && cache.props.as_ref() == Some(&self.props)
+            && let Some(element) = &cache.element
+        {
+            cache.registrations.replay();
+            return element.clone();
+        }
+
+        let mark = RegistrationMark::now();
+
+        let render = self.render;
+        let props = self.props;
+        let element = with_hooks(content.clone(), || render(&props));
+
+        let cmds = content.borrow_mut().take_cmds();
+        for cmd in cmds {
+            ctx.borrow_mut().queue_cmd(cmd);
+        }
+
+        cache.registrations = Registrations::since(mark);
+        cache.props = Some(props);
+        cache.element = Some(element.clone());
+        element
+    }
+}
+
+/// Marker type identifying the memoized subtree's hook context
+struct MemoContent;
+
+/// What a memo remembers from its last render
+struct MemoCache<P> {
+    props: Option<P>,
+    element: Option<Element>,
+    registrations: Registrations,
+    dirty: Arc<AtomicBool>,
+}
+
+impl<P> Default for MemoCache<P> {
+    fn default() -> Self {
+        Self {
+            props: None,
+            element: None,
+            registrations: Registrations::default(),
+            dirty: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Create a memoized subtree
+///
+/// Equivalent to `Memo::new(props, render)`.
+pub fn memo<P, F>(props: P, render: F) -> Memo<P, F>
+where
+    P: PartialEq + 'static,
+    F: Fn(&P) -> Element + 'static,
+{
+    Memo::new(props, render)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Box, Text};
+    use crate::hooks::use_input::{Key, clear_input_handlers, dispatch_input, use_input};
+    use crate::hooks::{Signal, use_signal};
+    use crate::layout::LayoutEngine;
+    use std::cell::Cell;
+
+    fn render_with(
+        root: &Rc<RefCell<HookContext>>,
+        calls: &Rc<Cell<usize>>,
+        label: &'static str,
+    ) -> Element {
+        let calls = calls.clone();
+        with_hooks(root.clone(), move || {
+            Box::new()
+                .child(Text::new("header").into_element())
+                .child(
+                    memo(label, move |label| {
+                        calls.set(calls.get() + 1);
+                        Text::new(*label).into_element()
+                    })
+                    .into_element(),
+                )
+                .into_element()
+        })
+    }
+
+    #[test]
+    fn test_equal_props_reuse_subtree() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let calls = Rc::new(Cell::new(0));
+
+        let first = render_with(&root, &calls, "a");
+        let second = render_with(&root, &calls, "a");
+        assert_eq!(calls.get(), 1);
+        assert_eq!(second.children.get(1).unwrap().get_text(), Some("a"));
+
+        let third = render_with(&root, &calls, "b");
+        assert_eq!(calls.get(), 2);
+        assert_eq!(third.children.get(1).unwrap().get_text(), Some("b"));
+
+        // The reused subtree keeps its layout nodes
+        let mut engine = LayoutEngine::new();
+        let mut first = first;
+        let mut second = second;
+        engine.compute_incremental(&mut first, 20, 5);
+        let nodes = engine.node_count();
+        engine.compute_incremental(&mut second, 20, 5);
+        assert_eq!(engine.node_count(), nodes);
+        assert_eq!(
+            first.children.get(1).unwrap().id,
+            second.children.get(1).unwrap().id
+        );
+    }
+
+    #[test]
+    fn test_internal_state_invalidates_cache() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let calls = Rc::new(Cell::new(0));
+        let counter: Rc<RefCell<Option<Signal<i32>>>> = Rc::new(RefCell::new(None));
+
+        let render = || {
+            let calls = calls.clone();
+            let counter = counter.clone();
+            with_hooks(root.clone(), move || {
+                memo((), move |_| {
+                    calls.set(calls.get() + 1);
+                    let count = use_signal(|| 0);
+                    *counter.borrow_mut() = Some(count.clone());
+                    Text::new(count.get().to_string()).into_element()
+                })
+                .into_element()
+            })
+        };
+
+        assert_eq!(render().get_text(), Some("0"));
+        assert_eq!(render().get_text(), Some("0"));
+        assert_eq!(calls.get(), 1);
+
+        counter.borrow().as_ref().unwrap().set(5);
+        assert_eq!(render().get_text(), Some("5"));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_reused_subtree_keeps_input_handlers() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let presses = Rc::new(Cell::new(0));
+
+        for _ in 0..3 {
+            clear_input_handlers();
+            let presses = presses.clone();
+            with_hooks(root.clone(), move || {
+                memo((), move |_| {
+                    let presses = presses.clone();
+                    use_input(move |_, _| presses.set(presses.get() + 1));
+                    Text::new("x").into_element()
+                })
+                .into_element()
+            });
+            dispatch_input("a", &Key::default());
+        }
+
+        assert_eq!(presses.get(), 3);
+        clear_input_handlers();
+    }
+
+    #[test]
+    fn test_reused_subtree_keeps_focus_and_refs() {
+        use crate::core::{FlexDirection, Overflow};
+        use crate::hooks::use_ref::update_element_refs;
+        use crate::hooks::{ElementRef, use_focus, use_focus_manager};
+
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let calls = Rc::new(Cell::new(0));
+        let container = ElementRef::new();
+        let rows: Vec<ElementRef> = (0..5).map(|_| ElementRef::new()).collect();
+        let mut engine = LayoutEngine::new();
+
+        let mut frame = || {
+            let calls = calls.clone();
+            let props = (container.clone(), rows.clone());
+            let mut element = with_hooks(root.clone(), move || {
+                memo(props, move |(container, rows)| {
+                    calls.set(calls.get() + 1);
+                    use_focus(rows[0].focus_options());
+                    Box::new()
+                        .ref_(container)
+                        .height(2)
+                        .overflow_y(Overflow::Hidden)
+                        .flex_direction(FlexDirection::Column)
+                        .children(rows.iter().enumerate().map(|(i, row)| {
+                            Box::new()
+                                .ref_(row)
+                                .flex_shrink(0.0)
+                                .child(Text::new(format!("row {}", i)).into_element())
+                                .into_element()
+                        }))
+                        .into_element()
+                })
+                .into_element()
+            });
+            engine.compute_incremental(&mut element, 20, 10);
+            update_element_refs(&mut element, &engine);
+        };
+
+        frame();
+        rows[4].scroll_into_view();
+        frame();
+        frame();
+        assert_eq!(calls.get(), 1);
+        // The scroll position chosen through a ref survives cache hits
+        assert_eq!(container.scroll_offset(), (0, 3));
+
+        // The element registered for focus on the first render stays focusable
+        use_focus_manager().focus(rows[0].focus_id());
+        frame();
+        assert_eq!(calls.get(), 1);
+        assert!(rows[0].is_focused());
+    }
+}
@@ -105,10 +105,10 @@ impl Message {
     fn color(&self) -> Color {
         match self.role {
             MessageRole::User => Color::Yellow,
-            MessageRole::Assistant => Color::BrightWhite,
+            MessageRole::Assistant => Color::adaptive(Color::Black, Color::BrightWhite),
             MessageRole::System => Color::Cyan,
             MessageRole::Tool => Color::Magenta,
-            MessageRole::ToolResult => Color::Ansi256(245),
+            MessageRole::ToolResult => Color::MUTED,
             MessageRole::Error => Color::Red,
         }
     }
@@ -117,10 +117,10 @@ impl Message {
     fn prefix_color(&self) -> Color {
         match self.role {
             MessageRole::User => Color::Yellow,
-            MessageRole::Assistant => Color::BrightWhite,
+            MessageRole::Assistant => Color::adaptive(Color::Black, Color::BrightWhite),
             MessageRole::System => Color::Cyan,
             MessageRole::Tool => Color::Magenta,
-            MessageRole::ToolResult => Color::Ansi256(245),
+            MessageRole::ToolResult => Color::MUTED,
             MessageRole::Error => Color::Red,
         }
     }
@@ -245,12 +245,8 @@ impl ThinkingBlock {
         }
 
         if has_more {
-            container = container.child(
-                Text::new("  ...")
-                    .color(Color::Ansi256(245))
-                    .dim()
-                    .into_element(),
-            );
+            container =
+                container.child(Text::new("  ...").color(Color::MUTED).dim().into_element());
         }
 
         container.into_element()
@@ -2,10 +2,14 @@
 
 mod barchart;
 mod box_component;
+mod component;
+mod error_boundary;
 mod list;
+mod memo;
 mod message;
 mod modal;
 mod newline;
+mod portal;
 mod progress;
 mod scrollable;
 mod scrollbar;
@@ -13,6 +17,7 @@ mod spacer;
 mod sparkline;
 mod spinner;
 mod static_output;
+pub(crate) mod suspense;
 mod table;
 mod tabs;
 pub mod text;
@@ -21,10 +26,14 @@ mod transform;
 
 pub use barchart::{Bar, BarChart, BarChartOrientation};
 pub use box_component::Box;
+pub use component::{Component, component};
+pub use error_boundary::{BoundaryReset, ErrorBoundary, RenderError};
 pub use list::{List, ListItem, ListState};
+pub use memo::{Memo, memo};
 pub use message::{Message, MessageRole, ThinkingBlock, ToolCall};
 pub use modal::{Dialog, DialogState, Modal, ModalAlign};
 pub use newline::Newline;
+pub use portal::Portal;
 pub use progress::{Gauge, Progress, ProgressSymbols};
 pub use scrollable::{ScrollableBox, fixed_bottom_layout, virtual_scroll_view};
 pub use scrollbar::{Scrollbar, ScrollbarOrientation, ScrollbarSymbols};
@@ -32,6 +41,7 @@ pub use spacer::Spacer;
 pub use sparkline::Sparkline;
 pub use spinner::{Spinner, SpinnerBuilder};
 pub use static_output::{Static, static_output};
+pub use suspense::Suspense;
 pub use table::{Cell, Constraint, Row, Table, TableState};
 pub use tabs::{Tab, Tabs};
 pub use text::{Line, Span, Text};
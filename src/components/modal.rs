@@ -19,6 +19,10 @@ pub enum ModalAlign {
 
 /// Modal component for displaying overlay content
 ///
+/// The modal renders through a [`Portal`](crate::components::Portal), so it
+/// is centered over the whole root area and painted above the rest of the
+/// tree wherever it is declared.
+///
 /// # Example
 ///
 /// ```rust
@@ -160,6 +164,7 @@ impl Modal {
     /// Convert to Element
     pub fn into_element(self) -> Element {
         use crate::components::Box;
+        use crate::components::Portal;
         use crate::components::Text;
 
         // Build the modal content box
@@ -220,10 +225,12 @@ impl Modal {
 
         // Add backdrop if enabled
         if self.backdrop {
-            wrapper = wrapper.background(Color::Black);
+            wrapper = wrapper.background(Color::adaptive(Color::White, Color::Black));
         }
 
-        wrapper.child(content_box.into_element()).into_element()
+        Portal::new()
+            .child(wrapper.child(content_box.into_element()).into_element())
+            .into_element()
     }
 }
 
@@ -281,7 +288,7 @@ impl Dialog {
             width: Some(50),
             confirm_color: Some(Color::Green),
             cancel_color: Some(Color::Red),
-            focus_color: Some(Color::Cyan),
+            focus_color: Some(Color::adaptive(Color::Blue, Color::Cyan)),
         }
     }
 
@@ -515,6 +522,12 @@ mod tests {
         assert!(!bottom.children.is_empty());
     }
 
+    #[test]
+    fn test_modal_renders_in_portal() {
+        let modal = Modal::new().title("Overlay").into_element();
+        assert_eq!(modal.element_type, crate::core::ElementType::Portal);
+    }
+
     #[test]
     fn test_dialog_creation() {
         let dialog = Dialog::new()
@@ -0,0 +1,108 @@
+//! Portal component for rendering into the overlay layer
+//!
+//! Children of a [`Portal`] are laid out against the root element instead of
+//! the portal's parent and painted above the rest of the tree. That lets
+//! dropdowns, tooltips and toasts be declared next to the element they belong
+//! to while still escaping `overflow: hidden` ancestors.
+
+use crate::core::{Dimension, Element, FlexDirection, Position};
+
+/// Renders its children in the overlay layer
+///
+/// The portal covers the whole root area as a column container; position
+/// content inside it with flexbox alignment or absolutely positioned
+/// children. Portals nested in other portals paint above them.
+///
+/// # Example
+///
+/// ```
+/// use rnk::components::{Box, Portal, Text};
+///
+/// let tooltip = Portal::new()
+///     .z_index(10)
+///     .child(
+///         Box::new()
+///             .position_absolute()
+///             .top(3.0)
+///             .left(12.0)
+///             .child(Text::new("Saved!").into_element())
+///             .into_element(),
+///     )
+///     .into_element();
+/// ```
+#[derive(Default)]
+pub struct Portal {
+    children: Vec<Element>,
+    z_index: i32,
+    key: Option<String>,
+}
+
+impl Portal {
+    /// Create a new portal
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a child element
+    pub fn child(mut self, element: Element) -> Self {
+        self.children.push(element);
+        self
+    }
+
+    /// Add multiple children
+    pub fn children(mut self, elements: impl IntoIterator<Item = Element>) -> Self {
+        self.children.extend(elements);
+        self
+    }
+
+    /// Set the z-index among other portals in the same layer
+    pub fn z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Set key for reconciliation
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Convert to Element
+    pub fn into_element(self) -> Element {
+        let mut element = Element::portal();
+        element.style.position = Position::Absolute;
+        element.style.top = Some(0.0);
+        element.style.left = Some(0.0);
+        element.style.width = Dimension::Percent(100.0);
+        element.style.height = Dimension::Percent(100.0);
+        element.style.flex_direction = FlexDirection::Column;
+        element.style.z_index = self.z_index;
+        element.key = self.key;
+        for child in self.children {
+            element.add_child(child);
+        }
+        element
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Text;
+    use crate::core::ElementType;
+
+    #[test]
+    fn test_portal_element() {
+        let portal = Portal::new()
+            .z_index(3)
+            .key("toast")
+            .child(Text::new("hi").into_element())
+            .into_element();
+
+        assert_eq!(portal.element_type, ElementType::Portal);
+        assert_eq!(portal.style.position, Position::Absolute);
+        assert_eq!(portal.style.z_index, 3);
+        assert_eq!(portal.key.as_deref(), Some("toast"));
+        assert_eq!(portal.children.len(), 1);
+    }
+}
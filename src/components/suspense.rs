@@ -0,0 +1,393 @@
+//! Suspense boundaries that show a fallback while descendants load
+//!
+//! A [`Suspense`] renders its children and counts the commands they start
+//! through [`use_cmd`](crate::hooks::use_cmd) and
+//! [`use_resource`](crate::hooks::use_resource). While any of them is still
+//! running, the fallback is shown in place of the children; once all have
+//! finished, the real subtree is swapped in. Screens that load several panels
+//! in parallel need one boundary instead of per-panel loading logic.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn dashboard() -> Element {
+//!     Suspense::new(|| {
+//!         Box::new()
+//!             .flex_direction(FlexDirection::Column)
+//!             .children((0..5).map(|id| Component::new(panel, id).key(id.to_string()).into_element()))
+//!             .into_element()
+//!     })
+//!     .fallback(|| Text::new("Loading dashboard...").dim().into_element())
+//!     .min_duration(Duration::from_millis(300))
+//!     .into_element()
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::cmd::Cmd;
+use crate::components::Text;
+use crate::core::Element;
+use crate::hooks::context::{
+    ComponentId, ContextId, HookContext, SyncRenderCallback, current_context, with_hooks,
+};
+
+type Children = Box<dyn FnOnce() -> Element>;
+type Fallback = Box<dyn FnOnce() -> Element>;
+
+/// Marker type identifying the children's hook context
+struct SuspenseContent;
+
+/// Marker type identifying the fallback's hook context
+struct SuspenseFallback;
+
+/// Element that shows a fallback until its descendants finish loading
+///
+/// The children are always rendered, so their hooks keep their state and
+/// start their loads on the first render; only the element they produce is
+/// held back while loads are pending. Hooks in the fallback are not tracked
+/// by this boundary.
+pub struct Suspense {
+    children: Children,
+    fallback: Option<Fallback>,
+    min_duration: Option<Duration>,
+    key: Option<String>,
+}
+
+impl Suspense {
+    /// Create a boundary around children that load data
+    pub fn new<F>(children: F) -> Self
+    where
+        F: FnOnce() -> Element + 'static,
+    {
+        Self {
+            children: Box::new(children),
+            fallback: None,
+            min_duration: None,
+            key: None,
+        }
+    }
+
+    /// Set the element rendered while loads are pending
+    ///
+    /// Without a fallback, a dimmed "Loading..." text is shown.
+    pub fn fallback<F>(mut self, fallback: F) -> Self
+    where
+        F: FnOnce() -> Element + 'static,
+    {
+        self.fallback = Some(Box::new(fallback));
+        self
+    }
+
+    /// Keep the fallback visible for at least `duration` once shown
+    ///
+    /// Avoids a flash of the fallback when loads finish almost immediately.
+    pub fn min_duration(mut self, duration: Duration) -> Self {
+        self.min_duration = Some(duration);
+        self
+    }
+
+    /// Set key for reconciliation
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Render the boundary and convert it to an Element
+    pub fn into_element(self) -> Element {
+        let key = self.key.clone();
+
+        let mut element = match current_context() {
+            Some(parent) => {
+                let ctx = parent
+                    .borrow_mut()
+                    .child_context(ComponentId::of::<Suspense>(), key.as_deref());
+                let element = with_hooks(ctx.clone(), || self.render());
+                forward_cmds(&ctx, &parent);
+                element
+            }
+            None => {
+                let ctx = Rc::new(RefCell::new(HookContext::new()));
+                let element = with_hooks(ctx.clone(), || self.render());
+                ctx.borrow_mut().unmount();
+                element
+            }
+        };
+
+        if element.key.is_none() {
+            element.key = key;
+        }
+        element
+    }
+
+    /// Render inside the boundary's own hook context
+    fn render(self) -> Element {
+        let ctx = current_context().expect("suspense renders inside its own context");
+        let state = {
+            let mut ctx_ref = ctx.borrow_mut();
+            let render_callback = ctx_ref.get_sync_render_callback();
+            ctx_ref
+                .use_hook(|| {
+                    Rc::new(RefCell::new(SuspenseState {
+                        boundary: SuspenseBoundary {
+                            pending: Arc::new(AtomicUsize::new(0)),
+                            render_callback,
+                        },
+                        shown_since: None,
+                        wake_scheduled: false,
+                    }))
+                })
+                .get::<Rc<RefCell<SuspenseState>>>()
+                .expect("suspense state has a fixed hook slot")
+        };
+        let mut state = state.borrow_mut();
+
+        let content = ctx
+            .borrow_mut()
+            .child_context(ComponentId::of::<SuspenseContent>(), None);
+        content.borrow_mut().provide(
+            ContextId::of::<SuspenseBoundary>(),
+            Rc::new(state.boundary.clone()),
+        );
+        let element = with_hooks(content.clone(), self.children);
+        forward_cmds(&content, &ctx);
+
+        let now = Instant::now();
+        if state.boundary.pending() {
+            state.shown_since.get_or_insert(now);
+        } else if let Some(since) = state.shown_since {
+            let remaining = self
+                .min_duration
+                .and_then(|min| min.checked_sub(now.duration_since(since)))
+                .filter(|remaining| !remaining.is_zero());
+            match remaining {
+                Some(remaining) => {
+                    // Render again once the minimum display time is over
+                    if !state.wake_scheduled {
+                        state.wake_scheduled = true;
+                        ctx.borrow_mut().queue_cmd(Cmd::sleep(remaining));
+                    }
+                }
+                None => {
+                    state.shown_since = None;
+                    state.wake_scheduled = false;
+                }
+            }
+        }
+
+        if state.shown_since.is_none() {
+            return element;
+        }
+
+        let fallback_ctx = ctx
+            .borrow_mut()
+            .child_context(ComponentId::of::<SuspenseFallback>(), None);
+        let fallback = self.fallback;
+        let element = with_hooks(fallback_ctx.clone(), || match fallback {
+            Some(fallback) => fallback(),
+            None => Text::new("Loading...").dim().into_element(),
+        });
+        forward_cmds(&fallback_ctx, &ctx);
+        element
+    }
+}
+
+/// What a suspense boundary remembers between renders
+struct SuspenseState {
+    boundary: SuspenseBoundary,
+    /// When the fallback was first shown, while it is showing
+    shown_since: Option<Instant>,
+    /// Whether a render after the minimum display time is already queued
+    wake_scheduled: bool,
+}
+
+/// Pending-load counter provided to the children of a [`Suspense`]
+#[derive(Clone)]
+struct SuspenseBoundary {
+    pending: Arc<AtomicUsize>,
+    render_callback: Option<SyncRenderCallback>,
+}
+
+impl SuspenseBoundary {
+    fn pending(&self) -> bool {
+        self.pending.load(Ordering::SeqCst) > 0
+    }
+}
+
+/// Counts one load as pending until dropped
+struct PendingGuard {
+    boundary: SuspenseBoundary,
+}
+
+impl PendingGuard {
+    fn new(boundary: SuspenseBoundary) -> Self {
+        boundary.pending.fetch_add(1, Ordering::SeqCst);
+        Self { boundary }
+    }
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        if self.boundary.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+            match &self.boundary.render_callback {
+                Some(callback) => callback(),
+                None => crate::renderer::request_render(),
+            }
+        }
+    }
+}
+
+/// Make the nearest enclosing [`Suspense`] wait for `cmd`
+///
+/// Returns `cmd` unchanged outside a boundary. Inside one, the command
+/// counts as pending until it completes (or is dropped without running).
+pub(crate) fn track_pending(ctx: &HookContext, cmd: Cmd) -> Cmd {
+    if cmd.is_none() {
+        return cmd;
+    }
+    let Some(boundary) = ctx
+        .provided(ContextId::of::<SuspenseBoundary>())
+        .and_then(|value| value.downcast_ref::<SuspenseBoundary>().cloned())
+    else {
+        return cmd;
+    };
+
+    let guard = PendingGuard::new(boundary);
+    Cmd::sequence(vec![
+        cmd,
+        Cmd::perform(move || async move {
+            drop(guard);
+        }),
+    ])
+}
+
+/// Move commands queued by a child context to its parent
+fn forward_cmds(child: &Rc<RefCell<HookContext>>, parent: &Rc<RefCell<HookContext>>) {
+    let cmds = child.borrow_mut().take_cmds();
+    let mut parent = parent.borrow_mut();
+    for cmd in cmds {
+        parent.queue_cmd(cmd);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Box, Component};
+    use crate::hooks::{Resource, use_cmd_once, use_resource};
+    use crate::renderer::App;
+    use crate::testing::TestRenderer;
+    use tokio::sync::oneshot;
+
+    type Gate = Rc<RefCell<Option<oneshot::Receiver<u32>>>>;
+
+    fn panel(gate: &Gate) -> Element {
+        let gate = gate.clone();
+        let (value, _) = use_resource((), move |_| {
+            let rx = gate.borrow_mut().take().expect("fetch starts once");
+            async move { rx.await.map_err(|_| "closed".to_string()) }
+        });
+        match value {
+            Resource::Ready(value) => Text::new(format!("value {}", value)).into_element(),
+            _ => Text::new("...").into_element(),
+        }
+    }
+
+    fn gate() -> (oneshot::Sender<u32>, Gate) {
+        let (tx, rx) = oneshot::channel();
+        (tx, Rc::new(RefCell::new(Some(rx))))
+    }
+
+    fn plain(element: &Element) -> String {
+        TestRenderer::new(20, 3).render_to_plain(element)
+    }
+
+    /// Render frames as the event loop would until `done` accepts one
+    fn render_until<F: Fn() -> Element>(app: &App<F>, done: impl Fn(&str) -> bool) -> String {
+        loop {
+            app.wait_for_render_request();
+            let output = plain(&app.render_detached());
+            if done(&output) {
+                return output;
+            }
+        }
+    }
+
+    fn dashboard(gates: &[Gate], min: Option<Duration>) -> impl FnOnce() -> Element + use<> {
+        let gates = gates.to_vec();
+        move || {
+            let mut suspense = Suspense::new(move || {
+                Box::new()
+                    .children(gates.into_iter().enumerate().map(|(i, gate)| {
+                        Component::new(panel, gate)
+                            .key(i.to_string())
+                            .into_element()
+                    }))
+                    .into_element()
+            })
+            .fallback(|| Text::new("loading").into_element());
+            if let Some(min) = min {
+                suspense = suspense.min_duration(min);
+            }
+            suspense.into_element()
+        }
+    }
+
+    #[test]
+    fn test_fallback_until_all_loads_resolve() {
+        let (first_tx, first) = gate();
+        let (second_tx, second) = gate();
+        let gates = [first, second];
+        let app = App::new(move || dashboard(&gates, None)());
+
+        assert!(plain(&app.render_detached()).contains("loading"));
+
+        first_tx.send(1).unwrap();
+        app.wait_for_render_request();
+        assert!(plain(&app.render_detached()).contains("loading"));
+
+        second_tx.send(2).unwrap();
+        let output = render_until(&app, |output| !output.contains("loading"));
+        assert!(output.contains("value 1"));
+        assert!(output.contains("value 2"));
+    }
+
+    #[test]
+    fn test_min_duration_keeps_fallback() {
+        let (tx, gate) = gate();
+        let gates = [gate];
+        let min = Duration::from_millis(150);
+        let app = App::new(move || dashboard(&gates, Some(min))());
+
+        let start = Instant::now();
+        assert!(plain(&app.render_detached()).contains("loading"));
+        tx.send(7).unwrap();
+
+        // The boundary wakes itself once the minimum display time is over
+        let output = render_until(&app, |output| !output.contains("loading"));
+        assert!(output.contains("value 7"));
+        assert!(start.elapsed() >= min);
+    }
+
+    #[test]
+    fn test_fallback_cmds_are_not_waited_for() {
+        let app = App::new(|| {
+            Suspense::new(|| {
+                use_cmd_once(|_| Cmd::perform(|| async {}));
+                Text::new("ready").into_element()
+            })
+            .fallback(|| {
+                use_cmd_once(|_| Cmd::sleep(Duration::from_secs(60)));
+                Text::new("loading").into_element()
+            })
+            .into_element()
+        });
+
+        assert!(plain(&app.render_detached()).contains("loading"));
+        render_until(&app, |output| output.contains("ready"));
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::components::{Box as TinkBox, Line, Span, Text};
 use crate::core::{Color, Element, FlexDirection, Style};
+use crate::layout::measure_text_width;
 
 /// Table cell content
 #[derive(Debug, Clone)]
@@ -272,7 +273,11 @@ impl Table {
     pub fn render(self, state: &TableState) -> Element {
         let selected = state.selected;
         let separator = self.column_separator.as_deref().unwrap_or(" ");
-        let symbol_width = self.highlight_symbol.as_ref().map(|s| s.len()).unwrap_or(0);
+        let symbol_width = self
+            .highlight_symbol
+            .as_deref()
+            .map(measure_text_width)
+            .unwrap_or(0);
 
         let mut container = TinkBox::new().flex_direction(FlexDirection::Column);
 
@@ -116,8 +116,7 @@ impl Span {
 
     /// Get the display width of this span
     pub fn width(&self) -> usize {
-        use unicode_width::UnicodeWidthStr;
-        self.content.width()
+        crate::layout::measure_text_width(&self.content)
     }
 }
 
@@ -369,6 +368,12 @@ impl Text {
         self
     }
 
+    /// Set the string that marks truncated text (default "…")
+    pub fn ellipsis(mut self, ellipsis: impl Into<String>) -> Self {
+        self.style.text_ellipsis = Some(ellipsis.into());
+        self
+    }
+
     // === Convenience methods ===
 
     /// Apply error style (red color)
@@ -391,9 +396,9 @@ impl Text {
         self.color(Color::Blue)
     }
 
-    /// Apply muted style (dim)
+    /// Apply muted style (gray, readable on light and dark backgrounds)
     pub fn muted(self) -> Self {
-        self.dim()
+        self.color(Color::MUTED)
     }
 
     /// Convert to Element
@@ -482,6 +487,9 @@ mod tests {
 
         let success = Text::new("Success").success().into_element();
         assert_eq!(success.style.color, Some(Color::Green));
+
+        let muted = Text::new("Muted").muted().into_element();
+        assert_eq!(muted.style.color, Some(Color::MUTED));
     }
 
     #[test]
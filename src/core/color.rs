@@ -1,6 +1,9 @@
 //! Color types for terminal styling
 
+use crate::runtime::Environment;
 use crossterm::style::Color as CrosstermColor;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 /// Color type supporting various color formats
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -34,6 +37,79 @@ pub enum Color {
 
     /// RGB color (24-bit)
     Rgb(u8, u8, u8),
+
+    /// A color that depends on the terminal background
+    Adaptive(AdaptiveColor),
+}
+
+/// A pair of colors for light and dark terminal backgrounds
+///
+/// Resolved when rendering, using the background detected at startup (see
+/// [`terminal_background`]). Build one with [`Color::adaptive`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveColor {
+    light: u32,
+    dark: u32,
+}
+
+impl AdaptiveColor {
+    /// Create an adaptive color
+    ///
+    /// Adaptive colors passed in contribute their own light or dark side.
+    pub const fn new(light: Color, dark: Color) -> Self {
+        Self {
+            light: pack(light, false),
+            dark: pack(dark, true),
+        }
+    }
+
+    /// The color used on light backgrounds
+    pub const fn light(&self) -> Color {
+        unpack(self.light)
+    }
+
+    /// The color used on dark backgrounds
+    pub const fn dark(&self) -> Color {
+        unpack(self.dark)
+    }
+}
+
+impl std::fmt::Debug for AdaptiveColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdaptiveColor")
+            .field("light", &self.light())
+            .field("dark", &self.dark())
+            .finish()
+    }
+}
+
+/// Pack a non-adaptive color into a tag byte and 24 bits of payload
+const fn pack(color: Color, dark: bool) -> u32 {
+    match color {
+        Color::Reset => 0,
+        Color::Ansi256(code) => (1 << 24) | code as u32,
+        Color::Rgb(r, g, b) => (2 << 24) | (r as u32) << 16 | (g as u32) << 8 | b as u32,
+        Color::Adaptive(adaptive) => {
+            if dark {
+                adaptive.dark
+            } else {
+                adaptive.light
+            }
+        }
+        named => match named.ansi16_index() {
+            Some(index) => (3 << 24) | index as u32,
+            None => 0,
+        },
+    }
+}
+
+const fn unpack(packed: u32) -> Color {
+    match packed >> 24 {
+        1 => Color::Ansi256(packed as u8),
+        2 => Color::Rgb((packed >> 16) as u8, (packed >> 8) as u8, packed as u8),
+        3 => Color::from_ansi16_index(packed as u8),
+        _ => Color::Reset,
+    }
 }
 
 impl Color {
@@ -92,11 +168,388 @@ impl Color {
     pub fn ansi256(code: u8) -> Self {
         Color::Ansi256(code)
     }
+
+    /// Create a color that is `light` on light backgrounds and `dark` on dark ones
+    ///
+    /// ```
+    /// use rnk::core::Color;
+    ///
+    /// let text = Color::adaptive(Color::Black, Color::BrightWhite);
+    /// ```
+    pub const fn adaptive(light: Color, dark: Color) -> Self {
+        Color::Adaptive(AdaptiveColor::new(light, dark))
+    }
+
+    /// Gray that stays readable on light and dark backgrounds
+    pub const MUTED: Color = Color::adaptive(Color::Ansi256(243), Color::Ansi256(245));
+
+    /// The concrete color for the current terminal background
+    pub fn resolve(self) -> Color {
+        match self {
+            Color::Adaptive(adaptive) => match terminal_background() {
+                TerminalBackground::Light => adaptive.light(),
+                TerminalBackground::Dark => adaptive.dark(),
+            },
+            other => other,
+        }
+    }
+
+    /// The RGB value of this color, using the xterm palette for indexed colors
+    ///
+    /// Returns `None` for [`Color::Reset`], whose value depends on the terminal.
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        match self.resolve() {
+            Color::Reset => None,
+            Color::Rgb(r, g, b) => Some((r, g, b)),
+            Color::Ansi256(code) => Some(palette_rgb(code)),
+            named => named.ansi16_index().map(palette_rgb),
+        }
+    }
+
+    /// Convert this color to one the given profile can display
+    ///
+    /// Colors the profile can't show are mapped to the perceptually nearest
+    /// palette entry, compared in CIELAB space. Returns `None` when the
+    /// profile has no colors at all.
+    pub fn downsample(self, profile: ColorProfile) -> Option<Color> {
+        let color = self.resolve();
+        match profile {
+            ColorProfile::NoColor => None,
+            ColorProfile::TrueColor => Some(color),
+            ColorProfile::Ansi256 => Some(match color {
+                // The 16 base colors vary between themes, so only match the
+                // fixed cube and gray ramp
+                Color::Rgb(r, g, b) => Color::Ansi256(nearest(Lab::from_rgb(r, g, b), 16..=255)),
+                other => other,
+            }),
+            ColorProfile::Ansi16 => Some(match color {
+                Color::Ansi256(code) if code < 16 => Color::from_ansi16_index(code),
+                Color::Ansi256(_) | Color::Rgb(..) => {
+                    let (r, g, b) = color.to_rgb().unwrap_or_default();
+                    Color::from_ansi16_index(nearest(Lab::from_rgb(r, g, b), 0..=15))
+                }
+                other => other,
+            }),
+        }
+    }
+
+    /// Palette index of a named color
+    const fn ansi16_index(self) -> Option<u8> {
+        let index = match self {
+            Color::Black => 0,
+            Color::Red => 1,
+            Color::Green => 2,
+            Color::Yellow => 3,
+            Color::Blue => 4,
+            Color::Magenta => 5,
+            Color::Cyan => 6,
+            Color::White => 7,
+            Color::BrightBlack => 8,
+            Color::BrightRed => 9,
+            Color::BrightGreen => 10,
+            Color::BrightYellow => 11,
+            Color::BrightBlue => 12,
+            Color::BrightMagenta => 13,
+            Color::BrightCyan => 14,
+            Color::BrightWhite => 15,
+            _ => return None,
+        };
+        Some(index)
+    }
+
+    /// Named color for a palette index below 16
+    const fn from_ansi16_index(index: u8) -> Color {
+        const NAMED: [Color; 16] = [
+            Color::Black,
+            Color::Red,
+            Color::Green,
+            Color::Yellow,
+            Color::Blue,
+            Color::Magenta,
+            Color::Cyan,
+            Color::White,
+            Color::BrightBlack,
+            Color::BrightRed,
+            Color::BrightGreen,
+            Color::BrightYellow,
+            Color::BrightBlue,
+            Color::BrightMagenta,
+            Color::BrightCyan,
+            Color::BrightWhite,
+        ];
+        NAMED[index as usize & 15]
+    }
+}
+
+/// How many colors the terminal can display
+///
+/// Profiles are ordered from fewest to most colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ColorProfile {
+    /// No colors; text attributes like bold still apply
+    NoColor,
+    /// The 16 basic and bright colors
+    Ansi16,
+    /// The 256-color palette
+    Ansi256,
+    /// 24-bit RGB
+    #[default]
+    TrueColor,
+}
+
+impl ColorProfile {
+    /// Detect the profile of the terminal on stdout
+    ///
+    /// `FORCE_COLOR` wins over everything else (`0` disables colors, `1`,
+    /// `2` and `3` force 16, 256 and 24-bit colors, and any other value
+    /// enables at least 16 colors). Then colors are disabled when
+    /// [`Environment::should_disable_colors`] says so. Otherwise
+    /// `COLORTERM=truecolor`, the terminal program and `TERM` decide.
+    ///
+    /// [`Environment::should_disable_colors`]: crate::runtime::Environment::should_disable_colors
+    pub fn detect() -> Self {
+        Self::from_env(
+            |name| std::env::var(name).ok(),
+            Environment::detect().should_disable_colors(),
+        )
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>, colors_disabled: bool) -> Self {
+        match var("FORCE_COLOR").as_deref().map(str::trim) {
+            Some("0" | "false") => return ColorProfile::NoColor,
+            Some("1" | "true") => return ColorProfile::Ansi16,
+            Some("2") => return ColorProfile::Ansi256,
+            Some("3") => return ColorProfile::TrueColor,
+            Some(_) => return Self::from_terminal(&var).max(ColorProfile::Ansi16),
+            None => {}
+        }
+
+        if colors_disabled {
+            return ColorProfile::NoColor;
+        }
+        Self::from_terminal(&var)
+    }
+
+    /// Profile advertised by the terminal's own environment variables
+    fn from_terminal(var: &impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default().to_lowercase();
+        if term == "dumb" {
+            return ColorProfile::NoColor;
+        }
+
+        let colorterm = var("COLORTERM").unwrap_or_default().to_lowercase();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        if matches!(colorterm.as_str(), "truecolor" | "24bit")
+            || ["truecolor", "24bit", "direct"]
+                .iter()
+                .any(|t| term.contains(t))
+            || matches!(
+                program.as_str(),
+                "iTerm.app" | "WezTerm" | "vscode" | "ghostty"
+            )
+            || var("WT_SESSION").is_some()
+        {
+            ColorProfile::TrueColor
+        } else if term.contains("256color") {
+            ColorProfile::Ansi256
+        } else {
+            ColorProfile::Ansi16
+        }
+    }
+}
+
+/// Sentinel meaning the profile was never set
+const PROFILE_UNSET: u8 = u8::MAX;
+
+static COLOR_PROFILE: AtomicU8 = AtomicU8::new(PROFILE_UNSET);
+
+/// Set the color profile colors are rendered for, for the whole process
+///
+/// Apps set this when they start, from
+/// [`AppBuilder::color_profile`](crate::AppBuilder::color_profile) or
+/// [`ColorProfile::detect`].
+pub fn set_color_profile(profile: ColorProfile) {
+    COLOR_PROFILE.store(profile as u8, Ordering::Relaxed);
+}
+
+/// The color profile colors are rendered for
+///
+/// Defaults to [`ColorProfile::TrueColor`], which writes colors unchanged,
+/// until [`set_color_profile`] is called.
+pub fn color_profile() -> ColorProfile {
+    match COLOR_PROFILE.load(Ordering::Relaxed) {
+        x if x == ColorProfile::NoColor as u8 => ColorProfile::NoColor,
+        x if x == ColorProfile::Ansi16 as u8 => ColorProfile::Ansi16,
+        x if x == ColorProfile::Ansi256 as u8 => ColorProfile::Ansi256,
+        _ => ColorProfile::TrueColor,
+    }
+}
+
+/// Whether the terminal has a light or dark background
+///
+/// Decides which side of a [`Color::Adaptive`] is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TerminalBackground {
+    /// Dark text is readable
+    Light,
+    /// Light text is readable
+    #[default]
+    Dark,
+}
+
+impl TerminalBackground {
+    /// Classify a background color by its perceived lightness
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        if Lab::from_rgb(r, g, b).l > 50.0 {
+            TerminalBackground::Light
+        } else {
+            TerminalBackground::Dark
+        }
+    }
+
+    /// Read the background from a `COLORFGBG` value such as `"15;0"`
+    ///
+    /// The last field is the palette index of the background; white and the
+    /// bright colors other than bright black count as light.
+    pub fn from_colorfgbg(value: &str) -> Option<Self> {
+        let background: u8 = value.rsplit(';').next()?.trim().parse().ok()?;
+        Some(match background {
+            7 | 9..=15 => TerminalBackground::Light,
+            _ => TerminalBackground::Dark,
+        })
+    }
+}
+
+static TERMINAL_BACKGROUND: AtomicU8 = AtomicU8::new(TerminalBackground::Dark as u8);
+
+/// Set the terminal background adaptive colors resolve against
+///
+/// Apps detect it when they start unless it is set with
+/// [`AppBuilder::background`](crate::renderer::AppBuilder::background); see
+/// [`Terminal::detect_background`](crate::renderer::Terminal::detect_background).
+pub fn set_terminal_background(background: TerminalBackground) {
+    TERMINAL_BACKGROUND.store(background as u8, Ordering::Relaxed);
+}
+
+/// The terminal background adaptive colors resolve against
+///
+/// Defaults to [`TerminalBackground::Dark`] until it is detected or set.
+pub fn terminal_background() -> TerminalBackground {
+    match TERMINAL_BACKGROUND.load(Ordering::Relaxed) {
+        x if x == TerminalBackground::Light as u8 => TerminalBackground::Light,
+        _ => TerminalBackground::Dark,
+    }
+}
+
+/// RGB value of an xterm palette entry
+fn palette_rgb(code: u8) -> (u8, u8, u8) {
+    const BASE: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match code {
+        0..=15 => BASE[code as usize],
+        16..=231 => {
+            let index = code - 16;
+            (
+                CUBE[(index / 36) as usize],
+                CUBE[(index / 6 % 6) as usize],
+                CUBE[(index % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (code - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Palette index in `range` perceptually closest to a color
+fn nearest(target: Lab, range: std::ops::RangeInclusive<u8>) -> u8 {
+    static PALETTE: OnceLock<Vec<Lab>> = OnceLock::new();
+    let palette = PALETTE.get_or_init(|| {
+        (0..=255)
+            .map(|code| {
+                let (r, g, b) = palette_rgb(code);
+                Lab::from_rgb(r, g, b)
+            })
+            .collect()
+    });
+
+    range
+        .min_by(|&a, &b| {
+            target
+                .distance(&palette[a as usize])
+                .total_cmp(&target.distance(&palette[b as usize]))
+        })
+        .unwrap_or(0)
+}
+
+/// A color in CIELAB space, where distance follows perceived difference
+#[derive(Debug, Clone, Copy)]
+struct Lab {
+    l: f32,
+    a: f32,
+    b: f32,
+}
+
+impl Lab {
+    /// Convert from sRGB (D65 white point)
+    fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        fn linear(channel: u8) -> f32 {
+            let c = channel as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+        fn f(t: f32) -> f32 {
+            if t > 0.008856 {
+                t.cbrt()
+            } else {
+                7.787 * t + 16.0 / 116.0
+            }
+        }
+
+        let (r, g, b) = (linear(r), linear(g), linear(b));
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+
+        Self {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    /// Squared CIE76 distance
+    fn distance(&self, other: &Lab) -> f32 {
+        (self.l - other.l).powi(2) + (self.a - other.a).powi(2) + (self.b - other.b).powi(2)
+    }
 }
 
 impl From<Color> for CrosstermColor {
     fn from(color: Color) -> Self {
-        match color {
+        match color.resolve() {
             Color::Reset => CrosstermColor::Reset,
             Color::Black => CrosstermColor::Black,
             Color::Red => CrosstermColor::DarkRed,
@@ -116,6 +569,7 @@ impl From<Color> for CrosstermColor {
             Color::BrightWhite => CrosstermColor::White,
             Color::Ansi256(code) => CrosstermColor::AnsiValue(code),
             Color::Rgb(r, g, b) => CrosstermColor::Rgb { r, g, b },
+            Color::Adaptive(_) => CrosstermColor::Reset,
         }
     }
 }
@@ -163,6 +617,137 @@ mod tests {
         assert_eq!(Color::ansi256(196), Color::Ansi256(196));
     }
 
+    #[test]
+    fn test_downsample_to_256() {
+        let profile = ColorProfile::Ansi256;
+        // Exact cube entries map to themselves
+        assert_eq!(
+            Color::Rgb(255, 0, 0).downsample(profile),
+            Some(Color::Ansi256(196))
+        );
+        assert_eq!(
+            Color::Rgb(128, 128, 128).downsample(profile),
+            Some(Color::Ansi256(244))
+        );
+        assert_eq!(Color::Red.downsample(profile), Some(Color::Red));
+    }
+
+    #[test]
+    fn test_downsample_to_16() {
+        let profile = ColorProfile::Ansi16;
+        assert_eq!(
+            Color::Rgb(250, 10, 10).downsample(profile),
+            Some(Color::BrightRed)
+        );
+        assert_eq!(
+            Color::Rgb(20, 20, 30).downsample(profile),
+            Some(Color::Black)
+        );
+        assert_eq!(
+            Color::Ansi256(9).downsample(profile),
+            Some(Color::BrightRed)
+        );
+        assert_eq!(Color::Ansi256(22).downsample(profile), Some(Color::Green));
+        assert_eq!(Color::Rgb(1, 2, 3).downsample(ColorProfile::NoColor), None);
+    }
+
+    #[test]
+    fn test_color_profile_detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        let detect = |vars, disabled| ColorProfile::from_env(env(vars), disabled);
+        assert_eq!(
+            detect(&[("COLORTERM", "truecolor"), ("TERM", "xterm")], false),
+            ColorProfile::TrueColor
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color")], false),
+            ColorProfile::Ansi256
+        );
+        assert_eq!(detect(&[("TERM", "xterm")], false), ColorProfile::Ansi16);
+        assert_eq!(detect(&[("TERM", "dumb")], false), ColorProfile::NoColor);
+        assert_eq!(
+            detect(&[("COLORTERM", "truecolor")], true),
+            ColorProfile::NoColor
+        );
+        assert_eq!(
+            detect(&[("FORCE_COLOR", "1"), ("TERM", "dumb")], true),
+            ColorProfile::Ansi16
+        );
+        assert_eq!(
+            detect(
+                &[("FORCE_COLOR", "true"), ("COLORTERM", "truecolor")],
+                false
+            ),
+            ColorProfile::Ansi16
+        );
+        assert_eq!(
+            detect(&[("FORCE_COLOR", "yes"), ("COLORTERM", "truecolor")], true),
+            ColorProfile::TrueColor
+        );
+        assert_eq!(
+            detect(&[("FORCE_COLOR", "3"), ("NO_COLOR", "1")], true),
+            ColorProfile::TrueColor
+        );
+        assert_eq!(
+            detect(&[("FORCE_COLOR", "0"), ("COLORTERM", "truecolor")], false),
+            ColorProfile::NoColor
+        );
+    }
+
+    #[test]
+    fn test_adaptive_color_sides() {
+        let color = AdaptiveColor::new(Color::Black, Color::Rgb(250, 250, 250));
+        assert_eq!(color.light(), Color::Black);
+        assert_eq!(color.dark(), Color::Rgb(250, 250, 250));
+
+        // Nested adaptive colors contribute the matching side
+        let nested = AdaptiveColor::new(
+            Color::adaptive(Color::Blue, Color::Red),
+            Color::Ansi256(245),
+        );
+        assert_eq!(nested.light(), Color::Blue);
+        assert_eq!(nested.dark(), Color::Ansi256(245));
+    }
+
+    #[test]
+    fn test_adaptive_downsamples_resolved_side() {
+        let color = Color::adaptive(Color::Black, Color::Rgb(255, 0, 0));
+        // Rendering defaults to a dark background
+        assert_eq!(
+            color.downsample(ColorProfile::Ansi256),
+            Some(Color::Ansi256(196))
+        );
+        assert_eq!(color.to_rgb(), Some((255, 0, 0)));
+    }
+
+    #[test]
+    fn test_background_classification() {
+        assert_eq!(
+            TerminalBackground::from_rgb(255, 255, 255),
+            TerminalBackground::Light
+        );
+        assert_eq!(
+            TerminalBackground::from_rgb(30, 30, 30),
+            TerminalBackground::Dark
+        );
+        assert_eq!(
+            TerminalBackground::from_colorfgbg("0;15"),
+            Some(TerminalBackground::Light)
+        );
+        assert_eq!(
+            TerminalBackground::from_colorfgbg("15;default;0"),
+            Some(TerminalBackground::Dark)
+        );
+        assert_eq!(TerminalBackground::from_colorfgbg("15;default"), None);
+    }
+
     #[test]
     fn test_crossterm_conversion() {
         let color = Color::Green;
@@ -1,6 +1,6 @@
 //! Element types for the UI tree
 
-use crate::core::Style;
+use crate::core::{Style, TextDirection};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Global element ID counter
@@ -33,6 +33,35 @@ impl Default for ElementId {
     }
 }
 
+/// Global element ref ID counter
+static ELEMENT_REF_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Identifier of a ref attached to an element
+///
+/// A ref such as [`ElementRef`](crate::hooks::ElementRef) tags the element
+/// it tracks with its ID; after layout the renderer reports the tagged
+/// element back to the ref.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ElementRefId(u64);
+
+impl ElementRefId {
+    /// Create a new unique element ref ID
+    pub fn new() -> Self {
+        Self(ELEMENT_REF_ID_COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Get the raw ID value
+    pub fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for ElementRefId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Element type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElementType {
@@ -44,6 +73,8 @@ pub enum ElementType {
     Text,
     /// Virtual text (nested inside Text)
     VirtualText,
+    /// Portal whose children render in the overlay layer
+    Portal,
 }
 
 /// Children container
@@ -137,6 +168,8 @@ pub struct Element {
     pub scroll_offset_x: Option<u16>,
     /// Vertical scroll offset (for overflow: scroll/hidden)
     pub scroll_offset_y: Option<u16>,
+    /// Ref updated with this element's layout after each render
+    pub element_ref: Option<ElementRefId>,
 }
 
 /// Clone implementation for Element.
@@ -160,6 +193,7 @@ impl Clone for Element {
             key: self.key.clone(),
             scroll_offset_x: self.scroll_offset_x,
             scroll_offset_y: self.scroll_offset_y,
+            element_ref: self.element_ref,
         }
     }
 }
@@ -177,6 +211,7 @@ impl Element {
             key: None,
             scroll_offset_x: None,
             scroll_offset_y: None,
+            element_ref: None,
         }
     }
 
@@ -192,6 +227,7 @@ impl Element {
             key: None,
             scroll_offset_x: None,
             scroll_offset_y: None,
+            element_ref: None,
         }
     }
 
@@ -207,6 +243,15 @@ impl Element {
         element
     }
 
+    /// Create a portal element
+    ///
+    /// A portal is laid out against the root element instead of its parent
+    /// and painted above the rest of the tree, unaffected by the clipping of
+    /// its ancestors.
+    pub fn portal() -> Self {
+        Self::new(ElementType::Portal)
+    }
+
     /// Set the element key
     pub fn with_key(mut self, key: impl Into<String>) -> Self {
         self.key = Some(key.into());
@@ -240,6 +285,37 @@ impl Element {
     pub fn get_text(&self) -> Option<&str> {
         self.text_content.as_deref()
     }
+
+    /// Pass a text direction down to descendants that don't set their own
+    pub(crate) fn inherit_direction(&mut self, direction: TextDirection) {
+        for child in self.children.iter_mut() {
+            if child.style.direction.is_none() {
+                child.style.direction = Some(direction);
+                child.inherit_direction(direction);
+            }
+        }
+    }
+
+    /// Text pieces paired with the style each is painted in
+    ///
+    /// Lines of rich text are separated by `"\n"` pieces, so the result can be
+    /// handed to [`layout_text`](crate::layout::layout_text) as spans.
+    pub fn text_pieces(&self) -> Vec<(&str, &Style)> {
+        if let Some(lines) = &self.spans {
+            let mut pieces = Vec::new();
+            for (index, line) in lines.iter().enumerate() {
+                if index > 0 {
+                    pieces.push(("\n", &self.style));
+                }
+                pieces.extend(line.spans.iter().map(|s| (s.content.as_str(), &s.style)));
+            }
+            pieces
+        } else if let Some(text) = &self.text_content {
+            vec![(text.as_str(), &self.style)]
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 impl Default for Element {
@@ -4,9 +4,13 @@ mod color;
 mod element;
 mod style;
 
-pub use color::Color;
-pub use element::{Children, Element, ElementId, ElementType};
+pub use color::{
+    AdaptiveColor, Color, ColorProfile, TerminalBackground, color_profile, set_color_profile,
+    set_terminal_background, terminal_background,
+};
+pub use element::{Children, Element, ElementId, ElementRefId, ElementType};
 pub use style::{
-    AlignItems, AlignSelf, BorderStyle, Dimension, Display, Edges, FlexDirection, JustifyContent,
-    Overflow, Position, Style, TextWrap,
+    AlignItems, AlignSelf, BorderStyle, DEFAULT_ELLIPSIS, Dimension, Display, Edges, FlexDirection,
+    GridLine, GridPlacement, GridRepeat, GridTemplate, GridTrack, JustifyContent, Overflow,
+    Position, Style, TextDirection, TextWrap, TrackSize,
 };
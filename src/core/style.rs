@@ -2,6 +2,9 @@
 
 use crate::core::Color;
 
+/// Ellipsis used by truncating text when none is set
+pub const DEFAULT_ELLIPSIS: &str = "…";
+
 /// Flex direction
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum FlexDirection {
@@ -101,6 +104,7 @@ impl From<JustifyContent> for taffy::JustifyContent {
 pub enum Display {
     #[default]
     Flex,
+    Grid,
     None,
 }
 
@@ -108,6 +112,7 @@ impl From<Display> for taffy::Display {
     fn from(display: Display) -> Self {
         match display {
             Display::Flex => taffy::Display::Flex,
+            Display::Grid => taffy::Display::Grid,
             Display::None => taffy::Display::None,
         }
     }
@@ -150,7 +155,7 @@ impl From<Overflow> for taffy::Overflow {
 }
 
 /// Text wrapping behavior
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum TextWrap {
     #[default]
     Wrap,
@@ -160,6 +165,18 @@ pub enum TextWrap {
     TruncateEnd,
 }
 
+/// Base direction of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    /// Left to right
+    Ltr,
+    /// Right to left
+    Rtl,
+    /// Taken from the first strong character of the text
+    #[default]
+    Auto,
+}
+
 /// Border style
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum BorderStyle {
@@ -205,20 +222,71 @@ impl BorderStyle {
 }
 
 /// Dimension type for width/height
+///
+/// `MinContent`, `MaxContent`, `FitContent` and `Calc` have no Taffy
+/// equivalent; [`Style::to_taffy`] maps them to `Auto` (or to the percentage
+/// part of a `Calc`) and the layout engine resolves them to fixed sizes
+/// before the final layout pass. On `flex_basis` they keep that
+/// approximation.
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum Dimension {
     #[default]
     Auto,
     Points(f32),
     Percent(f32),
+    /// Smallest size that fits the content without overflowing
+    MinContent,
+    /// Size the content takes without any wrapping
+    MaxContent,
+    /// `MaxContent`, but no more than the given number of cells
+    /// (and no less than `MinContent`)
+    FitContent(f32),
+    /// A percentage of the parent's content box plus a number of cells
+    Calc {
+        percent: f32,
+        points: f32,
+    },
+}
+
+impl Dimension {
+    /// `calc(percent% + points)`; use a negative `points` to subtract
+    ///
+    /// `Dimension::calc(100.0, -2.0)` is the full parent size minus two cells.
+    pub fn calc(percent: f32, points: f32) -> Self {
+        Dimension::Calc { percent, points }
+    }
+
+    /// Whether the layout engine resolves this dimension instead of Taffy
+    pub fn is_deferred(&self) -> bool {
+        matches!(
+            self,
+            Dimension::MinContent
+                | Dimension::MaxContent
+                | Dimension::FitContent(_)
+                | Dimension::Calc { .. }
+        )
+    }
+
+    /// Whether this dimension depends on the size of the content
+    pub fn is_intrinsic(&self) -> bool {
+        matches!(
+            self,
+            Dimension::MinContent | Dimension::MaxContent | Dimension::FitContent(_)
+        )
+    }
 }
 
 impl From<Dimension> for taffy::Dimension {
     fn from(dim: Dimension) -> Self {
         match dim {
-            Dimension::Auto => taffy::Dimension::Auto,
+            Dimension::Auto
+            | Dimension::MinContent
+            | Dimension::MaxContent
+            | Dimension::FitContent(_) => taffy::Dimension::Auto,
             Dimension::Points(v) => taffy::Dimension::Length(v),
-            Dimension::Percent(v) => taffy::Dimension::Percent(v / 100.0),
+            Dimension::Percent(v) | Dimension::Calc { percent: v, .. } => {
+                taffy::Dimension::Percent(v / 100.0)
+            }
         }
     }
 }
@@ -241,6 +309,257 @@ impl From<f32> for Dimension {
     }
 }
 
+/// Size of a grid track, or one bound of a `minmax` track
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TrackSize {
+    #[default]
+    Auto,
+    Points(f32),
+    Percent(f32),
+    /// Share of the free space (CSS `fr`)
+    Fr(f32),
+    MinContent,
+    MaxContent,
+}
+
+impl From<TrackSize> for taffy::MinTrackSizingFunction {
+    fn from(size: TrackSize) -> Self {
+        match size {
+            // Flexible minimums are invalid in CSS and behave like `auto`
+            TrackSize::Auto | TrackSize::Fr(_) => taffy::MinTrackSizingFunction::Auto,
+            TrackSize::Points(v) => {
+                taffy::MinTrackSizingFunction::Fixed(taffy::LengthPercentage::Length(v))
+            }
+            TrackSize::Percent(v) => {
+                taffy::MinTrackSizingFunction::Fixed(taffy::LengthPercentage::Percent(v / 100.0))
+            }
+            TrackSize::MinContent => taffy::MinTrackSizingFunction::MinContent,
+            TrackSize::MaxContent => taffy::MinTrackSizingFunction::MaxContent,
+        }
+    }
+}
+
+impl From<TrackSize> for taffy::MaxTrackSizingFunction {
+    fn from(size: TrackSize) -> Self {
+        match size {
+            TrackSize::Auto => taffy::MaxTrackSizingFunction::Auto,
+            TrackSize::Points(v) => {
+                taffy::MaxTrackSizingFunction::Fixed(taffy::LengthPercentage::Length(v))
+            }
+            TrackSize::Percent(v) => {
+                taffy::MaxTrackSizingFunction::Fixed(taffy::LengthPercentage::Percent(v / 100.0))
+            }
+            TrackSize::Fr(v) => taffy::MaxTrackSizingFunction::Fraction(v),
+            TrackSize::MinContent => taffy::MaxTrackSizingFunction::MinContent,
+            TrackSize::MaxContent => taffy::MaxTrackSizingFunction::MaxContent,
+        }
+    }
+}
+
+/// A grid column or row, sized between `min` and `max`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct GridTrack {
+    pub min: TrackSize,
+    pub max: TrackSize,
+}
+
+impl GridTrack {
+    /// Track sized by its content
+    pub fn auto() -> Self {
+        TrackSize::Auto.into()
+    }
+
+    /// Fixed track of `value` cells
+    pub fn points(value: f32) -> Self {
+        TrackSize::Points(value).into()
+    }
+
+    /// Track sized as a percentage of the container
+    pub fn percent(value: f32) -> Self {
+        TrackSize::Percent(value).into()
+    }
+
+    /// Flexible track taking `value` shares of the free space
+    pub fn fr(value: f32) -> Self {
+        TrackSize::Fr(value).into()
+    }
+
+    /// Track as narrow as its content allows
+    pub fn min_content() -> Self {
+        TrackSize::MinContent.into()
+    }
+
+    /// Track as wide as its content wants
+    pub fn max_content() -> Self {
+        TrackSize::MaxContent.into()
+    }
+
+    /// Track sized between `min` and `max` (CSS `minmax()`)
+    pub fn minmax(min: TrackSize, max: TrackSize) -> Self {
+        Self { min, max }
+    }
+}
+
+impl From<TrackSize> for GridTrack {
+    fn from(size: TrackSize) -> Self {
+        Self {
+            min: size,
+            max: size,
+        }
+    }
+}
+
+impl From<GridTrack> for taffy::NonRepeatedTrackSizingFunction {
+    fn from(track: GridTrack) -> Self {
+        taffy::MinMax {
+            min: track.min.into(),
+            max: track.max.into(),
+        }
+    }
+}
+
+/// How many times a `repeat()` entry repeats its tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridRepeat {
+    Count(u16),
+    /// As many repetitions as fit in the container
+    AutoFill,
+    /// Like `AutoFill`, but empty repetitions collapse
+    AutoFit,
+}
+
+impl From<GridRepeat> for taffy::GridTrackRepetition {
+    fn from(repeat: GridRepeat) -> Self {
+        match repeat {
+            GridRepeat::Count(n) => taffy::GridTrackRepetition::Count(n),
+            GridRepeat::AutoFill => taffy::GridTrackRepetition::AutoFill,
+            GridRepeat::AutoFit => taffy::GridTrackRepetition::AutoFit,
+        }
+    }
+}
+
+/// One entry of a grid template: a track or a repeated group of tracks
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridTemplate {
+    Track(GridTrack),
+    Repeat(GridRepeat, Vec<GridTrack>),
+}
+
+impl GridTemplate {
+    /// Repeat `tracks` `count` times (CSS `repeat(count, ...)`)
+    pub fn repeat(count: u16, tracks: impl IntoIterator<Item = impl Into<GridTrack>>) -> Self {
+        GridTemplate::Repeat(
+            GridRepeat::Count(count),
+            tracks.into_iter().map(Into::into).collect(),
+        )
+    }
+
+    /// Repeat `tracks` as often as they fit (CSS `repeat(auto-fill, ...)`)
+    pub fn auto_fill(tracks: impl IntoIterator<Item = impl Into<GridTrack>>) -> Self {
+        GridTemplate::Repeat(
+            GridRepeat::AutoFill,
+            tracks.into_iter().map(Into::into).collect(),
+        )
+    }
+
+    /// Repeat `tracks` as often as they fit, collapsing empty ones
+    /// (CSS `repeat(auto-fit, ...)`)
+    pub fn auto_fit(tracks: impl IntoIterator<Item = impl Into<GridTrack>>) -> Self {
+        GridTemplate::Repeat(
+            GridRepeat::AutoFit,
+            tracks.into_iter().map(Into::into).collect(),
+        )
+    }
+}
+
+impl From<GridTrack> for GridTemplate {
+    fn from(track: GridTrack) -> Self {
+        GridTemplate::Track(track)
+    }
+}
+
+impl From<TrackSize> for GridTemplate {
+    fn from(size: TrackSize) -> Self {
+        GridTemplate::Track(size.into())
+    }
+}
+
+impl From<&GridTemplate> for taffy::TrackSizingFunction {
+    fn from(template: &GridTemplate) -> Self {
+        match template {
+            GridTemplate::Track(track) => taffy::TrackSizingFunction::Single((*track).into()),
+            GridTemplate::Repeat(repeat, tracks) => taffy::TrackSizingFunction::Repeat(
+                (*repeat).into(),
+                tracks.iter().map(|track| (*track).into()).collect(),
+            ),
+        }
+    }
+}
+
+/// Start or end of a grid item's placement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GridPlacement {
+    /// Placed by the auto-placement algorithm
+    #[default]
+    Auto,
+    /// Grid line number, starting at 1; negative numbers count from the end
+    Line(i16),
+    /// Span this many tracks
+    Span(u16),
+}
+
+impl From<GridPlacement> for taffy::GridPlacement {
+    fn from(placement: GridPlacement) -> Self {
+        match placement {
+            GridPlacement::Auto => taffy::GridPlacement::Auto,
+            GridPlacement::Line(line) => taffy::GridPlacement::Line(line.into()),
+            GridPlacement::Span(span) => taffy::GridPlacement::Span(span),
+        }
+    }
+}
+
+/// Placement of a grid item along one axis (CSS `grid-column` / `grid-row`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GridLine {
+    pub start: GridPlacement,
+    pub end: GridPlacement,
+}
+
+impl GridLine {
+    pub fn new(start: GridPlacement, end: GridPlacement) -> Self {
+        Self { start, end }
+    }
+
+    /// Occupy the single track after line `line`
+    pub fn line(line: i16) -> Self {
+        Self::new(GridPlacement::Line(line), GridPlacement::Auto)
+    }
+
+    /// Occupy the tracks from line `start` up to line `end`
+    pub fn range(start: i16, end: i16) -> Self {
+        Self::new(GridPlacement::Line(start), GridPlacement::Line(end))
+    }
+
+    /// Span `span` tracks, placed automatically
+    pub fn span(span: u16) -> Self {
+        Self::new(GridPlacement::Span(span), GridPlacement::Auto)
+    }
+
+    /// Span `span` tracks starting at line `start`
+    pub fn line_span(start: i16, span: u16) -> Self {
+        Self::new(GridPlacement::Line(start), GridPlacement::Span(span))
+    }
+}
+
+impl From<GridLine> for taffy::Line<taffy::GridPlacement> {
+    fn from(line: GridLine) -> Self {
+        taffy::Line {
+            start: line.start.into(),
+            end: line.end.into(),
+        }
+    }
+}
+
 /// Edge values for padding/margin
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Edges {
@@ -329,6 +648,12 @@ pub struct Style {
     pub align_self: AlignSelf,
     pub justify_content: JustifyContent,
 
+    // Grid
+    pub grid_template_columns: Vec<GridTemplate>,
+    pub grid_template_rows: Vec<GridTemplate>,
+    pub grid_column: GridLine,
+    pub grid_row: GridLine,
+
     // Spacing
     pub padding: Edges,
     pub margin: Edges,
@@ -343,6 +668,8 @@ pub struct Style {
     pub min_height: Dimension,
     pub max_width: Dimension,
     pub max_height: Dimension,
+    /// Width divided by height, in cells
+    pub aspect_ratio: Option<f32>,
 
     // Border
     pub border_style: BorderStyle,
@@ -369,11 +696,18 @@ pub struct Style {
     pub dim: bool,
     pub inverse: bool,
     pub text_wrap: TextWrap,
+    /// Marks truncated text; `None` uses "…"
+    pub text_ellipsis: Option<String>,
+    /// Base text direction; `None` inherits from the enclosing box
+    pub direction: Option<TextDirection>,
 
     // Overflow
     pub overflow_x: Overflow,
     pub overflow_y: Overflow,
 
+    // Stacking (paint order within the parent's stacking context; 0 paints with the parent)
+    pub z_index: i32,
+
     // Static output marker (internal use)
     #[doc(hidden)]
     pub is_static: bool,
@@ -426,6 +760,10 @@ impl Style {
             align_items: Some(self.align_items.into()),
             align_self: self.align_self.into(),
             justify_content: Some(self.justify_content.into()),
+            grid_template_columns: self.grid_template_columns.iter().map(Into::into).collect(),
+            grid_template_rows: self.grid_template_rows.iter().map(Into::into).collect(),
+            grid_column: self.grid_column.into(),
+            grid_row: self.grid_row.into(),
             padding: taffy::Rect {
                 top: taffy::LengthPercentage::Length(self.padding.top),
                 right: taffy::LengthPercentage::Length(self.padding.right),
@@ -454,6 +792,7 @@ impl Style {
                 width: self.max_width.into(),
                 height: self.max_height.into(),
             },
+            aspect_ratio: self.aspect_ratio,
             border: if self.border_style.is_visible() {
                 taffy::Rect {
                     top: taffy::LengthPercentage::Length(if self.border_top { 1.0 } else { 0.0 }),
@@ -480,6 +819,39 @@ impl Style {
         }
     }
 
+    /// Whether any size needs resolving by the layout engine
+    ///
+    /// See [`Dimension::is_deferred`].
+    pub fn has_deferred_size(&self) -> bool {
+        [
+            self.width,
+            self.height,
+            self.min_width,
+            self.min_height,
+            self.max_width,
+            self.max_height,
+        ]
+        .iter()
+        .any(Dimension::is_deferred)
+    }
+
+    /// Ellipsis that marks truncated text
+    pub fn ellipsis(&self) -> &str {
+        self.text_ellipsis.as_deref().unwrap_or(DEFAULT_ELLIPSIS)
+    }
+
+    /// Cells taken by border and padding as (top, right, bottom, left)
+    pub fn content_insets(&self) -> (f32, f32, f32, f32) {
+        let visible = self.border_style.is_visible();
+        let border = |side: bool| if visible && side { 1.0 } else { 0.0 };
+        (
+            border(self.border_top) + self.padding.top,
+            border(self.border_right) + self.padding.right,
+            border(self.border_bottom) + self.padding.bottom,
+            border(self.border_left) + self.padding.left,
+        )
+    }
+
     /// Check if element has visible border
     pub fn has_border(&self) -> bool {
         self.border_style.is_visible()
@@ -543,4 +915,57 @@ mod tests {
         let dim: Dimension = 20i32.into();
         assert_eq!(dim, Dimension::Points(20.0));
     }
+
+    #[test]
+    fn test_grid_to_taffy() {
+        let style = Style {
+            display: Display::Grid,
+            grid_template_columns: vec![
+                GridTrack::points(10.0).into(),
+                GridTemplate::repeat(2, [GridTrack::fr(1.0)]),
+                GridTrack::minmax(TrackSize::Points(5.0), TrackSize::Fr(2.0)).into(),
+            ],
+            grid_column: GridLine::line_span(2, 2),
+            ..Style::new()
+        };
+        let taffy_style = style.to_taffy();
+
+        assert_eq!(taffy_style.display, taffy::Display::Grid);
+        assert_eq!(taffy_style.grid_template_columns.len(), 3);
+        assert_eq!(
+            taffy_style.grid_template_columns[1],
+            taffy::TrackSizingFunction::Repeat(
+                taffy::GridTrackRepetition::Count(2),
+                vec![taffy::MinMax {
+                    min: taffy::MinTrackSizingFunction::Auto,
+                    max: taffy::MaxTrackSizingFunction::Fraction(1.0),
+                }],
+            )
+        );
+        assert_eq!(
+            taffy_style.grid_template_columns[2],
+            taffy::TrackSizingFunction::Single(taffy::MinMax {
+                min: taffy::MinTrackSizingFunction::Fixed(taffy::LengthPercentage::Length(5.0)),
+                max: taffy::MaxTrackSizingFunction::Fraction(2.0),
+            })
+        );
+        assert_eq!(taffy_style.grid_column.end, taffy::GridPlacement::Span(2));
+    }
+
+    #[test]
+    fn test_deferred_dimensions_to_taffy() {
+        let style = Style {
+            width: Dimension::calc(100.0, -2.0),
+            height: Dimension::MaxContent,
+            aspect_ratio: Some(2.0),
+            ..Style::new()
+        };
+        assert!(style.has_deferred_size());
+        assert!(!Style::new().has_deferred_size());
+
+        let taffy_style = style.to_taffy();
+        assert_eq!(taffy_style.size.width, taffy::Dimension::Percent(1.0));
+        assert_eq!(taffy_style.size.height, taffy::Dimension::Auto);
+        assert_eq!(taffy_style.aspect_ratio, Some(2.0));
+    }
 }
@@ -1,15 +1,23 @@
 //! Hook context management
 
-use std::any::Any;
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 // Import Cmd type for command queue
 use crate::cmd::Cmd;
+use crate::core::Element;
 
 /// Callback type for triggering re-renders
 pub type RenderCallback = Rc<dyn Fn()>;
 
+/// Thread-safe render callback, for handles that can be moved into commands
+/// and other threads (such as [`Dispatch`](crate::hooks::Dispatch))
+pub(crate) type SyncRenderCallback = Arc<dyn Fn() + Send + Sync>;
+
 /// Effect callback type that returns an optional cleanup function
 pub type EffectCallback = Box<dyn FnOnce() -> Option<Box<dyn FnOnce()>>>;
 
@@ -42,18 +50,112 @@ pub struct Effect {
     pub deps: Option<Vec<u64>>, // Hash of dependencies
 }
 
+/// Identity of a provided context value
+///
+/// Each [`Context`](crate::hooks::Context) gets its own id, so two contexts
+/// carrying the same value type never shadow each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContextId(ContextIdRepr);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ContextIdRepr {
+    Unique(u64),
+    Type(TypeId),
+}
+
+impl ContextId {
+    /// Allocate an id no other context shares
+    pub fn unique() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(ContextIdRepr::Unique(
+            NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        ))
+    }
+
+    /// The id for values identified by their type alone
+    pub fn of<T: 'static>() -> Self {
+        Self(ContextIdRepr::Type(TypeId::of::<T>()))
+    }
+}
+
+/// Slot identifying a child component within its parent
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ComponentSlot {
+    /// Explicit key supplied by the caller
+    Keyed(String),
+    /// N-th unkeyed component of the same type in this render
+    Index(usize),
+}
+
+/// Identity of a component's render function
+///
+/// Render functions are told apart by type. Plain `fn` pointers all share
+/// one type, so they are also told apart by address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComponentId {
+    type_id: TypeId,
+    addr: usize,
+}
+
+impl ComponentId {
+    /// Identify a component by a type, such as a built-in wrapper component
+    pub fn of<T: 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            addr: 0,
+        }
+    }
+
+    /// Identify a component by its render function
+    pub fn of_render<P, F>(render: &F) -> Self
+    where
+        P: 'static,
+        F: Fn(&P) -> Element + 'static,
+    {
+        let addr = (render as &dyn Any)
+            .downcast_ref::<fn(&P) -> Element>()
+            .map_or(0, |f| *f as usize);
+        Self {
+            type_id: TypeId::of::<F>(),
+            addr,
+        }
+    }
+}
+
+/// Identity of a child component instance within its parent
+///
+/// Components are identified by their render function plus either their
+/// key or their position among siblings rendered by the same function.
+/// Conditionally rendering one component therefore never shifts the hook
+/// state of a different component.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ComponentKey {
+    id: ComponentId,
+    slot: ComponentSlot,
+}
+
 /// Hook context for a component
 pub struct HookContext {
     /// Hook values storage
     hooks: Vec<HookStorage>,
     /// Current hook index during render
     hook_index: usize,
-    /// Effects to run after render
-    effects: Vec<Effect>,
-    /// Cleanup functions from previous effects
-    cleanups: Vec<Option<Box<dyn FnOnce()>>>,
+    /// Effects to run after render, tagged with their hook slot
+    effects: Vec<(usize, Effect)>,
+    /// Cleanup functions from previous effects, keyed by hook slot
+    cleanups: HashMap<usize, Box<dyn FnOnce()>>,
+    /// Mounted child component instances
+    children: HashMap<ComponentKey, Rc<RefCell<HookContext>>>,
+    /// Child components rendered during the current render cycle
+    rendered_children: HashSet<ComponentKey>,
+    /// Per-component counters for unkeyed child components
+    child_ordinals: HashMap<ComponentId, usize>,
+    /// Context values provided by enclosing providers
+    provided: HashMap<ContextId, Rc<dyn Any>>,
     /// Callback to trigger re-render
     render_callback: Option<RenderCallback>,
+    /// Thread-safe form of `render_callback`, if the app provided one
+    sync_render_callback: Option<SyncRenderCallback>,
     /// Flag indicating if context is being rendered
     is_rendering: bool,
     /// Commands to execute after render
@@ -67,8 +169,13 @@ impl HookContext {
             hooks: Vec::new(),
             hook_index: 0,
             effects: Vec::new(),
-            cleanups: Vec::new(),
+            cleanups: HashMap::new(),
+            children: HashMap::new(),
+            rendered_children: HashSet::new(),
+            child_ordinals: HashMap::new(),
+            provided: HashMap::new(),
             render_callback: None,
+            sync_render_callback: None,
             is_rendering: false,
             cmd_queue: Vec::new(),
         }
@@ -77,6 +184,21 @@ impl HookContext {
     /// Set the render callback
     pub fn set_render_callback(&mut self, callback: RenderCallback) {
         self.render_callback = Some(callback);
+        self.sync_render_callback = None;
+    }
+
+    /// Set a thread-safe render callback
+    ///
+    /// It also serves as the regular render callback.
+    pub(crate) fn set_sync_render_callback(&mut self, callback: SyncRenderCallback) {
+        let wrapped = callback.clone();
+        self.render_callback = Some(Rc::new(move || wrapped()));
+        self.sync_render_callback = Some(callback);
+    }
+
+    /// Get the thread-safe render callback
+    pub(crate) fn get_sync_render_callback(&self) -> Option<SyncRenderCallback> {
+        self.sync_render_callback.clone()
     }
 
     /// Get the render callback
@@ -88,6 +210,8 @@ impl HookContext {
     pub fn begin_render(&mut self) {
         self.hook_index = 0;
         self.effects.clear();
+        self.rendered_children.clear();
+        self.child_ordinals.clear();
         self.is_rendering = true;
     }
 
@@ -96,6 +220,11 @@ impl HookContext {
         self.is_rendering = false;
     }
 
+    /// Index of the slot the next [`use_hook`](Self::use_hook) call allocates
+    pub fn hook_index(&self) -> usize {
+        self.hook_index
+    }
+
     /// Get or create a hook at the current index
     pub fn use_hook<T: Clone + 'static, F: FnOnce() -> T>(&mut self, init: F) -> HookStorage {
         let index = self.hook_index;
@@ -113,22 +242,130 @@ impl HookContext {
     }
 
     /// Add an effect to run after render
-    pub fn add_effect(&mut self, effect: Effect) {
-        self.effects.push(effect);
+    ///
+    /// `slot` is the hook slot that owns the effect, so its cleanup only runs
+    /// when that same effect re-runs or the component unmounts.
+    pub fn add_effect(&mut self, slot: usize, effect: Effect) {
+        self.effects.push((slot, effect));
     }
 
     /// Run all pending effects
     pub fn run_effects(&mut self) {
-        // Run cleanup functions from previous render
-        for cleanup_fn in self.cleanups.drain(..).flatten() {
-            cleanup_fn();
+        let effects = std::mem::take(&mut self.effects);
+        for (slot, effect) in effects {
+            // Clean up the previous run of this effect before re-running it
+            if let Some(cleanup_fn) = self.cleanups.remove(&slot) {
+                cleanup_fn();
+            }
+
+            if let Some(cleanup) = (effect.callback)() {
+                self.cleanups.insert(slot, cleanup);
+            }
         }
+    }
 
-        // Run new effects and collect cleanup functions
-        let effects = std::mem::take(&mut self.effects);
-        for effect in effects {
-            let cleanup = (effect.callback)();
-            self.cleanups.push(cleanup);
+    /// Get or create the hook context of a child component
+    ///
+    /// `id` identifies the component's render function and `key` is the
+    /// optional reconciliation key. Children that are not requested again
+    /// during a render cycle are unmounted when the render finishes.
+    pub fn child_context(
+        &mut self,
+        id: ComponentId,
+        key: Option<&str>,
+    ) -> Rc<RefCell<HookContext>> {
+        let slot = match key {
+            Some(key) => ComponentSlot::Keyed(key.to_string()),
+            None => {
+                let ordinal = self.child_ordinals.entry(id).or_insert(0);
+                let slot = ComponentSlot::Index(*ordinal);
+                *ordinal += 1;
+                slot
+            }
+        };
+        let component_key = ComponentKey { id, slot };
+        self.rendered_children.insert(component_key.clone());
+
+        let render_callback = self.render_callback.clone();
+        let sync_render_callback = self.sync_render_callback.clone();
+        let child = self
+            .children
+            .entry(component_key)
+            .or_insert_with(|| {
+                let mut child = HookContext::new();
+                child.render_callback = render_callback;
+                child.sync_render_callback = sync_render_callback;
+                Rc::new(RefCell::new(child))
+            })
+            .clone();
+
+        // The child sees whatever its providers offer at this point in the tree
+        child.borrow_mut().provided = self.provided.clone();
+        child
+    }
+
+    /// Provide a context value to everything rendered from now on
+    ///
+    /// Returns the value it shadows so the caller can restore it with
+    /// [`restore_provided`](Self::restore_provided) once its subtree is done.
+    pub fn provide(&mut self, id: ContextId, value: Rc<dyn Any>) -> Option<Rc<dyn Any>> {
+        self.provided.insert(id, value)
+    }
+
+    /// Restore a context value shadowed by [`provide`](Self::provide)
+    pub fn restore_provided(&mut self, id: ContextId, previous: Option<Rc<dyn Any>>) {
+        match previous {
+            Some(value) => {
+                self.provided.insert(id, value);
+            }
+            None => {
+                self.provided.remove(&id);
+            }
+        }
+    }
+
+    /// Get the nearest provided value of the given context
+    pub fn provided(&self, id: ContextId) -> Option<Rc<dyn Any>> {
+        self.provided.get(&id).cloned()
+    }
+
+    /// Remove child components that were not rendered in the last cycle
+    fn take_stale_children(&mut self) -> Vec<Rc<RefCell<HookContext>>> {
+        let stale: Vec<ComponentKey> = self
+            .children
+            .keys()
+            .filter(|key| !self.rendered_children.contains(*key))
+            .cloned()
+            .collect();
+
+        stale
+            .into_iter()
+            .filter_map(|key| self.children.remove(&key))
+            .collect()
+    }
+
+    /// Number of currently mounted child components
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+
+    /// Unmount this context
+    ///
+    /// Runs every outstanding effect cleanup, discards pending effects and
+    /// recursively unmounts all child components.
+    pub fn unmount(&mut self) {
+        self.effects.clear();
+
+        for (_, child) in self.children.drain() {
+            child.borrow_mut().unmount();
+        }
+
+        let mut slots: Vec<usize> = self.cleanups.keys().copied().collect();
+        slots.sort_unstable();
+        for slot in slots {
+            if let Some(cleanup_fn) = self.cleanups.remove(&slot) {
+                cleanup_fn();
+            }
         }
     }
 
@@ -166,15 +403,36 @@ pub fn current_context() -> Option<Rc<RefCell<HookContext>>> {
     CURRENT_CONTEXT.with(|ctx| ctx.borrow().clone())
 }
 
+/// Restores the previously active hook context when dropped
+///
+/// This keeps nested component renders (and unwinding panics) from leaving
+/// a stale context installed.
+struct ContextGuard {
+    previous: Option<Rc<RefCell<HookContext>>>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_CONTEXT.with(|current| {
+            *current.borrow_mut() = previous;
+        });
+    }
+}
+
 /// Run a function with a hook context
+///
+/// Calls may be nested: the previously active context is restored when the
+/// inner render finishes, which is how child components get their own hook
+/// state.
 pub fn with_hooks<F, R>(ctx: Rc<RefCell<HookContext>>, f: F) -> R
 where
     F: FnOnce() -> R,
 {
-    // Set the current context
-    CURRENT_CONTEXT.with(|current| {
-        *current.borrow_mut() = Some(ctx.clone());
-    });
+    // Set the current context, remembering the enclosing one
+    let _guard = ContextGuard {
+        previous: CURRENT_CONTEXT.with(|current| current.borrow_mut().replace(ctx.clone())),
+    };
 
     // Begin render
     ctx.borrow_mut().begin_render();
@@ -185,14 +443,15 @@ where
     // End render
     ctx.borrow_mut().end_render();
 
+    // Unmount children that were not rendered this time
+    let stale = ctx.borrow_mut().take_stale_children();
+    for child in stale {
+        child.borrow_mut().unmount();
+    }
+
     // Run effects
     ctx.borrow_mut().run_effects();
 
-    // Clear the current context
-    CURRENT_CONTEXT.with(|current| {
-        *current.borrow_mut() = None;
-    });
-
     result
 }
 
@@ -250,4 +509,71 @@ mod tests {
 
         assert_eq!(result, 42);
     }
+
+    #[test]
+    fn test_with_hooks_nested_restores_parent() {
+        let parent = Rc::new(RefCell::new(HookContext::new()));
+
+        with_hooks(parent.clone(), || {
+            let child = current_context()
+                .unwrap()
+                .borrow_mut()
+                .child_context(ComponentId::of::<()>(), None);
+            with_hooks(child.clone(), || {
+                assert!(Rc::ptr_eq(&current_context().unwrap(), &child));
+            });
+            assert!(Rc::ptr_eq(&current_context().unwrap(), &parent));
+        });
+
+        assert!(current_context().is_none());
+    }
+
+    #[test]
+    fn test_child_context_keyed_and_indexed() {
+        let mut ctx = HookContext::new();
+        ctx.begin_render();
+        let a = ctx.child_context(ComponentId::of::<u8>(), None);
+        let b = ctx.child_context(ComponentId::of::<u8>(), None);
+        let c = ctx.child_context(ComponentId::of::<u8>(), Some("c"));
+        assert!(!Rc::ptr_eq(&a, &b));
+        assert!(!Rc::ptr_eq(&a, &c));
+        assert_eq!(ctx.child_count(), 3);
+
+        ctx.begin_render();
+        let a2 = ctx.child_context(ComponentId::of::<u8>(), None);
+        let c2 = ctx.child_context(ComponentId::of::<u8>(), Some("c"));
+        assert!(Rc::ptr_eq(&a, &a2));
+        assert!(Rc::ptr_eq(&c, &c2));
+        assert_eq!(ctx.take_stale_children().len(), 1);
+        assert_eq!(ctx.child_count(), 2);
+    }
+
+    #[test]
+    fn test_unmount_runs_cleanups() {
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        let cleaned = Rc::new(RefCell::new(false));
+
+        let cleaned_clone = cleaned.clone();
+        with_hooks(ctx.clone(), || {
+            let ctx = current_context().unwrap();
+            let mut ctx = ctx.borrow_mut();
+            let slot = ctx.hook_index();
+            ctx.use_hook(|| ());
+            ctx.add_effect(
+                slot,
+                Effect {
+                    callback: Box::new(move || {
+                        Some(Box::new(move || *cleaned_clone.borrow_mut() = true)
+                            as Box<dyn FnOnce()>)
+                    }),
+                    cleanup: None,
+                    deps: None,
+                },
+            );
+        });
+
+        assert!(!*cleaned.borrow());
+        ctx.borrow_mut().unmount();
+        assert!(*cleaned.borrow());
+    }
 }
@@ -1,18 +1,25 @@
 //! Hooks system for reactive state management
 
 pub mod context;
+pub(crate) mod registrations;
 mod use_accessibility;
 pub(crate) mod use_app;
 mod use_cmd;
+pub(crate) mod use_context;
 mod use_effect;
 pub mod use_focus;
 pub mod use_input;
 mod use_measure;
 mod use_memo;
 pub mod use_mouse;
+mod use_reducer;
+pub(crate) mod use_ref;
+mod use_resource;
 mod use_scroll;
+mod use_shared_signal;
 mod use_signal;
 mod use_stdio;
+pub(crate) mod use_store;
 mod use_window_title;
 
 pub use context::{HookContext, current_context, with_hooks};
@@ -21,6 +28,7 @@ pub use use_accessibility::{
 };
 pub use use_app::{AppContext, get_app_context, set_app_context, use_app};
 pub use use_cmd::{Deps, use_cmd, use_cmd_once};
+pub use use_context::{Context, create_context, use_context};
 pub use use_effect::{use_effect, use_effect_once};
 pub use use_focus::{
     FocusManagerHandle, FocusState, UseFocusOptions, use_focus, use_focus_manager,
@@ -35,9 +43,14 @@ pub use use_mouse::{
     Mouse, MouseAction, MouseButton, clear_mouse_handlers, dispatch_mouse_event, is_mouse_enabled,
     set_mouse_enabled, use_mouse,
 };
+pub use use_reducer::{Dispatch, use_reducer};
+pub use use_ref::{ElementRef, Ref, use_element_ref, use_ref};
+pub use use_resource::{Refetch, Resource, use_resource};
 pub use use_scroll::{ScrollHandle, ScrollState, use_scroll};
+pub use use_shared_signal::{SyncSignal, use_shared_signal};
 pub use use_signal::{Signal, use_signal};
 pub use use_stdio::{StderrHandle, StdinHandle, StdoutHandle, use_stderr, use_stdin, use_stdout};
+pub use use_store::{Store, use_selector, use_store};
 pub use use_window_title::{
     WindowTitleGuard, clear_window_title, set_window_title, use_window_title, use_window_title_fn,
 };
@@ -0,0 +1,52 @@
+//! Per-frame registrations made by hooks while rendering
+//!
+//! Input and mouse handlers are registered again on every render. A subtree
+//! that is reused without rendering (see [`Memo`](crate::components::Memo))
+//! records what it registered and replays it on later frames.
+
+use crate::hooks::use_input::{
+    InputHandlerRc, input_handler_len, input_handlers_since, replay_input_handlers,
+};
+use crate::hooks::use_mouse::{
+    MouseHandlerRc, mouse_handler_len, mouse_handlers_since, replay_mouse_handlers,
+};
+
+/// Position in the current frame's registrations
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RegistrationMark {
+    input: usize,
+    mouse: usize,
+}
+
+impl RegistrationMark {
+    /// Mark the registrations made so far in this frame
+    pub(crate) fn now() -> Self {
+        Self {
+            input: input_handler_len(),
+            mouse: mouse_handler_len(),
+        }
+    }
+}
+
+/// Everything registered between a mark and the end of a subtree's render
+#[derive(Default)]
+pub(crate) struct Registrations {
+    input_handlers: Vec<InputHandlerRc>,
+    mouse_handlers: Vec<MouseHandlerRc>,
+}
+
+impl Registrations {
+    /// Collect the registrations made since `mark`
+    pub(crate) fn since(mark: RegistrationMark) -> Self {
+        Self {
+            input_handlers: input_handlers_since(mark.input),
+            mouse_handlers: mouse_handlers_since(mark.mouse),
+        }
+    }
+
+    /// Register everything again in the current frame
+    pub(crate) fn replay(&self) {
+        replay_input_handlers(&self.input_handlers);
+        replay_mouse_handlers(&self.mouse_handlers);
+    }
+}
@@ -4,9 +4,12 @@
 //! functionality like exiting the app, switching display modes, and printing
 //! persistent messages.
 
+use std::any::TypeId;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use super::use_store::{Store, StoreMap};
+
 /// App context that provides control over the application.
 ///
 /// This is obtained via the `use_app()` hook and provides methods for:
@@ -45,6 +48,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 pub struct AppContext {
     exit_flag: Arc<AtomicBool>,
     render_handle: crate::renderer::RenderHandle,
+    stores: StoreMap,
 }
 
 impl AppContext {
@@ -53,9 +57,27 @@ impl AppContext {
         Self {
             exit_flag,
             render_handle,
+            stores: StoreMap::default(),
         }
     }
 
+    /// Attach the stores registered with the app
+    pub(crate) fn with_stores(mut self, stores: StoreMap) -> Self {
+        self.stores = stores;
+        self
+    }
+
+    /// Get the store for state type `S` registered with the app
+    ///
+    /// Returns `None` if no such store was passed to
+    /// [`AppBuilder::store`](crate::renderer::AppBuilder::store).
+    pub fn store<S: Send + Sync + 'static>(&self) -> Option<Store<S>> {
+        self.stores
+            .get(&TypeId::of::<S>())?
+            .downcast_ref::<Store<S>>()
+            .cloned()
+    }
+
     /// Exit the application
     pub fn exit(&self) {
         self.exit_flag.store(true, Ordering::SeqCst);
@@ -5,6 +5,7 @@
 
 use super::context::current_context;
 use crate::cmd::Cmd;
+use crate::components::suspense::track_pending;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
@@ -203,6 +204,7 @@ where
         let cmd = f(deps.output());
 
         // Queue command for execution
+        let cmd = track_pending(&ctx.borrow(), cmd);
         ctx.borrow_mut().queue_cmd(cmd);
     }
 }
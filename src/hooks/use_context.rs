@@ -0,0 +1,323 @@
+//! Context hook for passing values down the component tree
+//!
+//! A context lets a component hand a value (a theme, the current user, a
+//! shared [`Signal`](crate::hooks::Signal)) to every component below it
+//! without threading it through each builder in between.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use rnk::prelude::*;
+//!
+//! #[derive(Clone)]
+//! struct Theme {
+//!     accent: Color,
+//! }
+//!
+//! static THEME: Context<Theme> = Context::with_default(|| Theme {
+//!     accent: Color::White,
+//! });
+//!
+//! fn title(_: &()) -> Element {
+//!     let theme = use_context(&THEME).expect("THEME has a default");
+//!     Text::new("Settings").color(theme.accent).into_element()
+//! }
+//!
+//! fn app() -> Element {
+//!     THEME.provider(Theme { accent: Color::Cyan }, || {
+//!         Box::new()
+//!             .child(Component::new(title, ()).into_element())
+//!             .into_element()
+//!     })
+//! }
+//! ```
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use crate::core::Element;
+use crate::hooks::context::{ContextId, HookContext, current_context, with_hooks};
+
+/// Handle for providing values of type `T` to a subtree
+///
+/// Every context has its own identity: [`use_context`] only sees providers
+/// created from the same handle (or a clone of it), so two contexts of the
+/// same value type never shadow each other. Contexts can be declared as
+/// `static`s.
+pub struct Context<T> {
+    id: OnceLock<ContextId>,
+    default: Option<fn() -> T>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Context<T> {
+    /// Create a context without a default value
+    pub const fn new() -> Self {
+        Self {
+            id: OnceLock::new(),
+            default: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Create a context whose consumers fall back to `default()` when no
+    /// provider encloses them
+    pub const fn with_default(default: fn() -> T) -> Self {
+        Self {
+            id: OnceLock::new(),
+            default: Some(default),
+            _marker: PhantomData,
+        }
+    }
+
+    fn id(&self) -> ContextId {
+        *self.id.get_or_init(ContextId::unique)
+    }
+}
+
+impl<T> Clone for Context<T> {
+    fn clone(&self) -> Self {
+        Self {
+            id: OnceLock::from(self.id()),
+            default: self.default,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for Context<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create a context for values of type `T`
+pub fn create_context<T: Clone + 'static>() -> Context<T> {
+    Context::new()
+}
+
+/// Restores the shadowed context value when the provider's subtree is done
+struct ProvideGuard {
+    ctx: Rc<RefCell<HookContext>>,
+    id: ContextId,
+    previous: Option<Rc<dyn Any>>,
+}
+
+impl Drop for ProvideGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        self.ctx.borrow_mut().restore_provided(self.id, previous);
+    }
+}
+
+impl<T: Clone + 'static> Context<T> {
+    /// Provide `value` to the subtree built by `children`
+    ///
+    /// Every component rendered inside `children` (directly or through
+    /// nested components) sees `value` from [`use_context`], unless a closer
+    /// provider overrides it. Because the subtree is rendered together with
+    /// the provider, consumers pick up a new value on the same render that
+    /// changes it. Provide a [`Signal`](crate::hooks::Signal) instead of a
+    /// plain value to let consumers write back.
+    ///
+    /// The returned element is the subtree itself; providers add no node to
+    /// the layout.
+    pub fn provider<F>(&self, value: T, children: F) -> Element
+    where
+        F: FnOnce() -> Element,
+    {
+        provide(self.id(), Rc::new(value), children)
+    }
+}
+
+/// Render `children` with `value` provided under `id`
+pub(crate) fn provide<F>(id: ContextId, value: Rc<dyn Any>, children: F) -> Element
+where
+    F: FnOnce() -> Element,
+{
+    match current_context() {
+        Some(ctx) => {
+            let previous = ctx.borrow_mut().provide(id, value);
+            let _guard = ProvideGuard { ctx, id, previous };
+            children()
+        }
+        None => {
+            // Outside a render (e.g. `render_to_string`), give the subtree
+            // a temporary context to carry the value
+            let ctx = Rc::new(RefCell::new(HookContext::new()));
+            ctx.borrow_mut().provide(id, value);
+            let element = with_hooks(ctx.clone(), children);
+            ctx.borrow_mut().unmount();
+            element
+        }
+    }
+}
+
+/// Value provided under `id` to the calling component, if any
+pub(crate) fn provided<T: Clone + 'static>(id: ContextId) -> Option<T> {
+    let ctx = current_context()?;
+    let value = ctx.borrow().provided(id)?;
+    value.downcast_ref::<T>().cloned()
+}
+
+/// Read the value of the nearest enclosing provider of `context`
+///
+/// Falls back to the context's default value, and returns `None` when the
+/// context has no default and no provider of it encloses the calling
+/// component. Unlike most hooks this takes no hook slot, so it may be
+/// called conditionally.
+pub fn use_context<T: Clone + 'static>(context: &Context<T>) -> Option<T> {
+    assert!(
+        current_context().is_some(),
+        "use_context must be called within a component"
+    );
+    provided(context.id()).or_else(|| context.default.map(|default| default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Box, Component, Text};
+    use crate::hooks::use_signal;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Theme(&'static str);
+
+    static THEME: Context<Theme> = Context::new();
+
+    fn themed(_: &()) -> Element {
+        let theme = use_context(&THEME).map(|t| t.0).unwrap_or("none");
+        Text::new(theme).into_element()
+    }
+
+    fn texts(element: &Element) -> Vec<String> {
+        element
+            .children
+            .iter()
+            .map(|child| child.get_text().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_nearest_provider_wins() {
+        let theme = &THEME;
+        let root = Rc::new(RefCell::new(HookContext::new()));
+
+        let element = with_hooks(root, || {
+            theme.provider(Theme("dark"), || {
+                Box::new()
+                    .child(Component::new(themed, ()).into_element())
+                    .child(
+                        theme
+                            .provider(Theme("light"), || Component::new(themed, ()).into_element()),
+                    )
+                    .child(Component::new(themed, ()).into_element())
+                    .into_element()
+            })
+        });
+
+        assert_eq!(texts(&element), vec!["dark", "light", "dark"]);
+    }
+
+    #[test]
+    fn test_missing_provider() {
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let element = with_hooks(root.clone(), || Component::new(themed, ()).into_element());
+        assert_eq!(element.get_text(), Some("none"));
+
+        // A provider's value does not leak past its subtree
+        let theme = &THEME;
+        let element = with_hooks(root, || {
+            Box::new()
+                .child(theme.provider(Theme("dark"), || Text::new("x").into_element()))
+                .child(Component::new(themed, ()).into_element())
+                .into_element()
+        });
+        assert_eq!(texts(&element), vec!["x", "none"]);
+    }
+
+    #[test]
+    fn test_consumers_see_new_value() {
+        fn nested(_: &()) -> Element {
+            Component::new(themed, ()).into_element()
+        }
+
+        let theme = &THEME;
+        let root = Rc::new(RefCell::new(HookContext::new()));
+
+        let render = |name: &'static str| {
+            with_hooks(root.clone(), || {
+                theme.provider(Theme(name), || Component::new(nested, ()).into_element())
+            })
+        };
+
+        assert_eq!(render("dark").get_text(), Some("dark"));
+        assert_eq!(render("light").get_text(), Some("light"));
+    }
+
+    #[test]
+    fn test_shared_signal_through_context() {
+        static COUNTER: Context<crate::hooks::Signal<i32>> = Context::new();
+
+        fn writer(_: &()) -> Element {
+            let count = use_context(&COUNTER).unwrap();
+            count.update(|c| *c += 1);
+            Text::new("writer").into_element()
+        }
+
+        let counter = &COUNTER;
+        let root = Rc::new(RefCell::new(HookContext::new()));
+
+        let count = with_hooks(root, || {
+            let count = use_signal(|| 0);
+            counter.provider(count.clone(), || Component::new(writer, ()).into_element());
+            count
+        });
+
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn test_provider_outside_render() {
+        let theme = &THEME;
+        let element = theme.provider(Theme("dark"), || Component::new(themed, ()).into_element());
+        assert_eq!(element.get_text(), Some("dark"));
+    }
+
+    #[test]
+    fn test_contexts_of_same_type_are_distinct() {
+        static ACCENT: Context<Theme> = Context::with_default(|| Theme("default"));
+
+        fn both(_: &()) -> Element {
+            let theme = use_context(&THEME).map(|t| t.0).unwrap_or("none");
+            let accent = use_context(&ACCENT).unwrap().0;
+            Text::new(format!("{theme}/{accent}")).into_element()
+        }
+
+        let root = Rc::new(RefCell::new(HookContext::new()));
+        let render = |accent: Option<Theme>| {
+            with_hooks(root.clone(), || {
+                THEME.provider(Theme("dark"), || match accent {
+                    Some(accent) => {
+                        ACCENT.provider(accent, || Component::new(both, ()).into_element())
+                    }
+                    None => Component::new(both, ()).into_element(),
+                })
+            })
+        };
+
+        assert_eq!(render(None).get_text(), Some("dark/default"));
+        assert_eq!(render(Some(Theme("red"))).get_text(), Some("dark/red"));
+
+        // Clones share the identity of the original handle
+        let theme = create_context::<Theme>();
+        let clone = theme.clone();
+        let element = theme.provider(Theme("light"), || {
+            Text::new(use_context(&clone).unwrap().0).into_element()
+        });
+        assert_eq!(element.get_text(), Some("light"));
+    }
+}
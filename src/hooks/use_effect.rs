@@ -100,6 +100,7 @@ where
     let new_deps_hash = deps.to_hash();
 
     // Get or create effect storage
+    let slot = ctx_ref.hook_index();
     let storage = ctx_ref.use_hook(|| EffectStorage {
         prev_deps_hash: None,
     });
@@ -121,11 +122,14 @@ where
         });
 
         // Add effect to run after render
-        ctx_ref.add_effect(Effect {
-            callback: Box::new(effect),
-            cleanup: None,
-            deps: Some(vec![new_deps_hash]),
-        });
+        ctx_ref.add_effect(
+            slot,
+            Effect {
+                callback: Box::new(effect),
+                cleanup: None,
+                deps: Some(vec![new_deps_hash]),
+            },
+        );
     }
 }
 
@@ -147,6 +151,7 @@ where
     let mut ctx_ref = ctx.borrow_mut();
 
     // Use a flag to track if effect has run
+    let slot = ctx_ref.hook_index();
     let storage = ctx_ref.use_hook(|| false);
 
     let has_run = storage.get::<bool>().unwrap_or(false);
@@ -154,11 +159,14 @@ where
     if !has_run {
         storage.set(true);
 
-        ctx_ref.add_effect(Effect {
-            callback: Box::new(effect),
-            cleanup: None,
-            deps: None,
-        });
+        ctx_ref.add_effect(
+            slot,
+            Effect {
+                callback: Box::new(effect),
+                cleanup: None,
+                deps: None,
+            },
+        );
     }
 }
 
@@ -122,6 +122,13 @@ impl FocusManager {
             .unwrap_or(false)
     }
 
+    /// Check if the element with a custom ID is focused
+    pub fn is_focused_id(&self, custom_id: &str) -> bool {
+        self.focused_index
+            .and_then(|idx| self.elements.get(idx))
+            .is_some_and(|e| e.custom_id.as_deref() == Some(custom_id))
+    }
+
     /// Focus next element
     pub fn focus_next(&mut self) {
         let active_elements: Vec<usize> = self
@@ -214,6 +221,15 @@ where
     f()
 }
 
+/// Run a function against the current focus manager
+pub(crate) fn update_focus_manager<R>(f: impl FnOnce(&mut FocusManager) -> R) -> R {
+    if let Some(ctx) = crate::runtime::current_runtime() {
+        f(ctx.borrow_mut().focus_manager_mut())
+    } else {
+        FOCUS_MANAGER.with(|fm| f(&mut fm.borrow_mut()))
+    }
+}
+
 /// Hook to make a component focusable
 ///
 /// # Example
@@ -77,7 +77,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 /// Internal input handler type (reference-counted for storage)
-type InputHandlerRc = Rc<dyn Fn(&str, &Key)>;
+pub(crate) type InputHandlerRc = Rc<dyn Fn(&str, &Key)>;
 
 thread_local! {
     static INPUT_HANDLERS: RefCell<Vec<InputHandlerRc>> = RefCell::new(Vec::new());
@@ -98,6 +98,39 @@ where
     }
 }
 
+/// Number of input handlers registered so far in this frame
+pub(crate) fn input_handler_len() -> usize {
+    if let Some(ctx) = crate::runtime::current_runtime() {
+        ctx.borrow().input_handlers().len()
+    } else {
+        INPUT_HANDLERS.with(|handlers| handlers.borrow().len())
+    }
+}
+
+/// Input handlers registered in this frame from position `start` on
+///
+/// Used to replay the handlers of subtrees that are reused without
+/// rendering (see [`Memo`](crate::components::Memo)).
+pub(crate) fn input_handlers_since(start: usize) -> Vec<InputHandlerRc> {
+    if let Some(ctx) = crate::runtime::current_runtime() {
+        ctx.borrow().input_handlers()[start..].to_vec()
+    } else {
+        INPUT_HANDLERS.with(|handlers| handlers.borrow()[start..].to_vec())
+    }
+}
+
+/// Register previously captured input handlers again
+pub(crate) fn replay_input_handlers(replayed: &[InputHandlerRc]) {
+    if let Some(ctx) = crate::runtime::current_runtime() {
+        let mut ctx = ctx.borrow_mut();
+        for handler in replayed {
+            ctx.push_input_handler(handler.clone());
+        }
+    } else {
+        INPUT_HANDLERS.with(|handlers| handlers.borrow_mut().extend(replayed.iter().cloned()));
+    }
+}
+
 /// Clear all input handlers
 pub fn clear_input_handlers() {
     INPUT_HANDLERS.with(|handlers| {
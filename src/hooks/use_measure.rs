@@ -26,6 +26,8 @@ impl From<Layout> for Dimensions {
 #[derive(Clone, Default)]
 pub struct MeasureContext {
     layouts: HashMap<ElementId, Layout>,
+    /// IDs elements were created with, mapped to their stable IDs
+    renamed: HashMap<ElementId, ElementId>,
 }
 
 impl MeasureContext {
@@ -33,6 +35,7 @@ impl MeasureContext {
     pub fn new() -> Self {
         Self {
             layouts: HashMap::new(),
+            renamed: HashMap::new(),
         }
     }
 
@@ -41,10 +44,17 @@ impl MeasureContext {
         self.layouts = layouts;
     }
 
+    /// Resolve IDs elements were created with to the stable IDs the layout
+    /// engine gave them (called internally by the renderer)
+    pub(crate) fn set_renamed_ids(&mut self, renamed: HashMap<ElementId, ElementId>) {
+        self.renamed = renamed;
+    }
+
     /// Measure an element by its ID
     pub fn measure(&self, element_id: ElementId) -> Option<Dimensions> {
+        let element_id = self.renamed.get(&element_id).unwrap_or(&element_id);
         self.layouts
-            .get(&element_id)
+            .get(element_id)
             .map(|layout| Dimensions::from(*layout))
     }
 }
@@ -132,7 +132,7 @@ impl Mouse {
 pub type MouseHandler = Box<dyn Fn(&Mouse)>;
 
 /// Internal mouse handler type (reference-counted for storage)
-type MouseHandlerRc = Rc<dyn Fn(&Mouse)>;
+pub(crate) type MouseHandlerRc = Rc<dyn Fn(&Mouse)>;
 
 thread_local! {
     static MOUSE_HANDLERS: RefCell<Vec<MouseHandlerRc>> = RefCell::new(Vec::new());
@@ -155,6 +155,43 @@ where
     }
 }
 
+/// Number of mouse handlers registered so far in this frame
+pub(crate) fn mouse_handler_len() -> usize {
+    if let Some(ctx) = crate::runtime::current_runtime() {
+        ctx.borrow().mouse_handlers().len()
+    } else {
+        MOUSE_HANDLERS.with(|handlers| handlers.borrow().len())
+    }
+}
+
+/// Mouse handlers registered in this frame from position `start` on
+///
+/// Used to replay the handlers of subtrees that are reused without
+/// rendering (see [`Memo`](crate::components::Memo)).
+pub(crate) fn mouse_handlers_since(start: usize) -> Vec<MouseHandlerRc> {
+    if let Some(ctx) = crate::runtime::current_runtime() {
+        ctx.borrow().mouse_handlers()[start..].to_vec()
+    } else {
+        MOUSE_HANDLERS.with(|handlers| handlers.borrow()[start..].to_vec())
+    }
+}
+
+/// Register previously captured mouse handlers again
+pub(crate) fn replay_mouse_handlers(replayed: &[MouseHandlerRc]) {
+    if replayed.is_empty() {
+        return;
+    }
+    if let Some(ctx) = crate::runtime::current_runtime() {
+        let mut ctx = ctx.borrow_mut();
+        for handler in replayed {
+            ctx.push_mouse_handler(handler.clone());
+        }
+    } else {
+        MOUSE_HANDLERS.with(|handlers| handlers.borrow_mut().extend(replayed.iter().cloned()));
+        set_mouse_enabled(true);
+    }
+}
+
 /// Clear all mouse handlers
 pub fn clear_mouse_handlers() {
     MOUSE_HANDLERS.with(|handlers| {
@@ -0,0 +1,319 @@
+//! Reducer hook for state with many transitions
+//!
+//! `use_reducer` keeps all state transitions in one function: components
+//! dispatch actions describing what happened, and the reducer decides how the
+//! state changes. This scales better than a handful of signals updated from
+//! scattered closures.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[derive(Clone, Default)]
+//! struct List {
+//!     items: Vec<String>,
+//!     selected: usize,
+//! }
+//!
+//! enum Action {
+//!     Up,
+//!     Down,
+//!     Loaded(Vec<String>),
+//! }
+//!
+//! fn reduce(list: &mut List, action: Action) {
+//!     match action {
+//!         Action::Up => list.selected = list.selected.saturating_sub(1),
+//!         Action::Down => list.selected = (list.selected + 1).min(list.items.len().saturating_sub(1)),
+//!         Action::Loaded(items) => *list = List { items, selected: 0 },
+//!     }
+//! }
+//!
+//! fn app() -> Element {
+//!     let (list, dispatch) = use_reducer(reduce, List::default);
+//!
+//!     let load = dispatch.clone();
+//!     use_cmd_once(move |_| {
+//!         Cmd::perform(move || async move {
+//!             load.dispatch(Action::Loaded(fetch_items().await));
+//!         })
+//!     });
+//!
+//!     use_input(move |_, key| {
+//!         if key.up_arrow {
+//!             dispatch.dispatch(Action::Up);
+//!         } else if key.down_arrow {
+//!             dispatch.dispatch(Action::Down);
+//!         }
+//!     });
+//!
+//!     Text::new(format!("{} of {}", list.selected + 1, list.items.len())).into_element()
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use crate::hooks::context::{SyncRenderCallback, current_context};
+
+/// Handle for sending actions to a reducer created by [`use_reducer`]
+///
+/// `Dispatch` is cheap to clone and, when the action type is `Send`, can be
+/// moved into commands and background threads. Actions are queued and applied
+/// in order at the start of the next render; all actions dispatched before
+/// that render share a single render request. Actions dispatched after the
+/// component unmounted are dropped.
+pub struct Dispatch<A> {
+    queue: Arc<ActionQueue<A>>,
+}
+
+struct ActionQueue<A> {
+    /// Actions waiting for the next render, `None` once unmounted
+    pending: Mutex<Option<Vec<A>>>,
+    render_callback: Option<SyncRenderCallback>,
+}
+
+/// Held in the component's hook slot; closes the queue on unmount
+struct Mounted<A>(Arc<ActionQueue<A>>);
+
+impl<A> Drop for Mounted<A> {
+    fn drop(&mut self) {
+        *self.0.lock() = None;
+    }
+}
+
+impl<A> ActionQueue<A> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Vec<A>>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<A> Clone for Dispatch<A> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<A> Dispatch<A> {
+    /// Queue an action for the reducer and schedule a re-render
+    pub fn dispatch(&self, action: A) {
+        let first = {
+            let mut pending = self.queue.lock();
+            let Some(pending) = pending.as_mut() else {
+                return;
+            };
+            pending.push(action);
+            pending.len() == 1
+        };
+        // Later actions in the same batch ride on the first request
+        if first {
+            match &self.queue.render_callback {
+                Some(callback) => callback(),
+                None => crate::renderer::request_render(),
+            }
+        }
+    }
+
+    /// Number of actions waiting for the next render
+    pub fn pending(&self) -> usize {
+        self.queue.lock().as_ref().map_or(0, Vec::len)
+    }
+
+    fn take(&self) -> Vec<A> {
+        self.queue
+            .lock()
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+}
+
+impl<A> std::fmt::Debug for Dispatch<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Dispatch")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+/// Internal storage for a reducer hook
+struct ReducerStorage<S, A> {
+    state: Rc<RefCell<S>>,
+    dispatch: Dispatch<A>,
+    _mounted: Rc<Mounted<A>>,
+}
+
+impl<S, A> Clone for ReducerStorage<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            dispatch: self.dispatch.clone(),
+            _mounted: self._mounted.clone(),
+        }
+    }
+}
+
+/// Manage state through a reducer function
+///
+/// Returns the current state and a [`Dispatch`] handle. Dispatched actions
+/// are applied with `reducer` at the start of the next render, in the order
+/// they were dispatched, so the returned state always reflects every action
+/// sent before this render.
+pub fn use_reducer<S, A, R>(reducer: R, initial: impl FnOnce() -> S) -> (S, Dispatch<A>)
+where
+    S: Clone + 'static,
+    A: 'static,
+    R: Fn(&mut S, A),
+{
+    let ctx = current_context().expect("use_reducer must be called within a component");
+    let storage = {
+        let mut ctx_ref = ctx.borrow_mut();
+        let render_callback = ctx_ref.get_sync_render_callback();
+        ctx_ref
+            .use_hook(|| {
+                let queue = Arc::new(ActionQueue {
+                    pending: Mutex::new(Some(Vec::new())),
+                    render_callback,
+                });
+                ReducerStorage::<S, A> {
+                    state: Rc::new(RefCell::new(initial())),
+                    dispatch: Dispatch {
+                        queue: queue.clone(),
+                    },
+                    _mounted: Rc::new(Mounted(queue)),
+                }
+            })
+            .get::<ReducerStorage<S, A>>()
+            .expect("use_reducer hook order changed between renders")
+    };
+
+    // Actions dispatched by the reducer itself land in the next batch
+    let mut state = storage.state.borrow_mut();
+    for action in storage.dispatch.take() {
+        reducer(&mut state, action);
+    }
+
+    (state.clone(), storage.dispatch.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::context::{HookContext, with_hooks};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    enum Action {
+        Add(i32),
+        Reset,
+    }
+
+    fn reduce(total: &mut i32, action: Action) {
+        match action {
+            Action::Add(n) => *total += n,
+            Action::Reset => *total = 0,
+        }
+    }
+
+    fn render(ctx: &Rc<RefCell<HookContext>>) -> (i32, Dispatch<Action>) {
+        with_hooks(ctx.clone(), || use_reducer(reduce, || 10))
+    }
+
+    #[test]
+    fn test_actions_apply_on_next_render() {
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+
+        let (total, dispatch) = render(&ctx);
+        assert_eq!(total, 10);
+
+        dispatch.dispatch(Action::Add(5));
+        dispatch.dispatch(Action::Reset);
+        dispatch.dispatch(Action::Add(2));
+        assert_eq!(dispatch.pending(), 3);
+
+        let (total, dispatch) = render(&ctx);
+        assert_eq!(total, 2);
+        assert_eq!(dispatch.pending(), 0);
+
+        // State persists across renders without actions
+        assert_eq!(render(&ctx).0, 2);
+    }
+
+    #[test]
+    fn test_one_render_request_per_batch() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        ctx.borrow_mut().set_sync_render_callback(Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let (_, dispatch) = render(&ctx);
+        let other = dispatch.clone();
+        dispatch.dispatch(Action::Add(1));
+        other.dispatch(Action::Add(1));
+        dispatch.dispatch(Action::Add(1));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        assert_eq!(render(&ctx).0, 13);
+        dispatch.dispatch(Action::Add(1));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_dispatch_from_another_thread() {
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        let (_, dispatch) = render(&ctx);
+
+        std::thread::spawn(move || dispatch.dispatch(Action::Add(32)))
+            .join()
+            .unwrap();
+
+        assert_eq!(render(&ctx).0, 42);
+    }
+
+    #[test]
+    fn test_dispatch_after_unmount_is_dropped() {
+        use crate::components::{Component, Text};
+        use crate::core::Element;
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        ctx.borrow_mut().set_sync_render_callback(Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let handle = Rc::new(RefCell::new(None));
+        let render_tree = |show: bool| {
+            let handle = handle.clone();
+            with_hooks(ctx.clone(), || {
+                if show {
+                    let counter = move |_: &()| {
+                        let (total, dispatch) = use_reducer(reduce, || 0);
+                        *handle.borrow_mut() = Some(dispatch);
+                        Text::new(total.to_string()).into_element()
+                    };
+                    Component::new(counter, ()).into_element()
+                } else {
+                    Element::text("")
+                }
+            })
+        };
+
+        render_tree(true);
+        let dispatch: Dispatch<Action> = handle.borrow_mut().take().unwrap();
+        dispatch.dispatch(Action::Add(1));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Unmounting drops the queued action, and later ones are ignored
+        render_tree(false);
+        assert_eq!(dispatch.pending(), 0);
+        dispatch.dispatch(Action::Add(1));
+        assert_eq!(dispatch.pending(), 0);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
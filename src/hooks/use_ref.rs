@@ -0,0 +1,602 @@
+//! Ref hooks for values and elements that persist across renders
+//!
+//! [`use_ref`] keeps a mutable value between renders without triggering a
+//! re-render when it changes, for things like timers, previous values or
+//! caches.
+//!
+//! [`use_element_ref`] creates an [`ElementRef`] that can be attached to a
+//! [`Box`](crate::components::Box) with `.ref_(&r)`. After each layout the
+//! ref holds the element's position and size, its scroll offset and whether
+//! it is focused, and it can move focus to the element or scroll it into
+//! view.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn list(items: &Vec<String>) -> Element {
+//!     let selected = use_signal(|| 0usize);
+//!     let rows: Vec<ElementRef> = (0..items.len()).map(|_| ElementRef::new()).collect();
+//!     let rows = use_ref(|| rows);
+//!
+//!     let handle = rows.clone();
+//!     use_input(move |_, key| {
+//!         if key.down_arrow {
+//!             selected.update(|s| *s += 1);
+//!             handle.with(|rows| rows[selected.get()].scroll_into_view());
+//!         }
+//!     });
+//!
+//!     Box::new()
+//!         .height(5)
+//!         .overflow_y(Overflow::Hidden)
+//!         .flex_direction(FlexDirection::Column)
+//!         .children(items.iter().enumerate().map(|(i, item)| {
+//!             Box::new()
+//!                 .ref_(&rows.with(|rows| rows[i].clone()))
+//!                 .child(Text::new(item).into_element())
+//!                 .into_element()
+//!         }))
+//!         .into_element()
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard, Weak};
+
+use crate::core::{Element, ElementId, ElementRefId, ElementType, Overflow};
+use crate::hooks::UseFocusOptions;
+use crate::hooks::context::{SyncRenderCallback, current_context};
+use crate::hooks::use_focus::update_focus_manager;
+use crate::layout::{Layout, LayoutEngine};
+
+/// A mutable value that persists across renders
+///
+/// Unlike a [`Signal`](crate::hooks::Signal), writing to a `Ref` does not
+/// schedule a re-render.
+pub struct Ref<T> {
+    value: Rc<RefCell<T>>,
+}
+
+impl<T> Clone for Ref<T> {
+    fn clone(&self) -> Self {
+        Self {
+            value: self.value.clone(),
+        }
+    }
+}
+
+impl<T> Ref<T> {
+    /// Get a clone of the current value
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.value.borrow().clone()
+    }
+
+    /// Set a new value
+    pub fn set(&self, value: T) {
+        *self.value.borrow_mut() = value;
+    }
+
+    /// Replace the value, returning the previous one
+    pub fn replace(&self, value: T) -> T {
+        self.value.replace(value)
+    }
+
+    /// Get a reference to the current value
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.value.borrow())
+    }
+
+    /// Modify the value in place
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.value.borrow_mut());
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for Ref<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Ref({:?})", self.value.borrow())
+    }
+}
+
+/// Create a mutable value that persists across renders
+///
+/// # Example
+///
+/// ```ignore
+/// let renders = use_ref(|| 0);
+/// renders.update(|n| *n += 1);
+/// ```
+pub fn use_ref<T: 'static>(init: impl FnOnce() -> T) -> Ref<T> {
+    let ctx = current_context().expect("use_ref must be called within a component");
+    let mut ctx_ref = ctx.borrow_mut();
+    ctx_ref
+        .use_hook(|| Ref {
+            value: Rc::new(RefCell::new(init())),
+        })
+        .get::<Ref<T>>()
+        .expect("use_ref hook order changed between renders")
+}
+
+/// Live element refs, so the renderer can find the ref an element is tagged with
+static ELEMENT_REFS: LazyLock<Mutex<HashMap<ElementRefId, Weak<ElementRefInner>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn element_refs() -> MutexGuard<'static, HashMap<ElementRefId, Weak<ElementRefInner>>> {
+    ELEMENT_REFS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Reference to a rendered element
+///
+/// Attach it with [`Box::ref_`](crate::components::Box::ref_). The values it
+/// reports are those of the most recently rendered frame; before the element
+/// has been laid out they are `None` or zero.
+#[derive(Clone)]
+pub struct ElementRef {
+    inner: Arc<ElementRefInner>,
+}
+
+struct ElementRefInner {
+    id: ElementRefId,
+    focus_id: String,
+    state: Mutex<ElementRefState>,
+    render_callback: Mutex<Option<SyncRenderCallback>>,
+}
+
+#[derive(Default)]
+struct ElementRefState {
+    element_id: Option<ElementId>,
+    layout: Option<Layout>,
+    scroll_offset: (u16, u16),
+    /// Scroll offset chosen by `scroll_into_view` of a descendant
+    scroll_override: Option<(u16, u16)>,
+    focused: bool,
+    focus_requested: bool,
+    scroll_requested: bool,
+}
+
+impl ElementRef {
+    /// Create an element ref that is not attached to any element yet
+    pub fn new() -> Self {
+        let id = ElementRefId::new();
+        let inner = Arc::new(ElementRefInner {
+            id,
+            focus_id: format!("element-ref-{}", id.as_u64()),
+            state: Mutex::new(ElementRefState::default()),
+            render_callback: Mutex::new(None),
+        });
+        element_refs().insert(id, Arc::downgrade(&inner));
+        Self { inner }
+    }
+
+    /// ID that tags the element this ref is attached to
+    pub fn id(&self) -> ElementRefId {
+        self.inner.id
+    }
+
+    /// The live ref with the given ID, if any
+    fn find(id: ElementRefId) -> Option<Self> {
+        let inner = element_refs().get(&id)?.upgrade()?;
+        Some(Self { inner })
+    }
+
+    /// ID of the element in the last rendered frame
+    ///
+    /// The ID can be passed to [`measure_element`](crate::hooks::measure_element).
+    pub fn element_id(&self) -> Option<ElementId> {
+        self.state().element_id
+    }
+
+    /// Position and size of the element on screen
+    ///
+    /// `x` and `y` are relative to the top-left corner of the app's output,
+    /// after scrolling of ancestors has been applied.
+    pub fn layout(&self) -> Option<Layout> {
+        self.state().layout
+    }
+
+    /// The element's own scroll offset as `(x, y)`
+    pub fn scroll_offset(&self) -> (u16, u16) {
+        self.state().scroll_offset
+    }
+
+    /// Whether the element is focused
+    ///
+    /// The element takes part in focus navigation once a component calls
+    /// [`use_focus`](crate::hooks::use_focus) with [`focus_options`].
+    ///
+    /// [`focus_options`]: ElementRef::focus_options
+    pub fn is_focused(&self) -> bool {
+        self.state().focused
+    }
+
+    /// Focus ID identifying this element in the focus manager
+    pub fn focus_id(&self) -> &str {
+        &self.inner.focus_id
+    }
+
+    /// Options registering this element with [`use_focus`](crate::hooks::use_focus)
+    pub fn focus_options(&self) -> UseFocusOptions {
+        UseFocusOptions::new().id(self.focus_id())
+    }
+
+    /// Move focus to this element
+    ///
+    /// Takes effect on the next render.
+    pub fn focus(&self) {
+        self.state().focus_requested = true;
+        self.request_render();
+    }
+
+    /// Scroll the nearest clipping ancestor so this element is visible
+    ///
+    /// The ancestor is a box with `overflow` set to hidden or scroll. If that
+    /// ancestor has an element ref of its own and no explicit scroll offset,
+    /// the new offset is kept on later renders. Takes effect on the next
+    /// render.
+    pub fn scroll_into_view(&self) {
+        self.state().scroll_requested = true;
+        self.request_render();
+    }
+
+    /// Scroll offset set on this element by `scroll_into_view`, if any
+    fn scroll_override(&self) -> Option<(u16, u16)> {
+        self.state().scroll_override
+    }
+
+    fn state(&self) -> MutexGuard<'_, ElementRefState> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn bind(&self, callback: Option<SyncRenderCallback>) {
+        if let Some(callback) = callback {
+            *self
+                .inner
+                .render_callback
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(callback);
+        }
+    }
+
+    fn request_render(&self) {
+        let callback = self
+            .inner
+            .render_callback
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        match callback {
+            Some(callback) => callback(),
+            None => crate::renderer::request_render(),
+        }
+    }
+}
+
+impl Drop for ElementRefInner {
+    fn drop(&mut self) {
+        element_refs().remove(&self.id);
+    }
+}
+
+impl Default for ElementRef {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for ElementRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl std::fmt::Debug for ElementRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElementRef")
+            .field("focus_id", &self.inner.focus_id)
+            .field("element_id", &self.element_id())
+            .finish()
+    }
+}
+
+/// Create an element ref owned by the current component
+pub fn use_element_ref() -> ElementRef {
+    let ctx = current_context().expect("use_element_ref must be called within a component");
+    let mut ctx_ref = ctx.borrow_mut();
+    let render_callback = ctx_ref.get_sync_render_callback();
+    let element_ref = ctx_ref
+        .use_hook(ElementRef::new)
+        .get::<ElementRef>()
+        .expect("use_element_ref hook order changed between renders");
+    element_ref.bind(render_callback);
+    element_ref
+}
+
+/// Update the element refs in a laid-out tree
+///
+/// Applies pending focus and scroll requests, then records each ref's
+/// element ID, screen layout, scroll offset and focus state. Called by the
+/// renderer after layout and before painting, so scrolling applies to the
+/// frame being drawn.
+pub(crate) fn update_element_refs(root: &mut Element, engine: &LayoutEngine) {
+    apply_requests(root, engine, (0.0, 0.0));
+    record(root, engine, (0.0, 0.0));
+}
+
+fn apply_requests(
+    element: &mut Element,
+    engine: &LayoutEngine,
+    offset: (f32, f32),
+) -> Option<Layout> {
+    if element.style.display == crate::core::Display::None {
+        return None;
+    }
+
+    let layout = engine.get_layout(element.id).unwrap_or_default();
+    let origin = (offset.0 + layout.x, offset.1 + layout.y);
+
+    let mut request = None;
+    if let Some(element_ref) = element.element_ref.and_then(ElementRef::find) {
+        // Offsets from `scroll_into_view` apply unless set explicitly. They are
+        // applied here rather than when the element is built, so subtrees
+        // reused from a memo cache pick them up too.
+        if let Some((x, y)) = element_ref.scroll_override() {
+            element.scroll_offset_x = element.scroll_offset_x.or(Some(x));
+            element.scroll_offset_y = element.scroll_offset_y.or(Some(y));
+        }
+        let mut state = element_ref.state();
+        if std::mem::take(&mut state.focus_requested) {
+            update_focus_manager(|fm| fm.focus(element_ref.focus_id()));
+        }
+        if std::mem::take(&mut state.scroll_requested) {
+            request = Some(Layout {
+                x: origin.0,
+                y: origin.1,
+                ..layout
+            });
+        }
+    }
+
+    let inner = content_offset(element, origin);
+    let mut descendant_request = None;
+    for child in element.children.iter_mut() {
+        // Portals are not clipped by their ancestors, so they never scroll them
+        if child.element_type == ElementType::Portal {
+            apply_requests(child, engine, (0.0, 0.0));
+        } else if let Some(rect) = apply_requests(child, engine, inner) {
+            descendant_request = Some(rect);
+        }
+    }
+
+    if let Some(rect) = descendant_request {
+        if clips_children(element) {
+            reveal(element, origin, &layout, &rect);
+        } else {
+            request = request.or(Some(rect));
+        }
+    }
+    request
+}
+
+fn record(element: &Element, engine: &LayoutEngine, offset: (f32, f32)) {
+    if element.style.display == crate::core::Display::None {
+        return;
+    }
+
+    let layout = engine.get_layout(element.id).unwrap_or_default();
+    let origin = (offset.0 + layout.x, offset.1 + layout.y);
+
+    if let Some(element_ref) = element.element_ref.and_then(ElementRef::find) {
+        let focused = update_focus_manager(|fm| fm.is_focused_id(element_ref.focus_id()));
+        let mut state = element_ref.state();
+        state.element_id = Some(element.id);
+        state.layout = Some(Layout {
+            x: origin.0,
+            y: origin.1,
+            ..layout
+        });
+        state.scroll_offset = (
+            element.scroll_offset_x.unwrap_or(0),
+            element.scroll_offset_y.unwrap_or(0),
+        );
+        state.focused = focused;
+    }
+
+    let inner = content_offset(element, origin);
+    for child in &element.children {
+        if child.element_type == ElementType::Portal {
+            record(child, engine, (0.0, 0.0));
+        } else {
+            record(child, engine, inner);
+        }
+    }
+}
+
+/// Origin for children, matching the element renderer's scroll handling
+fn content_offset(element: &Element, origin: (f32, f32)) -> (f32, f32) {
+    (
+        origin.0 - element.scroll_offset_x.unwrap_or(0) as f32,
+        origin.1 - element.scroll_offset_y.unwrap_or(0) as f32,
+    )
+}
+
+fn clips_children(element: &Element) -> bool {
+    [element.style.overflow_x, element.style.overflow_y]
+        .iter()
+        .any(|overflow| matches!(overflow, Overflow::Hidden | Overflow::Scroll))
+}
+
+/// Adjust a clipping element's scroll offset so `rect` is inside its viewport
+fn reveal(element: &mut Element, origin: (f32, f32), layout: &Layout, rect: &Layout) {
+    let border = if element.style.has_border() { 1.0 } else { 0.0 };
+    let view_x = origin.0 + border;
+    let view_y = origin.1 + border;
+    let view_width = (layout.width - 2.0 * border).max(0.0);
+    let view_height = (layout.height - 2.0 * border).max(0.0);
+
+    let scroll_x = element.scroll_offset_x.unwrap_or(0);
+    let scroll_y = element.scroll_offset_y.unwrap_or(0);
+    let new_x = reveal_axis(scroll_x, view_x, view_width, rect.x, rect.width);
+    let new_y = reveal_axis(scroll_y, view_y, view_height, rect.y, rect.height);
+
+    if element.style.overflow_x != Overflow::Visible {
+        element.scroll_offset_x = Some(new_x);
+    }
+    if element.style.overflow_y != Overflow::Visible {
+        element.scroll_offset_y = Some(new_y);
+    }
+    if let Some(element_ref) = element.element_ref.and_then(ElementRef::find) {
+        element_ref.state().scroll_override = Some((
+            element.scroll_offset_x.unwrap_or(0),
+            element.scroll_offset_y.unwrap_or(0),
+        ));
+    }
+}
+
+/// New scroll offset along one axis that brings `[start, start + len)` into
+/// the viewport `[view, view + view_len)`, scrolling as little as possible
+fn reveal_axis(scroll: u16, view: f32, view_len: f32, start: f32, len: f32) -> u16 {
+    let scroll = scroll as f32;
+    let new = if start < view || len > view_len {
+        scroll - (view - start)
+    } else if start + len > view + view_len {
+        scroll + (start + len - view - view_len)
+    } else {
+        scroll
+    };
+    new.max(0.0).round() as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Box, Text};
+    use crate::core::FlexDirection;
+    use crate::hooks::context::{HookContext, with_hooks};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn laid_out(mut root: Element) -> (Element, LayoutEngine) {
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(&mut root, 40, 20);
+        update_element_refs(&mut root, &engine);
+        (root, engine)
+    }
+
+    #[test]
+    fn test_use_ref_persists_without_render() {
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        let renders = Arc::new(AtomicUsize::new(0));
+        let counter = renders.clone();
+        ctx.borrow_mut().set_sync_render_callback(Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        for expected in 1..=3 {
+            let count = with_hooks(ctx.clone(), || {
+                let count = use_ref(|| 0);
+                count.update(|n| *n += 1);
+                count.get()
+            });
+            assert_eq!(count, expected);
+        }
+        assert_eq!(renders.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_element_ref_layout() {
+        let target = ElementRef::new();
+        assert!(target.layout().is_none());
+
+        let root = Box::new()
+            .padding(1)
+            .flex_direction(FlexDirection::Column)
+            .child(Text::new("header").into_element())
+            .child(
+                Box::new()
+                    .ref_(&target)
+                    .width(10)
+                    .child(Text::new("body").into_element())
+                    .into_element(),
+            )
+            .into_element();
+        let (root, _) = laid_out(root);
+
+        let layout = target.layout().unwrap();
+        assert_eq!((layout.x, layout.y), (1.0, 2.0));
+        assert_eq!((layout.width, layout.height), (10.0, 1.0));
+        assert_eq!(target.element_id(), Some(root.children.get(1).unwrap().id));
+    }
+
+    #[test]
+    fn test_scroll_into_view() {
+        let container = ElementRef::new();
+        let rows: Vec<ElementRef> = (0..10).map(|_| ElementRef::new()).collect();
+
+        let build = || {
+            Box::new()
+                .ref_(&container)
+                .height(3)
+                .overflow_y(Overflow::Hidden)
+                .flex_direction(FlexDirection::Column)
+                .children(rows.iter().enumerate().map(|(i, row)| {
+                    Box::new()
+                        .ref_(row)
+                        .flex_shrink(0.0)
+                        .child(Text::new(format!("row {}", i)).into_element())
+                        .into_element()
+                }))
+                .into_element()
+        };
+
+        laid_out(build());
+        assert_eq!(rows[7].layout().unwrap().y, 7.0);
+
+        rows[7].scroll_into_view();
+        let (root, _) = laid_out(build());
+        assert_eq!(root.scroll_offset_y, Some(5));
+        assert_eq!(container.scroll_offset(), (0, 5));
+        assert_eq!(rows[7].layout().unwrap().y, 2.0);
+
+        // The offset is kept on the next render, and scrolling back up works
+        rows[1].scroll_into_view();
+        let (root, _) = laid_out(build());
+        assert_eq!(root.scroll_offset_y, Some(1));
+        assert_eq!(rows[1].layout().unwrap().y, 0.0);
+    }
+
+    #[test]
+    fn test_focus_through_ref() {
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        let first = ElementRef::new();
+        let second = ElementRef::new();
+
+        with_hooks(ctx, || {
+            crate::hooks::use_focus(first.focus_options().auto_focus());
+            crate::hooks::use_focus(second.focus_options());
+        });
+
+        let build = || {
+            Box::new()
+                .child(Box::new().ref_(&first).into_element())
+                .child(Box::new().ref_(&second).into_element())
+                .into_element()
+        };
+
+        laid_out(build());
+        assert!(!second.is_focused());
+
+        second.focus();
+        laid_out(build());
+        assert!(second.is_focused());
+        assert!(!first.is_focused());
+    }
+}
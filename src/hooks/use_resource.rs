@@ -0,0 +1,350 @@
+//! Async data loading tied to dependencies
+//!
+//! Loading data used to take a signal for the result, a `use_cmd` to start
+//! the request and manual flags for loading and error states. `use_resource`
+//! does all of it: the fetch runs on the app's command executor, the
+//! component re-renders when it finishes, and changing the dependencies
+//! starts a new fetch while cancelling the one in flight.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn user_panel(user_id: u64) -> Element {
+//!     let (user, refetch) = use_resource(user_id, |id| async move { fetch_user(id).await });
+//!
+//!     use_input(move |input, _| {
+//!         if input == "r" {
+//!             refetch.refetch();
+//!         }
+//!     });
+//!
+//!     match user {
+//!         Resource::Loading => Spinner::new().into_element(),
+//!         Resource::Ready(user) => Text::new(user.name).into_element(),
+//!         Resource::Failed(err) => Text::new(format!("Error: {}", err)).into_element(),
+//!     }
+//! }
+//! ```
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::oneshot;
+
+use super::context::{SyncRenderCallback, current_context};
+use super::use_cmd::Deps;
+use crate::cmd::Cmd;
+use crate::components::suspense::track_pending;
+
+/// State of an async resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resource<T, E> {
+    /// A fetch is in flight
+    Loading,
+    /// The last fetch succeeded
+    Ready(T),
+    /// The last fetch failed
+    Failed(E),
+}
+
+impl<T, E> Resource<T, E> {
+    /// Check if a fetch is in flight
+    pub fn is_loading(&self) -> bool {
+        matches!(self, Resource::Loading)
+    }
+
+    /// Check if the resource holds a value
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Resource::Ready(_))
+    }
+
+    /// Check if the last fetch failed
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Resource::Failed(_))
+    }
+
+    /// Get the loaded value, if any
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Resource::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the error of the last fetch, if it failed
+    pub fn error(&self) -> Option<&E> {
+        match self {
+            Resource::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Handle for re-running a resource's fetch
+///
+/// `Refetch` is cheap to clone and can be moved into input handlers,
+/// commands and other threads. The fetch restarts on the next render.
+#[derive(Clone)]
+pub struct Refetch {
+    requested: Arc<AtomicU64>,
+    render_callback: Option<SyncRenderCallback>,
+}
+
+impl Refetch {
+    /// Fetch again with the current dependencies
+    pub fn refetch(&self) {
+        self.requested.fetch_add(1, Ordering::SeqCst);
+        match &self.render_callback {
+            Some(callback) => callback(),
+            None => crate::renderer::request_render(),
+        }
+    }
+}
+
+impl std::fmt::Debug for Refetch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Refetch")
+            .field("requested", &self.requested.load(Ordering::SeqCst))
+            .finish()
+    }
+}
+
+/// Result slot shared with the running fetch
+struct ResourceSlot<T, E> {
+    state: Resource<T, E>,
+    /// Incremented for every fetch; results from older fetches are dropped
+    generation: u64,
+}
+
+/// Internal state for a use_resource hook
+struct ResourceHook<T, E> {
+    slot: Arc<Mutex<ResourceSlot<T, E>>>,
+    deps_hash: Option<u64>,
+    refetch: Refetch,
+    /// Refetch count handled by the last fetch
+    seen_refetch: u64,
+    /// Dropping the sender cancels the fetch in flight
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+/// Load a value asynchronously, re-fetching when dependencies change
+///
+/// `fetch` is called with the dependency values on the first render, whenever
+/// the dependencies change and after [`Refetch::refetch`]. The future runs on
+/// the app's command executor; the resource is [`Resource::Loading`] until
+/// it resolves and the component re-renders with the result.
+///
+/// Starting a new fetch cancels the previous one, and so does unmounting the
+/// component, so a slow response never overwrites a newer one.
+///
+/// # Panics
+///
+/// Panics if called outside of a component render.
+pub fn use_resource<D, F, Fut, T, E>(deps: D, fetch: F) -> (Resource<T, E>, Refetch)
+where
+    D: Deps + 'static,
+    F: FnOnce(D::Output) -> Fut,
+    Fut: Future<Output = Result<T, E>> + Send + 'static,
+    T: Clone + Send + 'static,
+    E: Clone + Send + 'static,
+{
+    let ctx = current_context().expect("use_resource must be called within a component render");
+    let render_callback = ctx.borrow().get_sync_render_callback();
+
+    let hook = ctx
+        .borrow_mut()
+        .use_hook(|| {
+            Rc::new(RefCell::new(ResourceHook::<T, E> {
+                slot: Arc::new(Mutex::new(ResourceSlot {
+                    state: Resource::Loading,
+                    generation: 0,
+                })),
+                deps_hash: None,
+                refetch: Refetch {
+                    requested: Arc::new(AtomicU64::new(0)),
+                    render_callback: render_callback.clone(),
+                },
+                seen_refetch: 0,
+                cancel: None,
+            }))
+        })
+        .get::<Rc<RefCell<ResourceHook<T, E>>>>()
+        .expect("use_resource hook order changed between renders");
+    let mut hook = hook.borrow_mut();
+
+    let new_hash = deps.deps_hash();
+    let requested = hook.refetch.requested.load(Ordering::SeqCst);
+    if hook.deps_hash != Some(new_hash) || hook.seen_refetch != requested {
+        hook.deps_hash = Some(new_hash);
+        hook.seen_refetch = requested;
+
+        let generation = {
+            let mut slot = lock(&hook.slot);
+            slot.generation += 1;
+            slot.state = Resource::Loading;
+            slot.generation
+        };
+
+        // Replacing the sender cancels the previous fetch
+        let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+        hook.cancel = Some(cancel_tx);
+
+        let future = fetch(deps.output());
+        let slot = hook.slot.clone();
+        let cmd = Cmd::perform(move || async move {
+            tokio::select! {
+                result = future => {
+                    {
+                        let mut slot = lock(&slot);
+                        if slot.generation != generation {
+                            return;
+                        }
+                        slot.state = match result {
+                            Ok(value) => Resource::Ready(value),
+                            Err(error) => Resource::Failed(error),
+                        };
+                    }
+                    match render_callback {
+                        Some(callback) => callback(),
+                        None => crate::renderer::request_render(),
+                    }
+                }
+                _ = cancel_rx => {}
+            }
+        });
+        let cmd = track_pending(&ctx.borrow(), cmd);
+        ctx.borrow_mut().queue_cmd(cmd);
+    }
+
+    let state = lock(&hook.slot).state.clone();
+    (state, hook.refetch.clone())
+}
+
+fn lock<T, E>(slot: &Mutex<ResourceSlot<T, E>>) -> MutexGuard<'_, ResourceSlot<T, E>> {
+    slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::context::{HookContext, with_hooks};
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    type Ctx = Rc<RefCell<HookContext>>;
+
+    fn counting_context() -> (Ctx, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        ctx.borrow_mut().set_sync_render_callback(Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        (ctx, requests)
+    }
+
+    fn render(ctx: &Ctx, id: u32) -> (Resource<u32, String>, Refetch) {
+        with_hooks(ctx.clone(), || {
+            use_resource(id, |id| async move {
+                if id == 0 {
+                    Err("not found".to_string())
+                } else {
+                    Ok(id * 10)
+                }
+            })
+        })
+    }
+
+    /// Run the commands queued by the last render to completion
+    fn run_cmds(ctx: &Ctx) -> usize {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let cmds = ctx.borrow_mut().take_cmds();
+        let count = cmds.len();
+        for cmd in cmds {
+            if let Cmd::Perform { future } = cmd {
+                runtime.block_on(future);
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_loading_then_ready_or_failed() {
+        let (ctx, requests) = counting_context();
+
+        let (state, _) = render(&ctx, 4);
+        assert!(state.is_loading());
+        assert_eq!(run_cmds(&ctx), 1);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        let (state, _) = render(&ctx, 4);
+        assert_eq!(state, Resource::Ready(40));
+        // Unchanged deps don't fetch again
+        assert_eq!(run_cmds(&ctx), 0);
+
+        render(&ctx, 0);
+        run_cmds(&ctx);
+        let (state, _) = render(&ctx, 0);
+        assert_eq!(state.error().map(String::as_str), Some("not found"));
+    }
+
+    #[test]
+    fn test_deps_change_cancels_stale_fetch() {
+        let (ctx, requests) = counting_context();
+
+        let (gate_tx, gate_rx) = oneshot::channel::<()>();
+        with_hooks(ctx.clone(), || {
+            use_resource(1u32, |_| async move {
+                let _ = gate_rx.await;
+                Ok::<u32, String>(1)
+            })
+        });
+        let stale = ctx.borrow_mut().take_cmds();
+
+        let (state, _) = render(&ctx, 2);
+        assert!(state.is_loading());
+        run_cmds(&ctx);
+
+        // The first fetch was cancelled when deps changed, so it finishes
+        // without its gate ever opening
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        for cmd in stale {
+            if let Cmd::Perform { future } = cmd {
+                let finished = runtime
+                    .block_on(async { tokio::time::timeout(Duration::from_secs(5), future).await });
+                assert!(finished.is_ok());
+            }
+        }
+        drop(gate_tx);
+
+        assert_eq!(render(&ctx, 2).0, Resource::Ready(20));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_refetch_restarts_fetch() {
+        let (ctx, requests) = counting_context();
+
+        render(&ctx, 3);
+        run_cmds(&ctx);
+        let (state, refetch) = render(&ctx, 3);
+        assert_eq!(state, Resource::Ready(30));
+
+        refetch.refetch();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let (state, _) = render(&ctx, 3);
+        assert!(state.is_loading());
+        assert_eq!(run_cmds(&ctx), 1);
+        assert_eq!(render(&ctx, 3).0, Resource::Ready(30));
+    }
+}
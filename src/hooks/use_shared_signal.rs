@@ -0,0 +1,268 @@
+//! Thread-safe signals for state written from commands and background threads
+//!
+//! [`Signal`](crate::hooks::Signal) is single-threaded, so state updated by a
+//! worker thread or a `Cmd::perform` future used to need an `Arc<RwLock<_>>`
+//! plus a manual `request_render()` call. A [`SyncSignal`] bundles both: it can
+//! be cloned into any thread, and writing to it asks the running app to
+//! re-render.
+//!
+//! # Example
+//!
+//! ```ignore
+//! fn app() -> Element {
+//!     let progress = use_shared_signal(|| 0u8);
+//!
+//!     let worker = progress.clone();
+//!     use_effect_once(move || {
+//!         std::thread::spawn(move || {
+//!             for step in 0..=100 {
+//!                 worker.set(step);
+//!                 std::thread::sleep(Duration::from_millis(20));
+//!             }
+//!         });
+//!         None
+//!     });
+//!
+//!     Text::new(format!("{}%", progress.get())).into_element()
+//! }
+//! ```
+
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::hooks::context::{SyncRenderCallback, current_context};
+use crate::renderer::render_handle;
+
+/// A signal that can be shared across threads
+///
+/// Writes from any thread request a re-render of the app. The app coalesces
+/// render requests into its next frame, so a worker updating the value in a
+/// tight loop costs at most one render per frame.
+pub struct SyncSignal<T> {
+    inner: Arc<SyncSignalInner<T>>,
+}
+
+struct SyncSignalInner<T> {
+    value: RwLock<T>,
+    /// Render callback of the component that owns the signal
+    render_callback: Mutex<Option<SyncRenderCallback>>,
+}
+
+impl<T> Clone for SyncSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Send + Sync> SyncSignal<T> {
+    /// Create a new signal with an initial value
+    ///
+    /// Signals created outside a component (for example in `main`) request
+    /// renders from whichever app is running when they are written.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(SyncSignalInner {
+                value: RwLock::new(value),
+                render_callback: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Get a clone of the current value
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.read().clone()
+    }
+
+    /// Get a reference to the current value
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.read())
+    }
+
+    /// Set a new value and request a re-render
+    pub fn set(&self, value: T) {
+        *self.write() = value;
+        self.trigger_render();
+    }
+
+    /// Update the value using a function and request a re-render
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.write());
+        self.trigger_render();
+    }
+
+    /// Modify the value without requesting a re-render
+    pub fn set_silent(&self, value: T) {
+        *self.write() = value;
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner
+            .value
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.inner
+            .value
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Attach the render callback of the owner, which is rendering now
+    fn bind(&self, callback: Option<SyncRenderCallback>) {
+        if let Some(callback) = callback {
+            *self.callback_slot() = Some(callback);
+        }
+    }
+
+    fn callback_slot(&self) -> std::sync::MutexGuard<'_, Option<SyncRenderCallback>> {
+        self.inner
+            .render_callback
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn trigger_render(&self) {
+        let callback = self.callback_slot().clone();
+        match callback {
+            Some(callback) => callback(),
+            None => {
+                if let Some(handle) = render_handle() {
+                    handle.request_render();
+                }
+            }
+        }
+    }
+}
+
+impl<T: Send + Sync + std::fmt::Display> std::fmt::Display for SyncSignal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|value| write!(f, "{}", value))
+    }
+}
+
+impl<T: Send + Sync + std::fmt::Debug> std::fmt::Debug for SyncSignal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|value| write!(f, "SyncSignal({:?})", value))
+    }
+}
+
+/// Create a thread-safe signal owned by the current component
+///
+/// Like [`use_signal`](crate::hooks::use_signal), the value persists across
+/// renders. The returned [`SyncSignal`] can be moved into commands and
+/// threads; writes re-render the component's app.
+pub fn use_shared_signal<T>(init: impl FnOnce() -> T) -> SyncSignal<T>
+where
+    T: Send + Sync + 'static,
+{
+    let ctx = current_context().expect("use_shared_signal must be called within a component");
+    let mut ctx_ref = ctx.borrow_mut();
+    let render_callback = ctx_ref.get_sync_render_callback();
+
+    let signal = ctx_ref
+        .use_hook(|| SyncSignal::new(init()))
+        .get::<SyncSignal<T>>()
+        .expect("use_shared_signal hook order changed between renders");
+    signal.bind(render_callback);
+    signal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Component, Text};
+    use crate::core::Element;
+    use crate::hooks::context::{HookContext, with_hooks};
+    use crate::renderer::App;
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    #[test]
+    fn test_sync_signal_get_set() {
+        let signal = SyncSignal::new(1);
+        signal.set(2);
+        signal.update(|v| *v *= 10);
+        assert_eq!(signal.get(), 20);
+        assert_eq!(signal.with(|v| *v + 1), 21);
+    }
+
+    #[test]
+    fn test_state_persists_across_renders() {
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+
+        let first = with_hooks(ctx.clone(), || use_shared_signal(|| vec![1]));
+        first.update(|v| v.push(2));
+
+        let second = with_hooks(ctx, || use_shared_signal(|| vec![0]));
+        assert_eq!(second.get(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_writes_from_threads_coalesce() {
+        let shared = Rc::new(RefCell::new(None));
+        let slot = shared.clone();
+        let app = App::new(move || {
+            let signal = use_shared_signal(|| 0usize);
+            slot.borrow_mut().get_or_insert_with(|| signal.clone());
+            Text::new(signal.get().to_string()).into_element()
+        });
+        app.render_detached();
+        let signal = shared.borrow().clone().unwrap();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let signal = signal.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        signal.update(|v| *v += 1);
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        // All writes land in a single frame
+        app.wait_for_render_request();
+        assert_eq!(app.render_detached().get_text(), Some("400"));
+        assert!(!app.render_pending());
+    }
+
+    #[test]
+    fn test_writes_after_owner_stops_rendering() {
+        fn owner(slot: &Rc<RefCell<Option<SyncSignal<u32>>>>) -> Element {
+            let signal = use_shared_signal(|| 0);
+            slot.borrow_mut().get_or_insert_with(|| signal.clone());
+            Text::new(signal.get().to_string()).into_element()
+        }
+
+        let shared = Rc::new(RefCell::new(None));
+        let show_owner = Rc::new(Cell::new(true));
+        let (slot, show) = (shared.clone(), show_owner.clone());
+        let app = App::new(move || {
+            if show.get() {
+                Component::new(owner, slot.clone()).into_element()
+            } else {
+                Text::new("elsewhere").into_element()
+            }
+        });
+        app.render_detached();
+        let signal = shared.borrow().clone().unwrap();
+
+        // The frame after the first write no longer renders the owner
+        show_owner.set(false);
+        signal.set(1);
+        app.wait_for_render_request();
+        app.render_detached();
+
+        signal.set(2);
+        app.wait_for_render_request();
+    }
+}
@@ -0,0 +1,504 @@
+//! Application-wide store with selector subscriptions
+//!
+//! A [`Store`] holds state shared by the whole app. It is created up front,
+//! registered with the app through [`AppBuilder::store`], and can be written
+//! from input handlers, commands and background threads. Components read it
+//! with [`use_selector`], which subscribes to one slice of the state: writes
+//! that leave every subscribed slice unchanged do not re-render.
+//!
+//! # Example
+//!
+//! ```ignore
+//! #[derive(Default)]
+//! struct AppState {
+//!     unread: usize,
+//!     messages: Vec<String>,
+//! }
+//!
+//! fn badge() -> Element {
+//!     let unread = use_selector(|s: &AppState| &s.unread);
+//!     Text::new(format!("{} unread", unread)).into_element()
+//! }
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let store = Store::new(AppState::default());
+//!
+//!     let inbox = store.clone();
+//!     std::thread::spawn(move || {
+//!         inbox.update(|s| {
+//!             s.messages.push("hello".into());
+//!             s.unread += 1;
+//!         });
+//!     });
+//!
+//!     render(badge).store(store).run()
+//! }
+//! ```
+//!
+//! [`AppBuilder::store`]: crate::renderer::AppBuilder::store
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard};
+
+use crate::core::Element;
+use crate::hooks::context::{ContextId, SyncRenderCallback, current_context};
+use crate::hooks::get_app_context;
+use crate::hooks::use_context::{provide, provided};
+use crate::renderer::render_handle;
+
+/// Stores registered with an app, keyed by state type
+pub(crate) type StoreMap = Arc<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>;
+
+type ChangeCheck<S> = Arc<dyn Fn(&S) -> bool + Send + Sync>;
+
+/// A [`use_selector`] subscription: its change check and the render
+/// callback of the component that made it
+struct Subscriber<S> {
+    id: u64,
+    check: ChangeCheck<S>,
+    render_callback: Option<SyncRenderCallback>,
+}
+
+/// Shared application state
+///
+/// Cloning a store yields another handle to the same state. Writes request a
+/// re-render only from the components whose slice selected with
+/// [`use_selector`] changed.
+pub struct Store<S> {
+    inner: Arc<StoreInner<S>>,
+}
+
+struct StoreInner<S> {
+    state: RwLock<S>,
+    subscribers: Mutex<Vec<Subscriber<S>>>,
+    next_id: AtomicU64,
+}
+
+impl<S> Clone for Store<S> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S: Send + Sync + 'static> Store<S> {
+    /// Create a store with its initial state
+    pub fn new(state: S) -> Self {
+        Self {
+            inner: Arc::new(StoreInner {
+                state: RwLock::new(state),
+                subscribers: Mutex::new(Vec::new()),
+                next_id: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Get a clone of the whole state
+    pub fn get(&self) -> S
+    where
+        S: Clone,
+    {
+        self.read().clone()
+    }
+
+    /// Read the state
+    pub fn with<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        f(&self.read())
+    }
+
+    /// Replace the state
+    pub fn set(&self, state: S) {
+        self.update(|s| *s = state);
+    }
+
+    /// Modify the state, re-rendering if a selected slice changed
+    pub fn update(&self, f: impl FnOnce(&mut S)) {
+        {
+            let mut state = self
+                .inner
+                .state
+                .write()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&mut state);
+        }
+
+        self.notify_changed();
+    }
+
+    /// Provide this store to the subtree built by `children`
+    ///
+    /// [`use_store`] and [`use_selector`] inside the subtree find this store
+    /// before one registered with the app.
+    pub fn provider<F>(&self, children: F) -> Element
+    where
+        F: FnOnce() -> Element,
+    {
+        provide(ContextId::of::<Store<S>>(), Rc::new(self.clone()), children)
+    }
+
+    /// Number of live [`use_selector`] subscriptions
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers().len()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, S> {
+        self.inner
+            .state
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn subscribers(&self) -> MutexGuard<'_, Vec<Subscriber<S>>> {
+        self.inner
+            .subscribers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Request a render from every subscriber whose selected slice changed
+    fn notify_changed(&self) {
+        // Clone the checks so selectors run without the subscriber lock held
+        let subscribers: Vec<(ChangeCheck<S>, Option<SyncRenderCallback>)> = self
+            .subscribers()
+            .iter()
+            .map(|s| (s.check.clone(), s.render_callback.clone()))
+            .collect();
+        let changed: Vec<Option<SyncRenderCallback>> = {
+            let state = self.read();
+            subscribers
+                .into_iter()
+                .filter(|(check, _)| check(&state))
+                .map(|(_, callback)| callback)
+                .collect()
+        };
+
+        let mut app_render = false;
+        for callback in changed {
+            match callback {
+                Some(callback) => callback(),
+                None => app_render = true,
+            }
+        }
+        if app_render && let Some(handle) = render_handle() {
+            handle.request_render();
+        }
+    }
+
+    fn subscribe(&self, check: ChangeCheck<S>, render_callback: Option<SyncRenderCallback>) -> u64 {
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+        self.subscribers().push(Subscriber {
+            id,
+            check,
+            render_callback,
+        });
+        id
+    }
+
+    fn resubscribe(
+        &self,
+        id: u64,
+        check: ChangeCheck<S>,
+        render_callback: Option<SyncRenderCallback>,
+    ) {
+        if let Some(entry) = self.subscribers().iter_mut().find(|s| s.id == id) {
+            entry.check = check;
+            entry.render_callback = render_callback;
+        }
+    }
+
+    fn unsubscribe(&self, id: u64) {
+        self.subscribers().retain(|s| s.id != id);
+    }
+}
+
+impl<S: Send + Sync + std::fmt::Debug + 'static> std::fmt::Debug for Store<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|state| write!(f, "Store({:?})", state))
+    }
+}
+
+/// Subscription held in a component's hook slot, removed on unmount
+struct Subscription<S: Send + Sync + 'static> {
+    store: Store<S>,
+    id: u64,
+}
+
+impl<S: Send + Sync + 'static> Drop for Subscription<S> {
+    fn drop(&mut self) {
+        self.store.unsubscribe(self.id);
+    }
+}
+
+/// Internal storage for a selector hook
+struct SelectorStorage<S: Send + Sync + 'static, T> {
+    subscription: Arc<Subscription<S>>,
+    last: Arc<Mutex<T>>,
+}
+
+impl<S: Send + Sync + 'static, T> Clone for SelectorStorage<S, T> {
+    fn clone(&self) -> Self {
+        Self {
+            subscription: self.subscription.clone(),
+            last: self.last.clone(),
+        }
+    }
+}
+
+/// Get the app's store for state type `S`
+///
+/// Looks for a store provided to the subtree with [`Store::provider`]
+/// first, then for one registered with the running app. Use the returned
+/// handle to write to the store from handlers and commands.
+///
+/// # Panics
+///
+/// Panics if no store of type `S` is available.
+pub fn use_store<S: Send + Sync + 'static>() -> Store<S> {
+    find_store().unwrap_or_else(|| {
+        panic!(
+            "use_store: no Store<{}> registered with the app",
+            std::any::type_name::<S>()
+        )
+    })
+}
+
+fn find_store<S: Send + Sync + 'static>() -> Option<Store<S>> {
+    provided::<Store<S>>(ContextId::of::<Store<S>>()).or_else(|| get_app_context()?.store::<S>())
+}
+
+/// Subscribe to a slice of the app's [`Store`]
+///
+/// Returns a clone of the selected value. A store write re-renders the
+/// calling component only if the selected value differs (by `PartialEq`)
+/// from the one returned here.
+///
+/// # Panics
+///
+/// Panics if no store of type `S` is available (see [`use_store`]).
+pub fn use_selector<S, T, F>(selector: F) -> T
+where
+    S: Send + Sync + 'static,
+    T: Clone + PartialEq + Send + 'static,
+    F: Fn(&S) -> &T + Send + Sync + 'static,
+{
+    let store = use_store::<S>();
+    let selector = Arc::new(selector);
+    let selected = store.with(|state| selector(state).clone());
+
+    let ctx = current_context().expect("use_selector must be called within a component");
+    let mut ctx_ref = ctx.borrow_mut();
+    let render_callback = ctx_ref.get_sync_render_callback();
+
+    let storage = ctx_ref
+        .use_hook(|| {
+            let last = Arc::new(Mutex::new(selected.clone()));
+            let id = store.subscribe(change_check(&selector, &last), render_callback.clone());
+            SelectorStorage {
+                subscription: Arc::new(Subscription {
+                    store: store.clone(),
+                    id,
+                }),
+                last,
+            }
+        })
+        .get::<SelectorStorage<S, T>>()
+        .expect("use_selector hook order changed between renders");
+
+    *storage.last.lock().unwrap_or_else(|p| p.into_inner()) = selected.clone();
+    let subscription = &storage.subscription;
+    subscription.store.resubscribe(
+        subscription.id,
+        change_check(&selector, &storage.last),
+        render_callback,
+    );
+
+    selected
+}
+
+fn change_check<S, T, F>(selector: &Arc<F>, last: &Arc<Mutex<T>>) -> ChangeCheck<S>
+where
+    S: 'static,
+    T: PartialEq + Send + 'static,
+    F: Fn(&S) -> &T + Send + Sync + 'static,
+{
+    let selector = selector.clone();
+    let last = last.clone();
+    Arc::new(move |state| *last.lock().unwrap_or_else(|p| p.into_inner()) != *selector(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Component, Text};
+    use crate::core::Element;
+    use crate::hooks::context::{HookContext, with_hooks};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::atomic::AtomicUsize;
+
+    #[derive(Debug, Default)]
+    struct AppState {
+        count: i32,
+        name: String,
+    }
+
+    fn setup() -> (Rc<RefCell<HookContext>>, Arc<AtomicUsize>) {
+        let renders = Arc::new(AtomicUsize::new(0));
+        let counter = renders.clone();
+        let ctx = Rc::new(RefCell::new(HookContext::new()));
+        ctx.borrow_mut().set_sync_render_callback(Arc::new(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+        (ctx, renders)
+    }
+
+    fn render_count(ctx: &Rc<RefCell<HookContext>>, store: &Store<AppState>) -> i32 {
+        with_hooks(ctx.clone(), || {
+            store.provider(|| {
+                let count = use_selector(|s: &AppState| &s.count);
+                Text::new(count.to_string()).into_element()
+            })
+        })
+        .get_text()
+        .unwrap()
+        .parse()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_only_selected_changes_render() {
+        let (ctx, renders) = setup();
+        let store = Store::new(AppState::default());
+        assert_eq!(render_count(&ctx, &store), 0);
+
+        store.update(|s| s.name = "unrelated".into());
+        assert_eq!(renders.load(Ordering::SeqCst), 0);
+
+        store.update(|s| s.count += 1);
+        assert_eq!(renders.load(Ordering::SeqCst), 1);
+        assert_eq!(render_count(&ctx, &store), 1);
+
+        // Writing the value that was last rendered is not a change
+        store.update(|s| s.count = 1);
+        assert_eq!(renders.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_writes_from_threads() {
+        let (ctx, renders) = setup();
+        let store = Store::new(AppState::default());
+        render_count(&ctx, &store);
+
+        let writer = store.clone();
+        std::thread::spawn(move || writer.update(|s| s.count = 7))
+            .join()
+            .unwrap();
+
+        assert_eq!(renders.load(Ordering::SeqCst), 1);
+        assert_eq!(render_count(&ctx, &store), 7);
+    }
+
+    #[test]
+    fn test_unmount_unsubscribes() {
+        fn badge(_: &()) -> Element {
+            let name = use_selector(|s: &AppState| &s.name);
+            Text::new(name).into_element()
+        }
+
+        let (ctx, _) = setup();
+        let store = Store::new(AppState::default());
+
+        for show in [true, false] {
+            with_hooks(ctx.clone(), || {
+                store.provider(|| {
+                    if show {
+                        Component::new(badge, ()).into_element()
+                    } else {
+                        Text::new("").into_element()
+                    }
+                })
+            });
+            assert_eq!(store.subscriber_count(), usize::from(show));
+        }
+    }
+
+    #[test]
+    fn test_memoized_subscribers_each_rerender() {
+        use crate::components::{Box, memo};
+
+        let (ctx, renders) = setup();
+        let store = Store::new(AppState::default());
+        let render = || {
+            let element = with_hooks(ctx.clone(), || {
+                store.provider(|| {
+                    Box::new()
+                        .child(
+                            memo((), |_| {
+                                let count = use_selector(|s: &AppState| &s.count);
+                                Text::new(count.to_string()).into_element()
+                            })
+                            .into_element(),
+                        )
+                        .child(
+                            memo((), |_| {
+                                let name = use_selector(|s: &AppState| &s.name);
+                                Text::new(name).into_element()
+                            })
+                            .into_element(),
+                        )
+                        .into_element()
+                })
+            });
+            element
+                .children
+                .iter()
+                .map(|child| child.get_text().unwrap_or_default().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(render(), vec!["0", ""]);
+
+        // Each write invalidates the memo whose slice changed, and only it
+        store.update(|s| s.count = 1);
+        assert_eq!(renders.load(Ordering::SeqCst), 1);
+        assert_eq!(render(), vec!["1", ""]);
+
+        store.update(|s| s.name = "x".into());
+        assert_eq!(renders.load(Ordering::SeqCst), 2);
+        assert_eq!(render(), vec!["1", "x"]);
+    }
+
+    #[test]
+    fn test_store_from_app_context() {
+        let store = Store::new(AppState {
+            count: 3,
+            ..Default::default()
+        });
+        let mut stores: HashMap<TypeId, Arc<dyn Any + Send + Sync>> = HashMap::new();
+        stores.insert(TypeId::of::<AppState>(), Arc::new(store.clone()));
+
+        struct NoopSink;
+
+        impl crate::renderer::AppSink for NoopSink {
+            fn request_render(&self) {}
+            fn println(&self, _message: crate::renderer::Printable) {}
+            fn enter_alt_screen(&self) {}
+            fn exit_alt_screen(&self) {}
+            fn is_alt_screen(&self) -> bool {
+                false
+            }
+        }
+
+        let app = crate::hooks::AppContext::new(
+            Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            crate::renderer::RenderHandle::new(Arc::new(NoopSink)),
+        )
+        .with_stores(Arc::new(stores));
+        assert_eq!(app.store::<AppState>().unwrap().with(|s| s.count), 3);
+        assert!(app.store::<String>().is_none());
+    }
+}
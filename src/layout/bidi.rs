@@ -0,0 +1,163 @@
+//! Bidirectional text reordering
+//!
+//! Text is wrapped and measured in logical order. Rows containing
+//! right-to-left scripts are reordered into visual order with the Unicode
+//! Bidirectional Algorithm just before painting; reordering moves cells
+//! around within a row but never changes its width.
+
+use unicode_bidi::{BidiClass, BidiInfo, Level, bidi_class};
+use unicode_segmentation::UnicodeSegmentation;
+
+use super::measure::{TextFragment, TextLine};
+use crate::core::TextDirection;
+
+/// Check if text with the given direction reads right to left
+///
+/// With [`TextDirection::Auto`] the first strong character decides, as for
+/// a paragraph in the Unicode Bidirectional Algorithm.
+pub fn is_rtl(text: &str, direction: TextDirection) -> bool {
+    match direction {
+        TextDirection::Ltr => false,
+        TextDirection::Rtl => true,
+        TextDirection::Auto => text
+            .chars()
+            .find_map(|c| match bidi_class(c) {
+                BidiClass::L => Some(false),
+                BidiClass::R | BidiClass::AL => Some(true),
+                _ => None,
+            })
+            .unwrap_or(false),
+    }
+}
+
+/// Check if text contains characters that can reorder a row
+fn has_rtl(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(
+            bidi_class(c),
+            BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+        )
+    })
+}
+
+/// Reorder a laid-out row into visual order
+///
+/// `rtl` is the base direction of the text the row belongs to. Fragments
+/// keep the span they came from, so styles follow the reordered text.
+pub fn reorder_line(line: &TextLine, rtl: bool) -> TextLine {
+    if !rtl && !line.fragments.iter().any(|f| has_rtl(&f.text)) {
+        return line.clone();
+    }
+
+    let text = line.text();
+    let mut bounds = Vec::with_capacity(line.fragments.len());
+    let mut start = 0;
+    for fragment in &line.fragments {
+        bounds.push((start, start + fragment.text.len(), fragment.span));
+        start += fragment.text.len();
+    }
+
+    let level = if rtl { Level::rtl() } else { Level::ltr() };
+    let info = BidiInfo::new(&text, Some(level));
+    let mut visual = TextLine {
+        fragments: Vec::new(),
+        width: line.width,
+    };
+
+    for paragraph in &info.paragraphs {
+        let (levels, runs) = info.visual_runs(paragraph, paragraph.range.clone());
+        for run in runs {
+            let pieces = bounds.iter().filter_map(|&(start, end, span)| {
+                let (start, end) = (start.max(run.start), end.min(run.end));
+                (start < end).then(|| (span, &text[start..end]))
+            });
+
+            if levels[run.start].is_rtl() {
+                let pieces: Vec<_> = pieces.collect();
+                for (span, piece) in pieces.into_iter().rev() {
+                    let reversed: String = piece.graphemes(true).rev().map(mirror).collect();
+                    push_fragment(&mut visual, span, &reversed);
+                }
+            } else {
+                for (span, piece) in pieces {
+                    push_fragment(&mut visual, span, piece);
+                }
+            }
+        }
+    }
+
+    visual
+}
+
+fn push_fragment(line: &mut TextLine, span: usize, text: &str) {
+    match line.fragments.last_mut() {
+        Some(fragment) if fragment.span == span => fragment.text.push_str(text),
+        _ => line.fragments.push(TextFragment {
+            span,
+            text: text.to_string(),
+        }),
+    }
+}
+
+/// Mirrored form of paired punctuation shown right to left
+fn mirror(grapheme: &str) -> &str {
+    match grapheme {
+        "(" => ")",
+        ")" => "(",
+        "[" => "]",
+        "]" => "[",
+        "{" => "}",
+        "}" => "{",
+        "<" => ">",
+        ">" => "<",
+        "«" => "»",
+        "»" => "«",
+        _ => grapheme,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::TextWrap;
+    use crate::layout::layout_text;
+
+    fn visual(spans: &[&str], rtl: bool) -> TextLine {
+        let lines = layout_text(spans, None, TextWrap::Wrap, "…");
+        reorder_line(&lines[0], rtl)
+    }
+
+    #[test]
+    fn test_ltr_text_is_unchanged() {
+        let line = visual(&["hello ", "world"], false);
+        assert_eq!(line.text(), "hello world");
+        assert_eq!(line.fragments.len(), 2);
+    }
+
+    #[test]
+    fn test_rtl_words_are_reversed() {
+        // "שלום עולם" (hello world) shows right to left
+        assert_eq!(visual(&["שלום עולם"], true).text(), "םלוע םולש");
+        // Embedded Latin keeps its own order inside the RTL run
+        assert_eq!(visual(&["אבג abc דה"], true).text(), "הד abc גבא");
+        // RTL words inside an LTR paragraph are reversed in place
+        assert_eq!(visual(&["say שלום now"], false).text(), "say םולש now");
+    }
+
+    #[test]
+    fn test_reordering_keeps_spans_and_width() {
+        let line = visual(&["אב", "גד (x)"], true);
+        assert_eq!(line.width, 8);
+        assert_eq!(line.text(), "(x) דגבא");
+        assert_eq!(line.fragments.len(), 2);
+        assert_eq!(line.fragments[0].span, 1);
+        assert_eq!(line.fragments[1].text, "בא");
+    }
+
+    #[test]
+    fn test_auto_direction() {
+        assert!(is_rtl("  مرحبا hello", TextDirection::Auto));
+        assert!(!is_rtl("123 hello مرحبا", TextDirection::Auto));
+        assert!(is_rtl("hello", TextDirection::Rtl));
+    }
+}
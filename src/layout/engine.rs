@@ -1,8 +1,9 @@
 //! Layout engine using Taffy
 
-use crate::core::{Element, ElementId, ElementType};
-use crate::layout::measure::measure_text_width;
-use std::collections::HashMap;
+use crate::core::{Children, Dimension, Element, ElementId, ElementType, Style, TextWrap};
+use crate::layout::measure::{ambiguous_is_wide, measure_text_layout, min_content_width};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
 use taffy::{AvailableSpace, NodeId, TaffyTree};
 
 /// Computed layout for an element
@@ -14,18 +15,97 @@ pub struct Layout {
     pub height: f32,
 }
 
+/// Timing and cache statistics of a [`LayoutEngine`]
+///
+/// Per-frame fields describe the most recent `compute` call.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LayoutStats {
+    /// Number of layouts computed so far
+    pub frames: u64,
+    /// Time spent updating the tree and computing the last layout
+    pub duration: Duration,
+    /// Time spent across all layouts
+    pub total_duration: Duration,
+    /// Nodes in the layout tree
+    pub nodes: usize,
+    /// Nodes created in the last frame instead of reused
+    pub nodes_created: usize,
+    /// Reused nodes whose style or text changed in the last frame
+    pub nodes_changed: usize,
+    /// Layout passes run in the last frame, including `Calc` re-layouts
+    pub layout_passes: usize,
+    /// Text measurements answered from the cache in the last frame
+    pub measure_hits: usize,
+    /// Text measurements computed in the last frame
+    pub measure_misses: usize,
+}
+
 /// Context stored for each node (for text measurement)
 #[derive(Clone)]
 struct NodeContext {
     #[allow(dead_code)]
     element_id: ElementId,
     text_content: Option<String>,
+    text_wrap: TextWrap,
+    ellipsis: String,
+}
+
+impl NodeContext {
+    fn new(element_id: ElementId, element: &Element) -> Self {
+        Self {
+            element_id,
+            text_content: measured_text(element),
+            text_wrap: element.style.text_wrap,
+            ellipsis: element.style.ellipsis().to_string(),
+        }
+    }
+}
+
+/// Text a node is measured by; rich text lines are joined with newlines
+fn measured_text(element: &Element) -> Option<String> {
+    if element.spans.is_some() {
+        Some(
+            element
+                .text_pieces()
+                .into_iter()
+                .map(|(text, _)| text)
+                .collect(),
+        )
+    } else {
+        element.text_content.clone()
+    }
+}
+
+/// An element from the previous frame, kept for reconciliation
+struct RetainedNode {
+    id: ElementId,
+    node: NodeId,
+    element_type: ElementType,
+    key: Option<String>,
+    style: Style,
+    text_content: Option<String>,
+    children: Vec<RetainedNode>,
 }
 
 /// Layout engine that computes element positions
 pub struct LayoutEngine {
     taffy: TaffyTree<NodeContext>,
     node_map: HashMap<ElementId, NodeId>,
+    /// Element tree from the last `compute_incremental` call
+    retained: Option<RetainedNode>,
+    /// Portal nodes found while building, attached to the root node
+    portals: Vec<NodeId>,
+    /// Nodes with sizes Taffy can't express, with their element style
+    deferred: Vec<(NodeId, Style)>,
+    /// IDs assigned during reconciliation, in pre-order
+    assigned_ids: Vec<ElementId>,
+    /// IDs elements of the last frame were created with, mapped to the
+    /// stable IDs reconciliation gave them
+    renamed: HashMap<ElementId, ElementId>,
+    /// Whether reconciled elements take over the previous frame's IDs
+    stable_ids: bool,
+    measure_cache: MeasureCache,
+    stats: LayoutStats,
 }
 
 impl LayoutEngine {
@@ -33,6 +113,14 @@ impl LayoutEngine {
         Self {
             taffy: TaffyTree::new(),
             node_map: HashMap::new(),
+            retained: None,
+            portals: Vec::new(),
+            deferred: Vec::new(),
+            assigned_ids: Vec::new(),
+            renamed: HashMap::new(),
+            stable_ids: false,
+            measure_cache: MeasureCache::default(),
+            stats: LayoutStats::default(),
         }
     }
 
@@ -40,10 +128,36 @@ impl LayoutEngine {
     pub fn build_tree(&mut self, element: &Element) -> Option<NodeId> {
         self.taffy.clear();
         self.node_map.clear();
-        self.build_node(element)
+        self.retained = None;
+        self.portals.clear();
+        self.deferred.clear();
+        self.build_node(element, true)
     }
 
-    fn build_node(&mut self, element: &Element) -> Option<NodeId> {
+    /// Split built children into layout children and portals
+    ///
+    /// Portals are laid out as children of the root node rather than of
+    /// their parent, so they are taken out here and handed to the root.
+    fn layout_children(
+        &mut self,
+        children: impl IntoIterator<Item = (ElementType, NodeId)>,
+        is_root: bool,
+    ) -> Vec<NodeId> {
+        let mut nodes = Vec::new();
+        for (element_type, node) in children {
+            if element_type == ElementType::Portal {
+                self.portals.push(node);
+            } else {
+                nodes.push(node);
+            }
+        }
+        if is_root {
+            nodes.append(&mut self.portals);
+        }
+        nodes
+    }
+
+    fn build_node(&mut self, element: &Element, is_root: bool) -> Option<NodeId> {
         // Skip virtual text nodes (they don't have layout)
         if element.element_type == ElementType::VirtualText {
             return None;
@@ -52,16 +166,14 @@ impl LayoutEngine {
         let taffy_style = element.style.to_taffy();
 
         // Build children first
-        let child_nodes: Vec<NodeId> = element
+        let children: Vec<(ElementType, NodeId)> = element
             .children
             .iter()
-            .filter_map(|child| self.build_node(child))
+            .filter_map(|child| Some((child.element_type, self.build_node(child, false)?)))
             .collect();
+        let child_nodes = self.layout_children(children, is_root);
 
-        let context = NodeContext {
-            element_id: element.id,
-            text_content: element.text_content.clone(),
-        };
+        let context = NodeContext::new(element.id, element);
 
         // Create node with measure function for text
         let node_id = if element.is_text() {
@@ -79,28 +191,467 @@ impl LayoutEngine {
         };
 
         self.node_map.insert(element.id, node_id);
+        self.defer_sizes(node_id, &element.style);
+        self.stats.nodes_created += 1;
         Some(node_id)
     }
 
+    /// Reconcile an element tree against the tree from the previous frame
+    ///
+    /// Elements that match a previous element by type and key (unkeyed
+    /// siblings match by position) take over its `ElementId` and Taffy node,
+    /// so IDs stay stable across frames. Only nodes whose style, text or
+    /// children changed are marked dirty; Taffy keeps its cached layout for
+    /// everything else. Nodes that disappeared are removed from the tree.
+    /// Until the next frame, the IDs reused elements were created with (which
+    /// components may have recorded while rendering) resolve to their stable
+    /// IDs; see [`resolve_id`](Self::resolve_id).
+    pub fn reconcile(&mut self, root: &mut Element) -> Option<NodeId> {
+        self.stable_ids = true;
+        let root_node = self.reconcile_tree(root);
+
+        let mut ids = std::mem::take(&mut self.assigned_ids).into_iter();
+        assign_ids(root, &mut ids);
+        root_node
+    }
+
+    /// Reconcile against the previous frame without touching element IDs
+    ///
+    /// Nodes are reused exactly as in [`reconcile`](Self::reconcile), but
+    /// layouts are looked up by the IDs the elements already have.
+    fn reconcile_in_place(&mut self, root: &Element) -> Option<NodeId> {
+        self.stable_ids = false;
+        let root_node = self.reconcile_tree(root);
+        self.assigned_ids.clear();
+        root_node
+    }
+
+    fn reconcile_tree(&mut self, root: &Element) -> Option<NodeId> {
+        let previous = self.retained.take();
+        self.node_map.clear();
+        self.renamed.clear();
+        self.portals.clear();
+        self.deferred.clear();
+        self.assigned_ids.clear();
+        self.retained = self.reconcile_node(root, previous, true);
+        self.retained.as_ref().map(|retained| retained.node)
+    }
+
+    fn reconcile_node(
+        &mut self,
+        element: &Element,
+        previous: Option<RetainedNode>,
+        is_root: bool,
+    ) -> Option<RetainedNode> {
+        let slot = self.assigned_ids.len();
+        self.assigned_ids.push(element.id);
+
+        // Only reuse a node for an element of the same type and key
+        let previous = match previous {
+            Some(prev) if prev.element_type == element.element_type && prev.key == element.key => {
+                Some(prev)
+            }
+            Some(prev) => {
+                self.remove_retained(prev);
+                None
+            }
+            None => None,
+        };
+
+        // Skip virtual text nodes (they don't have layout)
+        if element.element_type == ElementType::VirtualText {
+            if let Some(prev) = previous {
+                self.remove_retained(prev);
+            }
+            return None;
+        }
+
+        let Some(mut retained) = previous else {
+            return self.create_retained(element, is_root);
+        };
+
+        if self.stable_ids {
+            self.assigned_ids[slot] = retained.id;
+            if element.id != retained.id {
+                self.renamed.insert(element.id, retained.id);
+            }
+        } else {
+            retained.id = element.id;
+        }
+
+        let mut changed = false;
+        if retained.style != element.style {
+            let _ = self
+                .taffy
+                .set_style(retained.node, element.style.to_taffy());
+            retained.style = element.style.clone();
+            changed = true;
+        }
+
+        let text_content = measured_text(element);
+        if changed || retained.text_content != text_content {
+            let context = NodeContext::new(retained.id, element);
+            let _ = self.taffy.set_node_context(retained.node, Some(context));
+            let _ = self.taffy.mark_dirty(retained.node);
+            retained.text_content = text_content;
+            changed = true;
+        }
+        if changed {
+            self.stats.nodes_changed += 1;
+        }
+
+        let old_children = std::mem::take(&mut retained.children);
+        retained.children = self.reconcile_children(&element.children, old_children);
+
+        // Text nodes are measured leaves; their children never join the layout
+        if !element.is_text() {
+            let children = retained.children.iter().map(|c| (c.element_type, c.node));
+            let new_nodes = self.layout_children(children, is_root);
+            if self.taffy.children(retained.node).ok().as_ref() != Some(&new_nodes) {
+                let _ = self.taffy.set_children(retained.node, &new_nodes);
+            }
+        }
+
+        self.node_map.insert(retained.id, retained.node);
+        self.defer_sizes(retained.node, &element.style);
+        Some(retained)
+    }
+
+    fn reconcile_children(
+        &mut self,
+        children: &Children,
+        previous: Vec<RetainedNode>,
+    ) -> Vec<RetainedNode> {
+        let mut keyed: HashMap<String, RetainedNode> = HashMap::new();
+        let mut unkeyed: VecDeque<RetainedNode> = VecDeque::new();
+        let mut duplicates = Vec::new();
+
+        for child in previous {
+            match child.key.clone() {
+                Some(key) => {
+                    if let Some(duplicate) = keyed.insert(key, child) {
+                        duplicates.push(duplicate);
+                    }
+                }
+                None => unkeyed.push_back(child),
+            }
+        }
+
+        let mut retained = Vec::with_capacity(children.len());
+        for child in children.iter() {
+            let previous = match &child.key {
+                Some(key) => keyed.remove(key),
+                None => unkeyed.pop_front(),
+            };
+            if let Some(node) = self.reconcile_node(child, previous, false) {
+                retained.push(node);
+            }
+        }
+
+        for stale in keyed.into_values().chain(unkeyed).chain(duplicates) {
+            self.remove_retained(stale);
+        }
+
+        retained
+    }
+
+    fn create_retained(&mut self, element: &Element, is_root: bool) -> Option<RetainedNode> {
+        let children = self.reconcile_children(&element.children, Vec::new());
+        let child_nodes = if element.is_text() {
+            Vec::new()
+        } else {
+            let layout_children = children.iter().map(|c| (c.element_type, c.node));
+            self.layout_children(layout_children, is_root)
+        };
+
+        let context = NodeContext::new(element.id, element);
+
+        let node = if element.is_text() {
+            self.taffy
+                .new_leaf_with_context(element.style.to_taffy(), context)
+                .ok()?
+        } else {
+            let node = self
+                .taffy
+                .new_with_children(element.style.to_taffy(), &child_nodes)
+                .ok()?;
+            let _ = self.taffy.set_node_context(node, Some(context));
+            node
+        };
+
+        self.node_map.insert(element.id, node);
+        self.defer_sizes(node, &element.style);
+        self.stats.nodes_created += 1;
+        Some(RetainedNode {
+            id: element.id,
+            node,
+            element_type: element.element_type,
+            key: element.key.clone(),
+            style: element.style.clone(),
+            text_content: measured_text(element),
+            children,
+        })
+    }
+
+    fn remove_retained(&mut self, retained: RetainedNode) {
+        for child in retained.children {
+            self.remove_retained(child);
+        }
+        let _ = self.taffy.set_node_context(retained.node, None);
+        let _ = self.taffy.remove(retained.node);
+    }
+
     /// Compute layout for the tree
+    ///
+    /// The layout tree persists between calls: nodes are matched against the
+    /// previous tree like in [`compute_incremental`], and only changed ones
+    /// are laid out again. Element IDs are left as they are, so look layouts
+    /// up with the IDs of `root`.
+    ///
+    /// [`compute_incremental`]: LayoutEngine::compute_incremental
     pub fn compute(&mut self, root: &Element, width: u16, height: u16) {
-        if let Some(root_node) = self.build_tree(root) {
-            let _ = self.taffy.compute_layout_with_measure(
-                root_node,
+        let start = self.begin_frame();
+        if let Some(root_node) = self.reconcile_in_place(root) {
+            self.compute_node(root_node, width, height);
+        }
+        self.end_frame(start);
+    }
+
+    /// Compute layout for the tree, reusing nodes from the previous frame
+    ///
+    /// This reconciles `root` against the previous call (see [`reconcile`])
+    /// and assigns stable `ElementId`s to unchanged elements. Use this when
+    /// the same engine renders successive frames of one UI.
+    ///
+    /// [`reconcile`]: LayoutEngine::reconcile
+    pub fn compute_incremental(&mut self, root: &mut Element, width: u16, height: u16) {
+        let start = self.begin_frame();
+        if let Some(root_node) = self.reconcile(root) {
+            self.compute_node(root_node, width, height);
+        }
+        self.end_frame(start);
+    }
+
+    /// Timing and cache statistics
+    pub fn stats(&self) -> LayoutStats {
+        self.stats
+    }
+
+    fn begin_frame(&mut self) -> Instant {
+        self.stats.nodes_created = 0;
+        self.stats.nodes_changed = 0;
+        self.stats.layout_passes = 0;
+        if self.measure_cache.begin_frame() {
+            // Text widths changed with the ambiguous-width policy
+            for node in self.node_map.values() {
+                let _ = self.taffy.mark_dirty(*node);
+            }
+        }
+        Instant::now()
+    }
+
+    fn end_frame(&mut self, start: Instant) {
+        let duration = start.elapsed();
+        self.measure_cache.end_frame();
+        self.stats.frames += 1;
+        self.stats.duration = duration;
+        self.stats.total_duration += duration;
+        self.stats.nodes = self.node_count();
+        self.stats.measure_hits = self.measure_cache.hits;
+        self.stats.measure_misses = self.measure_cache.misses;
+    }
+
+    fn compute_node(&mut self, root_node: NodeId, width: u16, height: u16) {
+        if self.deferred.is_empty() {
+            self.run_layout(root_node, width, height);
+            return;
+        }
+
+        // Start every frame from the approximations in `to_taffy`, so sizes
+        // resolved last frame don't leak into this one
+        let deferred = std::mem::take(&mut self.deferred);
+        let mut styles: Vec<taffy::Style> =
+            deferred.iter().map(|(_, style)| style.to_taffy()).collect();
+
+        // Children come before their parents, so nested intrinsic sizes are
+        // fixed by the time an ancestor measures its content
+        for ((node, style), taffy_style) in deferred.iter().zip(styles.iter_mut()) {
+            self.resolve_intrinsic(*node, style, taffy_style);
+        }
+        self.run_layout(root_node, width, height);
+
+        // Each pass resolves `Calc` sizes against the parents' current
+        // layout; nested ones settle after one pass per level. Nodes keep
+        // last frame's resolved sizes, so an unchanged frame needs no pass.
+        let viewport = (width as f32, height as f32);
+        for _ in 0..MAX_CALC_PASSES {
+            let mut changed = false;
+            for ((node, style), taffy_style) in deferred.iter().zip(styles.iter_mut()) {
+                self.resolve_calc(*node, style, taffy_style, viewport);
+                if self.taffy.style(*node).ok() != Some(&*taffy_style) {
+                    let _ = self.taffy.set_style(*node, taffy_style.clone());
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+            self.run_layout(root_node, width, height);
+        }
+
+        self.deferred = deferred;
+    }
+
+    fn run_layout(&mut self, root_node: NodeId, width: u16, height: u16) {
+        self.stats.layout_passes += 1;
+        self.run_layout_in(
+            root_node,
+            taffy::Size {
+                width: AvailableSpace::Definite(width as f32),
+                height: AvailableSpace::Definite(height as f32),
+            },
+        );
+    }
+
+    fn run_layout_in(&mut self, node: NodeId, available: taffy::Size<AvailableSpace>) {
+        let cache = &mut self.measure_cache;
+        let _ = self.taffy.compute_layout_with_measure(
+            node,
+            available,
+            |known_dimensions, available_space, _node_id, node_context, _style| {
+                measure_text_node(known_dimensions, available_space, node_context, cache)
+            },
+        );
+    }
+
+    fn defer_sizes(&mut self, node: NodeId, style: &Style) {
+        if style.has_deferred_size() {
+            self.deferred.push((node, style.clone()));
+        }
+    }
+
+    /// Replace min-content, max-content and fit-content sizes with the
+    /// measured size of the node's content
+    fn resolve_intrinsic(&mut self, node: NodeId, style: &Style, taffy_style: &mut taffy::Style) {
+        let mut measured = false;
+        for axis in [Axis::Width, Axis::Height] {
+            let dims = axis.dimensions(style);
+            if !dims.iter().any(Dimension::is_intrinsic) {
+                continue;
+            }
+            measured = true;
+
+            // Measure with this axis unconstrained
+            let mut measuring = taffy_style.clone();
+            for field in axis.fields(&mut measuring) {
+                *field = taffy::Dimension::Auto;
+            }
+            let _ = self.taffy.set_style(node, measuring);
+            let min_content = self.measure_content(node, axis, AvailableSpace::MinContent);
+            let max_content = self.measure_content(node, axis, AvailableSpace::MaxContent);
+
+            for (dim, field) in dims.iter().zip(axis.fields(taffy_style)) {
+                let size = match *dim {
+                    Dimension::MinContent => min_content,
+                    Dimension::MaxContent => max_content,
+                    Dimension::FitContent(limit) => limit.min(max_content).max(min_content),
+                    _ => continue,
+                };
+                *field = taffy::Dimension::Length(size);
+            }
+        }
+        if measured {
+            let _ = self.taffy.set_style(node, taffy_style.clone());
+        }
+    }
+
+    fn measure_content(&mut self, node: NodeId, axis: Axis, space: AvailableSpace) -> f32 {
+        let available = match axis {
+            Axis::Width => taffy::Size {
+                width: space,
+                height: AvailableSpace::MaxContent,
+            },
+            Axis::Height => {
+                // Content height depends on the width it wraps at
+                let width = match self.taffy.style(node).map(|style| style.size.width) {
+                    Ok(taffy::Dimension::Length(width)) => AvailableSpace::Definite(width),
+                    _ => AvailableSpace::MaxContent,
+                };
                 taffy::Size {
-                    width: AvailableSpace::Definite(width as f32),
-                    height: AvailableSpace::Definite(height as f32),
-                },
-                |known_dimensions, available_space, _node_id, node_context, _style| {
-                    measure_text_node(known_dimensions, available_space, node_context)
-                },
-            );
+                    width,
+                    height: space,
+                }
+            }
+        };
+        self.run_layout_in(node, available);
+        self.taffy
+            .layout(node)
+            .map(|layout| match axis {
+                Axis::Width => layout.size.width,
+                Axis::Height => layout.size.height,
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Resolve `Calc` sizes against the parent's content box
+    fn resolve_calc(
+        &self,
+        node: NodeId,
+        style: &Style,
+        taffy_style: &mut taffy::Style,
+        viewport: (f32, f32),
+    ) {
+        let container = match self.taffy.parent(node) {
+            Some(parent) => match self.taffy.layout(parent) {
+                Ok(layout) => (
+                    layout.size.width
+                        - layout.padding.left
+                        - layout.padding.right
+                        - layout.border.left
+                        - layout.border.right,
+                    layout.size.height
+                        - layout.padding.top
+                        - layout.padding.bottom
+                        - layout.border.top
+                        - layout.border.bottom,
+                ),
+                Err(_) => return,
+            },
+            None => viewport,
+        };
+
+        for (axis, container) in [(Axis::Width, container.0), (Axis::Height, container.1)] {
+            let dims = axis.dimensions(style);
+            for (dim, field) in dims.iter().zip(axis.fields(taffy_style)) {
+                if let Dimension::Calc { percent, points } = *dim {
+                    let size = (container * percent / 100.0 + points).max(0.0);
+                    *field = taffy::Dimension::Length(size);
+                }
+            }
         }
     }
 
+    /// Number of nodes currently held in the layout tree
+    pub fn node_count(&self) -> usize {
+        self.taffy.total_node_count()
+    }
+
+    /// Stable ID of an element of the last frame
+    ///
+    /// Reconciliation gives reused elements the ID they had before; this maps
+    /// the ID such an element was created with to the one it now carries.
+    pub fn resolve_id(&self, element_id: ElementId) -> ElementId {
+        self.renamed.get(&element_id).copied().unwrap_or(element_id)
+    }
+
+    /// IDs of the last frame's elements that reconciliation replaced
+    pub(crate) fn renamed_ids(&self) -> HashMap<ElementId, ElementId> {
+        self.renamed.clone()
+    }
+
     /// Get computed layout for an element
     pub fn get_layout(&self, element_id: ElementId) -> Option<Layout> {
-        let node_id = self.node_map.get(&element_id)?;
+        let node_id = self.node_map.get(&self.resolve_id(element_id))?;
         let layout = self.taffy.layout(*node_id).ok()?;
 
         Some(Layout {
@@ -131,58 +682,219 @@ impl LayoutEngine {
     }
 }
 
+/// Upper bound on layout passes spent resolving nested `Calc` sizes
+const MAX_CALC_PASSES: usize = 8;
+
+/// Layout axis, for resolving sizes one direction at a time
+#[derive(Clone, Copy)]
+enum Axis {
+    Width,
+    Height,
+}
+
+impl Axis {
+    /// Size, min size and max size along this axis
+    fn dimensions(self, style: &Style) -> [Dimension; 3] {
+        match self {
+            Axis::Width => [style.width, style.min_width, style.max_width],
+            Axis::Height => [style.height, style.min_height, style.max_height],
+        }
+    }
+
+    /// The Taffy fields matching [`Axis::dimensions`]
+    fn fields(self, style: &mut taffy::Style) -> [&mut taffy::Dimension; 3] {
+        match self {
+            Axis::Width => [
+                &mut style.size.width,
+                &mut style.min_size.width,
+                &mut style.max_size.width,
+            ],
+            Axis::Height => [
+                &mut style.size.height,
+                &mut style.min_size.height,
+                &mut style.max_size.height,
+            ],
+        }
+    }
+}
+
 impl Default for LayoutEngine {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Write IDs recorded during reconciliation back into the element tree
+fn assign_ids(element: &mut Element, ids: &mut impl Iterator<Item = ElementId>) {
+    if let Some(id) = ids.next() {
+        element.id = id;
+    }
+    if element.element_type == ElementType::VirtualText {
+        return;
+    }
+    for child in element.children.iter_mut() {
+        assign_ids(child, ids);
+    }
+}
+
+/// Text sizes kept across frames, keyed by text, wrap mode and wrap width
+///
+/// Taffy only caches measurements until a node is marked dirty; this cache
+/// also survives resizes of the parent and re-created nodes, so unchanged
+/// lines of a long log are measured once.
+#[derive(Default)]
+struct MeasureCache {
+    entries: HashMap<String, CachedText>,
+    frame: u64,
+    /// Ambiguous-width policy the entries were measured with
+    ambiguous_wide: bool,
+    /// Entries used in the current frame
+    used: usize,
+    hits: usize,
+    misses: usize,
+}
+
+struct CachedText {
+    /// Sizes by layout, most recent last
+    sizes: Vec<(TextLayoutKey, (f32, f32))>,
+    last_used: u64,
+}
+
+/// What a text's size depends on besides the text itself
+struct TextLayoutKey {
+    wrap_width: WrapWidth,
+    wrap: TextWrap,
+    ellipsis: String,
+}
+
+/// Width text is laid out at for measurement
+#[derive(Clone, Copy, PartialEq)]
+enum WrapWidth {
+    /// Only hard breaks apply
+    Unbounded,
+    /// As narrow as the text allows
+    MinContent,
+    Cells(usize),
+}
+
+/// Minimum number of cache entries before unused ones are dropped
+const MEASURE_CACHE_MIN: usize = 1024;
+
+/// Layouts remembered per text
+const MEASURE_CACHE_WIDTHS: usize = 4;
+
+impl MeasureCache {
+    /// Start a frame; returns whether cached sizes were dropped
+    fn begin_frame(&mut self) -> bool {
+        self.frame += 1;
+        self.used = 0;
+        self.hits = 0;
+        self.misses = 0;
+
+        let wide = ambiguous_is_wide();
+        let invalidated = self.frame > 1 && wide != self.ambiguous_wide;
+        if invalidated {
+            self.entries.clear();
+        }
+        self.ambiguous_wide = wide;
+        invalidated
+    }
+
+    /// Drop entries not used this frame once the cache grows large
+    fn end_frame(&mut self) {
+        if self.entries.len() > MEASURE_CACHE_MIN.max(self.used * 2) {
+            let frame = self.frame;
+            self.entries.retain(|_, entry| entry.last_used == frame);
+        }
+    }
+
+    /// Width and height of a text node's text laid out at `wrap_width`
+    fn measure(&mut self, context: &NodeContext, text: &str, wrap_width: WrapWidth) -> (f32, f32) {
+        let frame = self.frame;
+        if !self.entries.contains_key(text) {
+            self.entries.insert(
+                text.to_string(),
+                CachedText {
+                    sizes: Vec::new(),
+                    last_used: 0,
+                },
+            );
+        }
+        let entry = self.entries.get_mut(text).expect("entry was just inserted");
+        if entry.last_used != frame {
+            entry.last_used = frame;
+            self.used += 1;
+        }
+
+        if let Some(&(_, size)) = entry.sizes.iter().find(|(key, _)| {
+            key.wrap_width == wrap_width
+                && key.wrap == context.text_wrap
+                && key.ellipsis == context.ellipsis
+        }) {
+            self.hits += 1;
+            return size;
+        }
+
+        self.misses += 1;
+        let max_width = match wrap_width {
+            WrapWidth::Unbounded => None,
+            WrapWidth::MinContent => Some(min_content_width(
+                text,
+                context.text_wrap,
+                &context.ellipsis,
+            )),
+            WrapWidth::Cells(width) => Some(width),
+        };
+        let (width, height) =
+            measure_text_layout(text, max_width, context.text_wrap, &context.ellipsis);
+        let size = (width as f32, height.max(1) as f32);
+        if entry.sizes.len() == MEASURE_CACHE_WIDTHS {
+            entry.sizes.remove(0);
+        }
+        let key = TextLayoutKey {
+            wrap_width,
+            wrap: context.text_wrap,
+            ellipsis: context.ellipsis.clone(),
+        };
+        entry.sizes.push((key, size));
+        size
+    }
+}
+
 /// Measure text content for layout
+///
+/// Text is wrapped at the node's known width, or else at the definite
+/// available width, the same way the renderer lays it out for painting.
 fn measure_text_node(
     known_dimensions: taffy::Size<Option<f32>>,
     available_space: taffy::Size<AvailableSpace>,
     node_context: Option<&mut NodeContext>,
+    cache: &mut MeasureCache,
 ) -> taffy::Size<f32> {
-    let text = node_context
-        .and_then(|ctx| ctx.text_content.as_ref())
-        .map(|s| s.as_str())
-        .unwrap_or("");
-
-    if text.is_empty() {
+    let Some(context) =
+        node_context.filter(|ctx| ctx.text_content.as_ref().is_some_and(|t| !t.is_empty()))
+    else {
         return taffy::Size {
             width: known_dimensions.width.unwrap_or(0.0),
             height: known_dimensions.height.unwrap_or(0.0),
         };
-    }
-
-    // Measure text using unicode-width
-    let text_width = measure_text_width(text) as f32;
-
-    // Calculate height considering text wrapping
-    let available_width = match available_space.width {
-        AvailableSpace::Definite(w) => Some(w as usize),
-        _ => None,
     };
+    let text = context.text_content.as_deref().unwrap_or_default();
 
-    let text_height = if let Some(max_width) = available_width {
-        if max_width > 0 && text_width > max_width as f32 {
-            // Text needs wrapping - calculate wrapped line count
-            use super::measure::wrap_text;
-            let wrapped = wrap_text(text, max_width);
-            wrapped.lines().count().max(1) as f32
-        } else {
-            text.lines().count().max(1) as f32
+    let wrap_width = match (known_dimensions.width, available_space.width) {
+        (Some(w), _) | (None, AvailableSpace::Definite(w)) => {
+            WrapWidth::Cells(w.round().max(0.0) as usize)
         }
-    } else {
-        text.lines().count().max(1) as f32
+        (None, AvailableSpace::MinContent) => WrapWidth::MinContent,
+        (None, AvailableSpace::MaxContent) => WrapWidth::Unbounded,
     };
+    let (text_width, text_height) = cache.measure(context, text, wrap_width);
 
     let width = known_dimensions
         .width
         .unwrap_or_else(|| match available_space.width {
             AvailableSpace::Definite(w) => text_width.min(w),
-            AvailableSpace::MinContent => text_width,
-            AvailableSpace::MaxContent => text_width,
+            _ => text_width,
         });
 
     let height = known_dimensions.height.unwrap_or(text_height);
@@ -228,4 +940,271 @@ mod tests {
         // "Hello World" is 11 characters wide
         assert!(layout.width >= 11.0);
     }
+
+    fn list(items: &[&str], keyed: bool) -> Element {
+        let mut root = Element::box_element();
+        root.style.flex_direction = crate::core::FlexDirection::Column;
+        for item in items {
+            let mut child = Element::text(*item);
+            if keyed {
+                child.key = Some(item.to_string());
+            }
+            root.add_child(child);
+        }
+        root
+    }
+
+    #[test]
+    fn test_incremental_reuses_ids() {
+        let mut engine = LayoutEngine::new();
+
+        let mut first = list(&["a", "b"], false);
+        engine.compute_incremental(&mut first, 80, 24);
+
+        let mut second = list(&["a", "b"], false);
+        let created = second.children.get(1).unwrap().id;
+        engine.compute_incremental(&mut second, 80, 24);
+
+        assert_eq!(first.id, second.id);
+        // The ID the element was created with resolves to its stable ID
+        assert_eq!(
+            engine.resolve_id(created),
+            second.children.get(1).unwrap().id
+        );
+        assert!(engine.get_layout(created).is_some());
+        assert_eq!(engine.node_map.len(), 3);
+        assert_eq!(
+            first.children.get(1).unwrap().id,
+            second.children.get(1).unwrap().id
+        );
+        assert!(
+            engine
+                .get_layout(second.children.get(1).unwrap().id)
+                .is_some()
+        );
+        assert_eq!(engine.node_count(), 3);
+    }
+
+    #[test]
+    fn test_incremental_keyed_reorder() {
+        let mut engine = LayoutEngine::new();
+
+        let mut first = list(&["a", "b", "c"], true);
+        engine.compute_incremental(&mut first, 80, 24);
+        let id_of = |root: &Element, index: usize| root.children.get(index).unwrap().id;
+        let (a, c) = (id_of(&first, 0), id_of(&first, 2));
+
+        let mut second = list(&["c", "a"], true);
+        engine.compute_incremental(&mut second, 80, 24);
+
+        assert_eq!(id_of(&second, 0), c);
+        assert_eq!(id_of(&second, 1), a);
+        assert_eq!(engine.node_count(), 3);
+
+        // Positions follow the new order
+        assert_eq!(engine.get_layout(c).unwrap().y, 0.0);
+        assert_eq!(engine.get_layout(a).unwrap().y, 1.0);
+    }
+
+    #[test]
+    fn test_incremental_marks_only_changed_dirty() {
+        let mut engine = LayoutEngine::new();
+
+        let mut first = list(&["short", "b"], false);
+        engine.compute_incremental(&mut first, 80, 24);
+        let root_node = engine.node_map[&first.id];
+        assert!(!engine.taffy.dirty(root_node).unwrap());
+
+        let mut same = list(&["short", "b"], false);
+        engine.reconcile(&mut same);
+        assert!(!engine.taffy.dirty(root_node).unwrap());
+
+        let mut changed = list(&["a much longer line", "b"], false);
+        engine.reconcile(&mut changed);
+        let unchanged_node = engine.node_map[&changed.children.get(1).unwrap().id];
+        assert!(engine.taffy.dirty(root_node).unwrap());
+        assert!(!engine.taffy.dirty(unchanged_node).unwrap());
+
+        engine.compute_incremental(&mut changed, 80, 24);
+        let width = engine
+            .get_layout(changed.children.get(0).unwrap().id)
+            .unwrap()
+            .width;
+        assert_eq!(width, 18.0);
+    }
+
+    #[test]
+    fn test_incremental_type_change_replaces_node() {
+        let mut engine = LayoutEngine::new();
+
+        let mut first = Element::box_element();
+        first.add_child(Element::text("a"));
+        engine.compute_incremental(&mut first, 80, 24);
+        let old_child = first.children.get(0).unwrap().id;
+
+        let mut second = Element::box_element();
+        second.add_child(Element::box_element());
+        engine.compute_incremental(&mut second, 80, 24);
+
+        assert_ne!(second.children.get(0).unwrap().id, old_child);
+        assert!(engine.get_layout(old_child).is_none());
+        assert_eq!(engine.node_count(), 2);
+    }
+
+    fn row(children: Vec<Element>) -> Element {
+        let mut root = Element::box_element();
+        for child in children {
+            root.add_child(child);
+        }
+        root
+    }
+
+    #[test]
+    fn test_calc_sizes_against_parent() {
+        let mut sidebar = Element::box_element();
+        sidebar.style.width = Dimension::Points(20.0);
+        let mut main = Element::box_element();
+        main.style.width = Dimension::calc(100.0, -20.0);
+        main.style.flex_shrink = 0.0;
+        let mut inner = Element::box_element();
+        inner.style.width = Dimension::calc(50.0, -1.0);
+        main.add_child(inner);
+
+        let mut root = row(vec![sidebar, main]);
+        root.style.width = Dimension::Percent(100.0);
+        root.style.padding = crate::core::Edges::horizontal(1.0);
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(&mut root, 80, 10);
+
+        let main = root.children.get(1).unwrap();
+        assert_eq!(engine.get_layout(main.id).unwrap().width, 58.0);
+        let inner = main.children.get(0).unwrap();
+        assert_eq!(engine.get_layout(inner.id).unwrap().width, 28.0);
+        assert!(engine.stats().layout_passes > 1);
+
+        // An unchanged frame keeps the resolved sizes without re-layout
+        engine.compute_incremental(&mut root, 80, 10);
+        assert_eq!(engine.stats().layout_passes, 1);
+        let main = root.children.get(1).unwrap();
+        assert_eq!(engine.get_layout(main.id).unwrap().width, 58.0);
+
+        // Resolved again against the new size on the next frame
+        engine.compute_incremental(&mut root, 40, 10);
+        let main = root.children.get(1).unwrap();
+        assert_eq!(engine.get_layout(main.id).unwrap().width, 18.0);
+    }
+
+    #[test]
+    fn test_intrinsic_sizes() {
+        let sized = |width: Dimension| {
+            let mut element = row(vec![Element::text("hello"), Element::text("world!")]);
+            element.style.width = width;
+            element.style.flex_shrink = 0.0;
+            element
+        };
+
+        let mut root = Element::box_element();
+        root.style.flex_direction = crate::core::FlexDirection::Column;
+        root.style.align_items = crate::core::AlignItems::FlexStart;
+        root.add_child(sized(Dimension::MaxContent));
+        root.add_child(sized(Dimension::FitContent(8.0)));
+        root.add_child(sized(Dimension::FitContent(40.0)));
+
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(&mut root, 80, 10);
+        let width = |i: usize| {
+            engine
+                .get_layout(root.children.get(i).unwrap().id)
+                .unwrap()
+                .width
+        };
+
+        assert_eq!(width(0), 11.0);
+        // min-content of unwrappable text is its full width
+        assert_eq!(width(1), 11.0);
+        assert_eq!(width(2), 11.0);
+    }
+
+    #[test]
+    fn test_text_shrinks_to_wrap_in_row() {
+        let mut label = Element::text("fixed");
+        label.style.flex_shrink = 0.0;
+        let mut root = row(vec![label, Element::text("wraps at word boundaries")]);
+        root.style.width = Dimension::Points(16.0);
+        root.style.align_items = crate::core::AlignItems::FlexStart;
+
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(&mut root, 80, 10);
+        let layout = engine.get_layout(root.children.get(1).unwrap().id).unwrap();
+        // Shrunk to the 11 cells left: "wraps at" / "word" / "boundaries"
+        assert_eq!((layout.width, layout.height), (11.0, 3.0));
+    }
+
+    #[test]
+    fn test_aspect_ratio() {
+        let mut chart = Element::box_element();
+        chart.style.width = Dimension::Points(20.0);
+        chart.style.aspect_ratio = Some(2.0);
+        let mut root = Element::box_element();
+        root.style.align_items = crate::core::AlignItems::FlexStart;
+        root.add_child(chart);
+
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(&mut root, 80, 24);
+        let layout = engine.get_layout(root.children.get(0).unwrap().id).unwrap();
+        assert_eq!((layout.width, layout.height), (20.0, 10.0));
+    }
+
+    fn log_view(lines: usize, extra: Option<&str>) -> Element {
+        let mut root = Element::box_element();
+        root.style.flex_direction = crate::core::FlexDirection::Column;
+        root.style.width = Dimension::Percent(100.0);
+        for i in 0..lines {
+            root.add_child(Element::text(format!("log line {}", i)));
+        }
+        if let Some(extra) = extra {
+            root.add_child(Element::text(extra));
+        }
+        root
+    }
+
+    #[test]
+    fn test_compute_keeps_tree_between_frames() {
+        let mut engine = LayoutEngine::new();
+
+        engine.compute(&log_view(100, None), 80, 24);
+        let stats = engine.stats();
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.nodes_created, 101);
+        assert_eq!(stats.measure_misses, 100);
+
+        // A fresh element tree with one new line reuses every other node
+        let root = log_view(100, Some("new"));
+        engine.compute(&root, 80, 24);
+        let stats = engine.stats();
+        assert_eq!(stats.nodes_created, 1);
+        assert_eq!(stats.nodes, 102);
+        assert_eq!(engine.node_count(), 102);
+
+        // Layouts are keyed by the IDs of the tree passed in
+        let last = root.children.get(100).unwrap();
+        assert_eq!(engine.get_layout(last.id).unwrap().y, 100.0);
+    }
+
+    #[test]
+    fn test_measure_cache_survives_resize() {
+        let mut engine = LayoutEngine::new();
+        let mut root = log_view(50, None);
+
+        engine.compute_incremental(&mut root, 80, 24);
+        engine.compute_incremental(&mut root, 60, 24);
+        engine.compute_incremental(&mut root, 80, 24);
+
+        // Every line was measured at width 80 in the first frame
+        let stats = engine.stats();
+        assert_eq!(stats.measure_misses, 0);
+        assert!(stats.measure_hits >= 50);
+        assert_eq!(stats.frames, 3);
+        assert!(stats.total_duration >= stats.duration);
+    }
 }
@@ -1,8 +1,111 @@
 //! Text measurement utilities
 
+pub use crate::core::DEFAULT_ELLIPSIS;
+use crate::core::TextWrap;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+/// How East-Asian ambiguous-width characters are measured
+///
+/// Characters such as `…` and `○` take one cell in most terminals but two in
+/// terminals running a CJK locale. Measuring them the way the terminal draws
+/// them keeps text and table columns aligned. Box-drawing and block glyphs
+/// are always one cell: borders, scrollbars and bars paint them one per cell
+/// and layout reserves a single cell for each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AmbiguousWidth {
+    /// One cell
+    Narrow,
+    /// Two cells
+    Wide,
+    /// Wide when the locale is Chinese, Japanese or Korean
+    #[default]
+    Auto,
+}
+
+static AMBIGUOUS_WIDTH: AtomicU8 = AtomicU8::new(AmbiguousWidth::Auto as u8);
+
+/// Set how ambiguous-width characters are measured, for the whole process
+///
+/// Affects text measurement in layout, the output buffer and
+/// [`testing::display_width`](crate::testing::display_width). Set it before
+/// rendering; text laid out earlier keeps its old size until it changes.
+pub fn set_ambiguous_width(policy: AmbiguousWidth) {
+    AMBIGUOUS_WIDTH.store(policy as u8, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Per-thread policy override, so tests don't race on the global one
+    static TEST_AMBIGUOUS_WIDTH: std::cell::Cell<Option<AmbiguousWidth>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Run `f` with a policy that only applies to the current thread
+#[cfg(test)]
+pub(crate) fn with_ambiguous_width<R>(policy: AmbiguousWidth, f: impl FnOnce() -> R) -> R {
+    let previous = TEST_AMBIGUOUS_WIDTH.replace(Some(policy));
+    let result = f();
+    TEST_AMBIGUOUS_WIDTH.set(previous);
+    result
+}
+
+/// The configured ambiguous-width policy
+pub fn ambiguous_width() -> AmbiguousWidth {
+    #[cfg(test)]
+    if let Some(policy) = TEST_AMBIGUOUS_WIDTH.get() {
+        return policy;
+    }
+    match AMBIGUOUS_WIDTH.load(Ordering::Relaxed) {
+        x if x == AmbiguousWidth::Narrow as u8 => AmbiguousWidth::Narrow,
+        x if x == AmbiguousWidth::Wide as u8 => AmbiguousWidth::Wide,
+        _ => AmbiguousWidth::Auto,
+    }
+}
+
+/// Whether ambiguous-width characters currently measure two cells
+pub fn ambiguous_is_wide() -> bool {
+    static LOCALE_IS_CJK: OnceLock<bool> = OnceLock::new();
+    match ambiguous_width() {
+        AmbiguousWidth::Narrow => false,
+        AmbiguousWidth::Wide => true,
+        AmbiguousWidth::Auto => *LOCALE_IS_CJK.get_or_init(|| {
+            locale_is_cjk(
+                ["LC_ALL", "LC_CTYPE", "LANG"]
+                    .iter()
+                    .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty())),
+            )
+        }),
+    }
+}
+
+/// Check if a locale such as `zh_CN.UTF-8` is Chinese, Japanese or Korean
+fn locale_is_cjk(locale: Option<String>) -> bool {
+    locale.is_some_and(|locale| {
+        let language = locale.split(['_', '.', '@', '-']).next().unwrap_or("");
+        matches!(language.to_ascii_lowercase().as_str(), "zh" | "ja" | "ko")
+    })
+}
+
+/// Display width of a single grapheme cluster
+fn grapheme_width(grapheme: &str, wide: bool) -> usize {
+    if wide && !is_box_drawing(grapheme) {
+        grapheme.width_cjk()
+    } else {
+        grapheme.width()
+    }
+}
+
+/// Check if a grapheme is a box-drawing or block element glyph
+fn is_box_drawing(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .next()
+        .is_some_and(|c| matches!(c, '\u{2500}'..='\u{259F}'))
+}
+
 /// Measure the display width of text using grapheme clusters
 ///
 /// This function properly handles:
@@ -10,8 +113,10 @@ use unicode_width::UnicodeWidthStr;
 /// - Emoji sequences (including ZWJ sequences like 👨‍👩‍👧‍👦)
 /// - Combining characters (e.g., é = e + combining acute)
 /// - Zero-width characters
+/// - Ambiguous-width characters, following [`set_ambiguous_width`]
 pub fn measure_text_width(text: &str) -> usize {
-    text.graphemes(true).map(UnicodeWidthStr::width).sum()
+    let wide = ambiguous_is_wide();
+    text.graphemes(true).map(|g| grapheme_width(g, wide)).sum()
 }
 
 /// Measure the display width using grapheme clusters (alias for measure_text_width)
@@ -23,190 +128,363 @@ pub fn display_width(text: &str) -> usize {
 pub fn measure_text(text: &str) -> (usize, usize) {
     let lines: Vec<&str> = text.lines().collect();
     let height = lines.len().max(1);
-    let width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+    let width = lines
+        .iter()
+        .map(|line| measure_text_width(line))
+        .max()
+        .unwrap_or(0);
     (width, height)
 }
 
-/// Wrap text to fit within a maximum width (grapheme-aware)
-pub fn wrap_text(text: &str, max_width: usize) -> String {
-    if max_width == 0 {
-        return String::new();
-    }
+/// A run of text from a single span within a [`TextLine`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextFragment {
+    /// Index of the span the text came from
+    pub span: usize,
+    pub text: String,
+}
 
-    let mut result = String::new();
-    let mut current_width = 0;
+/// One row of text after wrapping or truncation
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TextLine {
+    pub fragments: Vec<TextFragment>,
+    /// Display width in cells
+    pub width: usize,
+}
 
-    for grapheme in text.graphemes(true) {
-        let grapheme_width = UnicodeWidthStr::width(grapheme);
+impl TextLine {
+    /// The row's text without span boundaries
+    pub fn text(&self) -> String {
+        self.fragments.iter().map(|f| f.text.as_str()).collect()
+    }
 
-        if grapheme == "\n" {
-            result.push('\n');
-            current_width = 0;
-        } else if current_width + grapheme_width > max_width {
-            result.push('\n');
-            result.push_str(grapheme);
-            current_width = grapheme_width;
-        } else {
-            result.push_str(grapheme);
-            current_width += grapheme_width;
+    fn push(&mut self, span: usize, text: &str, width: usize) {
+        match self.fragments.last_mut() {
+            Some(fragment) if fragment.span == span => fragment.text.push_str(text),
+            _ => self.fragments.push(TextFragment {
+                span,
+                text: text.to_string(),
+            }),
         }
+        self.width += width;
     }
 
-    result
+    fn push_all(&mut self, graphemes: &[Grapheme<'_>]) {
+        for g in graphemes {
+            self.push(g.span, g.text, g.width);
+        }
+    }
 }
 
-/// Truncate text to fit within a maximum width (grapheme-aware)
-pub fn truncate_text(text: &str, max_width: usize, ellipsis: &str) -> String {
-    let text_width = measure_text_width(text);
+/// A grapheme cluster tagged with the span it belongs to
+#[derive(Clone, Copy)]
+struct Grapheme<'a> {
+    span: usize,
+    text: &'a str,
+    width: usize,
+}
 
-    if text_width <= max_width {
-        return text.to_string();
+impl Grapheme<'_> {
+    /// Whitespace a line may break at (no-break spaces are excluded)
+    fn is_space(&self) -> bool {
+        self.text
+            .chars()
+            .all(|c| c.is_whitespace() && !matches!(c, '\u{a0}' | '\u{2007}' | '\u{202f}'))
     }
+}
 
-    let ellipsis_width = measure_text_width(ellipsis);
-    if max_width <= ellipsis_width {
-        // Just take as much of ellipsis as we can
-        let mut result = String::new();
-        let mut width = 0;
-        for g in ellipsis.graphemes(true) {
-            let gw = UnicodeWidthStr::width(g);
-            if width + gw > max_width {
-                break;
+fn total_width(graphemes: &[Grapheme<'_>]) -> usize {
+    graphemes.iter().map(|g| g.width).sum()
+}
+
+/// Lay out text into the rows it occupies at `max_width`
+///
+/// `spans` are consecutive pieces of text, each painted with its own style;
+/// every [`TextFragment`] points back to the span it was cut from, so styles
+/// carry over when a span is wrapped onto the next row. A `\n` in any span is
+/// a hard break.
+///
+/// With [`TextWrap::Wrap`] rows break at whitespace, and words wider than
+/// the whole row break between grapheme clusters. The truncating modes keep
+/// one row per hard line and replace the cut text with `ellipsis`. Without a
+/// `max_width` only hard breaks apply.
+///
+/// The layout engine measures text and the renderer paints it with this
+/// function, so a text node always gets exactly the rows it is painted with.
+pub fn layout_text<S: AsRef<str>>(
+    spans: &[S],
+    max_width: Option<usize>,
+    wrap: TextWrap,
+    ellipsis: &str,
+) -> Vec<TextLine> {
+    let wide = ambiguous_is_wide();
+    let mut paragraphs: Vec<Vec<Grapheme<'_>>> = vec![Vec::new()];
+    for (span, text) in spans.iter().enumerate() {
+        for g in text.as_ref().graphemes(true) {
+            if g == "\n" || g == "\r\n" {
+                paragraphs.push(Vec::new());
+                continue;
+            }
+            let grapheme = Grapheme {
+                span,
+                text: g,
+                width: grapheme_width(g, wide),
+            };
+            if let Some(paragraph) = paragraphs.last_mut() {
+                paragraph.push(grapheme);
             }
-            result.push_str(g);
-            width += gw;
         }
-        return result;
     }
 
-    let target_width = max_width - ellipsis_width;
-    let mut result = String::new();
-    let mut current_width = 0;
-
-    for grapheme in text.graphemes(true) {
-        let grapheme_width = UnicodeWidthStr::width(grapheme);
-        if current_width + grapheme_width > target_width {
-            break;
+    let mut lines = Vec::new();
+    for paragraph in &paragraphs {
+        let Some(max_width) = max_width.filter(|&w| total_width(paragraph) > w) else {
+            let mut line = TextLine::default();
+            line.push_all(paragraph);
+            lines.push(line);
+            continue;
+        };
+        match wrap {
+            TextWrap::Wrap => wrap_paragraph(paragraph, max_width, &mut lines),
+            TextWrap::Truncate | TextWrap::TruncateEnd => {
+                lines.push(truncate_paragraph(paragraph, max_width, ellipsis, Cut::End))
+            }
+            TextWrap::TruncateStart => lines.push(truncate_paragraph(
+                paragraph,
+                max_width,
+                ellipsis,
+                Cut::Start,
+            )),
+            TextWrap::TruncateMiddle => lines.push(truncate_paragraph(
+                paragraph,
+                max_width,
+                ellipsis,
+                Cut::Middle,
+            )),
         }
-        result.push_str(grapheme);
-        current_width += grapheme_width;
     }
-
-    result.push_str(ellipsis);
-    result
+    lines
 }
 
-/// Truncate text from the start (grapheme-aware)
-pub fn truncate_start(text: &str, max_width: usize, ellipsis: &str) -> String {
-    let text_width = measure_text_width(text);
-
-    if text_width <= max_width {
-        return text.to_string();
-    }
+/// Width and height of text laid out with [`layout_text`]
+pub fn measure_text_layout(
+    text: &str,
+    max_width: Option<usize>,
+    wrap: TextWrap,
+    ellipsis: &str,
+) -> (usize, usize) {
+    let lines = layout_text(&[text], max_width, wrap, ellipsis);
+    let width = lines.iter().map(|line| line.width).max().unwrap_or(0);
+    (width, lines.len())
+}
 
-    let ellipsis_width = measure_text_width(ellipsis);
-    if max_width <= ellipsis_width {
-        let mut result = String::new();
-        let mut width = 0;
-        for g in ellipsis.graphemes(true) {
-            let gw = UnicodeWidthStr::width(g);
-            if width + gw > max_width {
-                break;
+/// Narrowest width text can be laid out at without overflowing
+///
+/// Wrapping text can't get narrower than its widest word; truncated text can
+/// shrink down to its ellipsis.
+pub fn min_content_width(text: &str, wrap: TextWrap, ellipsis: &str) -> usize {
+    match wrap {
+        TextWrap::Wrap => {
+            let mut widest = 0;
+            let mut word = 0;
+            let wide = ambiguous_is_wide();
+            for g in text.graphemes(true) {
+                let width = grapheme_width(g, wide);
+                let grapheme = Grapheme {
+                    span: 0,
+                    text: g,
+                    width,
+                };
+                if grapheme.is_space() || width > 1 {
+                    widest = widest
+                        .max(word)
+                        .max(if grapheme.is_space() { 0 } else { width });
+                    word = 0;
+                } else {
+                    word += width;
+                }
             }
-            result.push_str(g);
-            width += gw;
+            widest.max(word)
+        }
+        _ => {
+            let ellipsis_width = measure_text_width(ellipsis);
+            text.split('\n')
+                .map(|line| measure_text_width(line).min(ellipsis_width))
+                .max()
+                .unwrap_or(0)
         }
-        return result;
     }
+}
 
-    let target_width = max_width - ellipsis_width;
-    let graphemes: Vec<&str> = text.graphemes(true).collect();
-    let mut result = String::new();
-    let mut current_width = 0;
-    let mut end_graphemes = Vec::new();
+/// Break a line that doesn't fit into rows, preferring whitespace
+fn wrap_paragraph(paragraph: &[Grapheme<'_>], max_width: usize, lines: &mut Vec<TextLine>) {
+    let mut line = TextLine::default();
+    let mut rest = paragraph;
 
-    for grapheme in graphemes.iter().rev() {
-        let grapheme_width = UnicodeWidthStr::width(*grapheme);
-        if current_width + grapheme_width > target_width {
-            break;
+    while !rest.is_empty() {
+        // Leading whitespace, then a word; wide characters are words of their own
+        let spaces = rest.iter().take_while(|g| g.is_space()).count();
+        let word = if rest.get(spaces).is_some_and(|g| g.width > 1) {
+            1
+        } else {
+            rest[spaces..]
+                .iter()
+                .take_while(|g| !g.is_space() && g.width <= 1)
+                .count()
+        };
+        let (space, after) = rest.split_at(spaces);
+        let (word, after) = after.split_at(word);
+        rest = after;
+
+        let space_width = total_width(space);
+        let word_width = total_width(word);
+
+        if line.fragments.is_empty() {
+            // Indentation is kept as far as it fits
+            for g in space {
+                if line.width + g.width > max_width {
+                    break;
+                }
+                line.push(g.span, g.text, g.width);
+            }
+        } else if line.width + space_width + word_width <= max_width {
+            line.push_all(space);
+        } else if !word.is_empty() {
+            // Whitespace at a break is dropped
+            lines.push(std::mem::take(&mut line));
         }
-        end_graphemes.push(*grapheme);
-        current_width += grapheme_width;
-    }
 
-    end_graphemes.reverse();
-    result.push_str(ellipsis);
-    for g in end_graphemes {
-        result.push_str(g);
+        if line.width + word_width <= max_width {
+            line.push_all(word);
+            continue;
+        }
+        // Words wider than a row break between grapheme clusters
+        for g in word {
+            if line.width + g.width > max_width && line.width > 0 {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(g.span, g.text, g.width);
+        }
     }
-    result
-}
 
-/// Truncate text from the middle (grapheme-aware)
-pub fn truncate_middle(text: &str, max_width: usize, ellipsis: &str) -> String {
-    let text_width = measure_text_width(text);
+    lines.push(line);
+}
 
-    if text_width <= max_width {
-        return text.to_string();
-    }
+/// Which part of a truncated line the ellipsis replaces
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Cut {
+    Start,
+    Middle,
+    End,
+}
 
+/// Shorten a line that doesn't fit to `max_width`, marking the cut with `ellipsis`
+fn truncate_paragraph(
+    paragraph: &[Grapheme<'_>],
+    max_width: usize,
+    ellipsis: &str,
+    cut: Cut,
+) -> TextLine {
+    let mut line = TextLine::default();
     let ellipsis_width = measure_text_width(ellipsis);
+
     if max_width <= ellipsis_width {
-        let mut result = String::new();
-        let mut width = 0;
+        // Just take as much of the ellipsis as we can
+        let span = match cut {
+            Cut::Start => paragraph.last(),
+            Cut::Middle | Cut::End => paragraph.first(),
+        }
+        .map_or(0, |g| g.span);
         for g in ellipsis.graphemes(true) {
-            let gw = UnicodeWidthStr::width(g);
-            if width + gw > max_width {
+            let width = measure_text_width(g);
+            if line.width + width > max_width {
                 break;
             }
-            result.push_str(g);
-            width += gw;
+            line.push(span, g, width);
         }
-        return result;
+        return line;
     }
 
     let available = max_width - ellipsis_width;
-    let left_width = available / 2;
-    let right_width = available - left_width;
-
-    let graphemes: Vec<&str> = text.graphemes(true).collect();
-
-    // Build left part
-    let mut left = String::new();
-    let mut current_width = 0;
-    for grapheme in &graphemes {
-        let grapheme_width = UnicodeWidthStr::width(*grapheme);
-        if current_width + grapheme_width > left_width {
-            break;
-        }
-        left.push_str(grapheme);
-        current_width += grapheme_width;
-    }
+    let (head_width, tail_width) = match cut {
+        Cut::Start => (0, available),
+        Cut::Middle => (available / 2, available - available / 2),
+        Cut::End => (available, 0),
+    };
+
+    let head = fitting(paragraph.iter(), head_width);
+    let tail = fitting(paragraph.iter().rev(), tail_width);
+    let tail = &paragraph[paragraph.len() - tail..];
+    let head = &paragraph[..head];
+
+    // The ellipsis takes the style of the text it stands next to
+    let span = head
+        .last()
+        .or(tail.first())
+        .or(paragraph.first())
+        .map_or(0, |g| g.span);
+
+    line.push_all(head);
+    line.push(span, ellipsis, ellipsis_width);
+    line.push_all(tail);
+    line
+}
 
-    // Build right part
-    let mut right_graphemes = Vec::new();
-    current_width = 0;
-    for grapheme in graphemes.iter().rev() {
-        let grapheme_width = UnicodeWidthStr::width(*grapheme);
-        if current_width + grapheme_width > right_width {
-            break;
-        }
-        right_graphemes.push(*grapheme);
-        current_width += grapheme_width;
-    }
-    right_graphemes.reverse();
+/// Number of graphemes taken from `graphemes` before exceeding `max_width`
+fn fitting<'a, 'b: 'a>(
+    graphemes: impl Iterator<Item = &'a Grapheme<'b>>,
+    max_width: usize,
+) -> usize {
+    let mut width = 0;
+    graphemes
+        .take_while(|g| {
+            width += g.width;
+            width <= max_width
+        })
+        .count()
+}
 
-    let mut right = String::new();
-    for g in right_graphemes {
-        right.push_str(g);
+/// Wrap text to fit within a maximum width
+///
+/// Breaks at whitespace where possible; see [`layout_text`].
+pub fn wrap_text(text: &str, max_width: usize) -> String {
+    if max_width == 0 {
+        return String::new();
     }
 
-    format!("{}{}{}", left, ellipsis, right)
+    layout_text(&[text], Some(max_width), TextWrap::Wrap, DEFAULT_ELLIPSIS)
+        .iter()
+        .map(TextLine::text)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn truncate_with(text: &str, max_width: usize, ellipsis: &str, wrap: TextWrap) -> String {
+    layout_text(&[text], Some(max_width), wrap, ellipsis)
+        .iter()
+        .map(TextLine::text)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Truncate text to fit within a maximum width (grapheme-aware)
+pub fn truncate_text(text: &str, max_width: usize, ellipsis: &str) -> String {
+    truncate_with(text, max_width, ellipsis, TextWrap::TruncateEnd)
+}
+
+/// Truncate text from the start (grapheme-aware)
+pub fn truncate_start(text: &str, max_width: usize, ellipsis: &str) -> String {
+    truncate_with(text, max_width, ellipsis, TextWrap::TruncateStart)
+}
+
+/// Truncate text from the middle (grapheme-aware)
+pub fn truncate_middle(text: &str, max_width: usize, ellipsis: &str) -> String {
+    truncate_with(text, max_width, ellipsis, TextWrap::TruncateMiddle)
 }
 
 /// Pad text to a specific width
 pub fn pad_text(text: &str, width: usize, align: TextAlign) -> String {
-    let text_width = text.width();
+    let text_width = measure_text_width(text);
 
     if text_width >= width {
         return text.to_string();
@@ -251,6 +529,33 @@ mod tests {
         assert_eq!(measure_text_width("Hello 世界"), 10);
     }
 
+    #[test]
+    fn test_ambiguous_width_policy() {
+        for ambiguous in ["…", "○"] {
+            assert_eq!(grapheme_width(ambiguous, false), 1);
+            assert_eq!(grapheme_width(ambiguous, true), 2);
+        }
+        // Border and bar glyphs keep one cell
+        for glyph in ["─", "│", "╭", "═", "█", "░"] {
+            assert_eq!(grapheme_width(glyph, true), 1);
+        }
+        // Unambiguous characters don't depend on the policy
+        assert_eq!(grapheme_width("a", true), 1);
+        assert_eq!(grapheme_width("你", false), 2);
+    }
+
+    #[test]
+    fn test_locale_detection() {
+        let cjk = |locale: &str| locale_is_cjk(Some(locale.to_string()));
+        assert!(cjk("zh_CN.UTF-8"));
+        assert!(cjk("ja_JP"));
+        assert!(cjk("ko_KR.eucKR"));
+        assert!(cjk("zh-Hant"));
+        assert!(!cjk("en_US.UTF-8"));
+        assert!(!cjk("C"));
+        assert!(!locale_is_cjk(None));
+    }
+
     #[test]
     fn test_measure_text_dimensions() {
         let (w, h) = measure_text("hello\nworld");
@@ -264,6 +569,87 @@ mod tests {
         assert!(wrapped.contains('\n'));
     }
 
+    fn rows<S: AsRef<str>>(spans: &[S], width: usize, wrap: TextWrap) -> Vec<String> {
+        layout_text(spans, Some(width), wrap, DEFAULT_ELLIPSIS)
+            .iter()
+            .map(TextLine::text)
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap_at_word_boundaries() {
+        assert_eq!(
+            rows(&["the quick brown fox"], 10, TextWrap::Wrap),
+            ["the quick", "brown fox"]
+        );
+        // Words longer than a row break between graphemes
+        assert_eq!(
+            rows(&["a supercalifragilistic word"], 8, TextWrap::Wrap),
+            ["a", "supercal", "ifragili", "stic", "word"]
+        );
+        // Wide characters may break anywhere
+        assert_eq!(
+            rows(&["ab 你好世界"], 6, TextWrap::Wrap),
+            ["ab 你", "好世界"]
+        );
+    }
+
+    #[test]
+    fn test_hard_breaks_and_indentation() {
+        assert_eq!(
+            rows(&["  one two\nthree"], 7, TextWrap::Wrap),
+            ["  one", "two", "three"]
+        );
+        assert_eq!(rows(&["a\n\nb"], 5, TextWrap::Wrap), ["a", "", "b"]);
+        assert_eq!(
+            rows(&["long line here\nshort"], 8, TextWrap::TruncateEnd),
+            ["long li…", "short"]
+        );
+    }
+
+    #[test]
+    fn test_span_styles_survive_wrapping() {
+        let lines = layout_text(&["hello wo", "rld again"], Some(11), TextWrap::Wrap, "…");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0].fragments,
+            [
+                TextFragment {
+                    span: 0,
+                    text: "hello wo".to_string()
+                },
+                TextFragment {
+                    span: 1,
+                    text: "rld".to_string()
+                },
+            ]
+        );
+        assert_eq!(lines[1].text(), "again");
+        assert_eq!(lines[1].fragments[0].span, 1);
+        assert_eq!(lines[0].width, 11);
+    }
+
+    #[test]
+    fn test_ellipsis_option_and_style() {
+        let lines = layout_text(&["abc", "defghij"], Some(6), TextWrap::TruncateEnd, "..");
+        assert_eq!(lines[0].text(), "abcd..");
+        // The ellipsis is styled like the text it follows
+        assert_eq!(lines[0].fragments.last().unwrap().span, 1);
+
+        let lines = layout_text(&["abcdefg", "hij"], Some(6), TextWrap::TruncateStart, "~");
+        assert_eq!(lines[0].text(), "~fghij");
+        assert_eq!(lines[0].fragments[0].span, 0);
+
+        assert_eq!(
+            rows(&["abcdefghij"], 5, TextWrap::TruncateMiddle),
+            ["ab…ij"]
+        );
+        assert_eq!(
+            measure_text_layout("hello world", Some(5), TextWrap::Wrap, "…"),
+            (5, 2)
+        );
+    }
+
     #[test]
     fn test_truncate_text() {
         let truncated = truncate_text("hello world", 8, "...");
@@ -1,7 +1,11 @@
 //! Layout system using Taffy
 
+pub mod bidi;
 mod engine;
 pub mod measure;
 
-pub use engine::{Layout, LayoutEngine};
-pub use measure::{TextAlign, measure_text, measure_text_width, truncate_text, wrap_text};
+pub use engine::{Layout, LayoutEngine, LayoutStats};
+pub use measure::{
+    AmbiguousWidth, TextAlign, TextFragment, TextLine, layout_text, measure_text,
+    measure_text_width, set_ambiguous_width, truncate_text, wrap_text,
+};
@@ -104,6 +104,15 @@
 //!     request_render(); // Notify rnk to re-render
 //! });
 //! ```
+//!
+//! A [`SyncSignal`](hooks::SyncSignal) does both in one step:
+//!
+//! ```rust,ignore
+//! let progress = use_shared_signal(|| 0);
+//! let worker = progress.clone();
+//!
+//! thread::spawn(move || worker.set(100)); // Re-renders automatically
+//! ```
 
 pub mod cmd;
 pub mod components;
@@ -130,6 +139,7 @@ pub use crate::renderer::{
     IntoPrintable,
     ModeSwitch,
     Printable,
+    Program,
     // Types
     RenderHandle,
     enter_alt_screen,
@@ -138,6 +148,7 @@ pub use crate::renderer::{
     println,
     println_trimmed,
     // Main entry points
+    program,
     render,
     render_fullscreen,
     render_handle,
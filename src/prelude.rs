@@ -8,16 +8,18 @@
 //! ```
 
 pub use crate::core::{
-    AlignItems, BorderStyle, Color, Display, Element, ElementId, FlexDirection, JustifyContent,
-    Overflow, Position, Style, TextWrap,
+    AlignItems, BorderStyle, Color, ColorProfile, Display, Element, ElementId, FlexDirection,
+    GridLine, GridTemplate, GridTrack, JustifyContent, Overflow, Position, Style, TextDirection,
+    TextWrap, TrackSize,
 };
 
 pub use crate::components::{
-    Bar, BarChart, Box, Cell, Constraint, Gauge, Line, List, ListItem, ListState, Message,
-    MessageRole, Newline, Progress, ProgressSymbols, Row, ScrollableBox, Scrollbar,
-    ScrollbarSymbols, Spacer, Span, Sparkline, Spinner, SpinnerBuilder, Static, Tab, Table,
-    TableState, Tabs, Text, TextInputHandle, TextInputOptions, TextInputState, ThinkingBlock,
-    ToolCall, Transform, fixed_bottom_layout, static_output, use_text_input, virtual_scroll_view,
+    Bar, BarChart, BoundaryReset, Box, Cell, Component, Constraint, ErrorBoundary, Gauge, Line,
+    List, ListItem, ListState, Memo, Message, MessageRole, Newline, Portal, Progress,
+    ProgressSymbols, Row, ScrollableBox, Scrollbar, ScrollbarSymbols, Spacer, Span, Sparkline,
+    Spinner, SpinnerBuilder, Static, Suspense, Tab, Table, TableState, Tabs, Text, TextInputHandle,
+    TextInputOptions, TextInputState, ThinkingBlock, ToolCall, Transform, component,
+    fixed_bottom_layout, memo, static_output, use_text_input, virtual_scroll_view,
 };
 
 // Rendering APIs
@@ -27,6 +29,7 @@ pub use crate::renderer::{
     IntoPrintable,
     ModeSwitch,
     Printable,
+    Program,
     // Types
     RenderHandle,
     enter_alt_screen,
@@ -35,6 +38,7 @@ pub use crate::renderer::{
     println,
     println_trimmed,
     // Main entry points
+    program,
     render,
     render_fullscreen,
     render_handle,
@@ -49,9 +53,12 @@ pub use crate::renderer::{
 
 // Hooks
 pub use crate::hooks::{
-    AppContext, Dimensions, FocusManagerHandle, FocusState, Key, MeasureRef, Mouse, MouseAction,
-    MouseButton, ScrollHandle, ScrollState, Signal, StderrHandle, StdinHandle, StdoutHandle,
-    UseFocusOptions, measure_element, set_window_title, use_app, use_effect, use_focus,
-    use_focus_manager, use_input, use_is_screen_reader_enabled, use_measure, use_mouse, use_scroll,
-    use_signal, use_stderr, use_stdin, use_stdout, use_window_title, use_window_title_fn,
+    AppContext, Context, Dimensions, Dispatch, ElementRef, FocusManagerHandle, FocusState, Key,
+    MeasureRef, Mouse, MouseAction, MouseButton, Ref, Refetch, Resource, ScrollHandle, ScrollState,
+    Signal, StderrHandle, StdinHandle, StdoutHandle, Store, SyncSignal, UseFocusOptions,
+    create_context, measure_element, set_window_title, use_app, use_context, use_effect,
+    use_element_ref, use_focus, use_focus_manager, use_input, use_is_screen_reader_enabled,
+    use_measure, use_mouse, use_reducer, use_ref, use_resource, use_scroll, use_shared_signal,
+    use_signal, use_stderr, use_stdin, use_stdout, use_store, use_window_title,
+    use_window_title_fn,
 };
@@ -7,13 +7,20 @@ use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::core::Element;
+use crate::cmd::{self, CmdExecutor};
+use crate::core::{
+    ColorProfile, Element, TerminalBackground, set_color_profile, set_terminal_background,
+};
 use crate::hooks::context::{HookContext, with_hooks};
 use crate::hooks::use_app::{AppContext, set_app_context};
 use crate::hooks::use_input::clear_input_handlers;
 use crate::hooks::use_mouse::{clear_mouse_handlers, is_mouse_enabled};
+use crate::hooks::use_ref::update_element_refs;
+use crate::hooks::use_store::StoreMap;
+use crate::hooks::{MeasureContext, set_measure_context};
 use crate::layout::LayoutEngine;
 use crate::renderer::{Output, Terminal};
+use crate::runtime::supports_synchronized_output;
 
 use super::builder::AppOptions;
 use super::element_renderer::render_element;
@@ -35,12 +42,16 @@ where
     should_exit: Arc<AtomicBool>,
     runtime: Arc<AppRuntime>,
     render_handle: RenderHandle,
+    /// Runs the commands hooks queue during render
+    executor: CmdExecutor,
     /// Static content renderer for inline mode
     static_renderer: StaticRenderer,
     /// Last known terminal width (for detecting width decreases)
     last_width: u16,
     /// Last known terminal height
     last_height: u16,
+    /// Application-wide stores, exposed through the app context
+    stores: StoreMap,
 }
 
 impl<F> App<F>
@@ -62,31 +73,57 @@ where
         let runtime_clone = runtime.clone();
         hook_context
             .borrow_mut()
-            .set_render_callback(Rc::new(move || {
+            .set_sync_render_callback(Arc::new(move || {
                 runtime_clone.request_render();
             }));
 
+        // Finished commands wake the event loop like any other render request
+        let runtime_clone = runtime.clone();
+        let executor = CmdExecutor::with_render_handle(cmd::RenderHandle::from_fn(move || {
+            runtime_clone.request_render();
+        }));
+
         // Get initial terminal size
         let (initial_width, initial_height) = Terminal::size().unwrap_or((80, 24));
 
+        let mut terminal = Terminal::new();
+        terminal.set_synchronized_output(
+            options
+                .synchronized_output
+                .unwrap_or_else(supports_synchronized_output),
+        );
+
         Self {
             component,
-            terminal: Terminal::new(),
+            terminal,
             layout_engine: LayoutEngine::new(),
             hook_context,
             options,
             should_exit: Arc::new(AtomicBool::new(false)),
             runtime,
             render_handle,
+            executor,
             static_renderer: StaticRenderer::new(),
             last_width: initial_width,
             last_height: initial_height,
+            stores: StoreMap::default(),
         }
     }
 
+    /// Attach application-wide stores
+    pub(crate) fn with_stores(mut self, stores: StoreMap) -> Self {
+        self.stores = stores;
+        self
+    }
+
     /// Run the application
     pub fn run(&mut self) -> std::io::Result<()> {
         let _app_guard = register_app(self.runtime.clone());
+        let color_profile = self
+            .options
+            .color_profile
+            .unwrap_or_else(ColorProfile::detect);
+        set_color_profile(color_profile);
 
         // Enter terminal mode based on options
         if self.options.alternate_screen {
@@ -97,6 +134,20 @@ where
             self.runtime.set_alt_screen_state(false);
         }
 
+        // Adaptive colors resolve against the background from here on
+        let mut typed_ahead = Vec::new();
+        let background = match self.options.background {
+            Some(background) => background,
+            // Nothing is colored, so don't make the terminal answer a query
+            None if color_profile == ColorProfile::NoColor => TerminalBackground::default(),
+            None => {
+                let (background, events) = Terminal::detect_background();
+                typed_ahead = events;
+                background
+            }
+        };
+        set_terminal_background(background);
+
         // Create event loop
         let mut event_loop = EventLoop::new(
             self.runtime.clone(),
@@ -104,6 +155,7 @@ where
             self.options.fps,
             self.options.exit_on_ctrl_c,
         );
+        event_loop.queue_events(typed_ahead);
 
         // Run event loop with render callback
         event_loop.run(|| {
@@ -128,6 +180,9 @@ where
             self.render_frame()
         })?;
 
+        // Unmount the component tree so effect cleanups run
+        self.hook_context.borrow_mut().unmount();
+
         // Exit terminal mode
         if self.terminal.is_alt_screen() {
             self.terminal.exit()?;
@@ -218,17 +273,7 @@ where
         // Get terminal size
         let (width, height) = Terminal::size()?;
 
-        // Set up app context for use_app hook
-        set_app_context(Some(AppContext::new(
-            self.should_exit.clone(),
-            self.render_handle.clone(),
-        )));
-
-        // Build element tree with hooks context
-        let root = with_hooks(self.hook_context.clone(), || (self.component)());
-
-        // Clear app context after render
-        set_app_context(None);
+        let root = self.render_component();
 
         // Enable/disable mouse mode based on whether any component uses it
         if is_mouse_enabled() {
@@ -245,26 +290,55 @@ where
         }
 
         // Filter out static elements from the tree for dynamic rendering
-        let dynamic_root = self.static_renderer.filter_static_elements(&root);
+        let mut dynamic_root = self.static_renderer.filter_static_elements(&root);
+        let output = self.layout_frame(&mut dynamic_root, width, height);
+
+        // Write to terminal
+        self.terminal.render_output(output)
+    }
+
+    /// Run the component with the hook and app contexts set up
+    pub(crate) fn render_component(&self) -> Element {
+        // Set up app context for use_app hook
+        set_app_context(Some(
+            AppContext::new(self.should_exit.clone(), self.render_handle.clone())
+                .with_stores(self.stores.clone()),
+        ));
+
+        // Build element tree with hooks context
+        let root = with_hooks(self.hook_context.clone(), || (self.component)());
+
+        // Clear app context after render
+        set_app_context(None);
+
+        // Start the commands queued by this render
+        for cmd in self.hook_context.borrow_mut().take_cmds() {
+            self.executor.execute(cmd);
+        }
+        root
+    }
+
+    /// Lay out the dynamic element tree and paint it into an output buffer
+    fn layout_frame(&mut self, root: &mut Element, width: u16, height: u16) -> Output {
+        // Reconcile against the previous frame and compute layout
+        self.layout_engine.compute_incremental(root, width, height);
 
-        // Compute layout for dynamic content
-        self.layout_engine.compute(&dynamic_root, width, height);
+        // Publish layouts to element refs and `measure_element`
+        update_element_refs(root, &self.layout_engine);
+        let mut measure_context = MeasureContext::new();
+        measure_context.set_layouts(self.layout_engine.get_all_layouts());
+        measure_context.set_renamed_ids(self.layout_engine.renamed_ids());
+        set_measure_context(Some(measure_context));
 
         // Get the actual content size from layout
-        let root_layout = self
-            .layout_engine
-            .get_layout(dynamic_root.id)
-            .unwrap_or_default();
+        let root_layout = self.layout_engine.get_layout(root.id).unwrap_or_default();
         let content_width = (root_layout.width as u16).max(1).min(width);
         let render_height = (root_layout.height as u16).max(1).min(height);
 
         // Render to output buffer
         let mut output = Output::new(content_width, render_height);
-        render_element(&dynamic_root, &self.layout_engine, &mut output, 0.0, 0.0);
-
-        // Write to terminal
-        let rendered = output.render();
-        self.terminal.render(&rendered)
+        render_element(root, &self.layout_engine, &mut output, 0.0, 0.0);
+        output
     }
 
     /// Request exit
@@ -277,6 +351,32 @@ where
 mod tests {
     use super::*;
     use crate::renderer::registry::{is_alt_screen, render_handle};
+    use std::time::{Duration, Instant};
+
+    impl<F> App<F>
+    where
+        F: Fn() -> Element,
+    {
+        /// Render the element tree as one event loop frame would
+        pub(crate) fn render_detached(&self) -> Element {
+            self.runtime.clear_render_request();
+            self.render_component()
+        }
+
+        /// Whether something requested the next frame
+        pub(crate) fn render_pending(&self) -> bool {
+            self.runtime.render_requested()
+        }
+
+        /// Block until something requests the next frame
+        pub(crate) fn wait_for_render_request(&self) {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !self.runtime.render_requested() {
+                assert!(Instant::now() < deadline, "no render was requested");
+                std::thread::sleep(Duration::from_millis(5));
+            }
+        }
+    }
 
     #[test]
     fn test_registry_cleanup_on_drop() {
@@ -291,4 +391,59 @@ mod tests {
         assert!(render_handle().is_none());
         assert_eq!(is_alt_screen(), None);
     }
+
+    #[test]
+    fn test_use_measure_across_frames() {
+        use crate::components::Box;
+        use crate::hooks::{Dimensions, use_measure};
+
+        let measured = Rc::new(RefCell::new(Vec::new()));
+        let seen = Rc::clone(&measured);
+        let mut app = App::new(move || {
+            let (measure_ref, dimensions) = use_measure();
+            seen.borrow_mut()
+                .push(dimensions().map(|Dimensions { width, height }| (width, height)));
+
+            let element = Box::new().width(12).height(3).into_element();
+            measure_ref.set(element.id);
+            Box::new().child(element).into_element()
+        });
+
+        for _ in 0..4 {
+            let mut root = app.render_component();
+            app.layout_frame(&mut root, 40, 10);
+        }
+        set_measure_context(None);
+
+        // Each frame records a fresh ID; the previous layout must resolve it
+        let expected = [
+            None,
+            Some((12.0, 3.0)),
+            Some((12.0, 3.0)),
+            Some((12.0, 3.0)),
+        ];
+        assert_eq!(*measured.borrow(), expected);
+    }
+
+    #[test]
+    fn test_queued_commands_run_after_render() {
+        use crate::components::Text;
+        use crate::hooks::{Resource, use_resource};
+
+        let app = App::new(|| {
+            let (answer, _) = use_resource((), |()| async { Ok::<_, String>(42) });
+            let text = match answer {
+                Resource::Loading => "loading".to_string(),
+                Resource::Ready(value) => value.to_string(),
+                Resource::Failed(error) => error,
+            };
+            Text::new(text).into_element()
+        });
+
+        assert_eq!(app.render_detached().get_text(), Some("loading"));
+
+        // The fetch runs on the app's executor and asks for another frame
+        app.wait_for_render_request();
+        assert_eq!(app.render_detached().get_text(), Some("42"));
+    }
 }
@@ -2,7 +2,12 @@
 //!
 //! This module provides configuration types for the application runner.
 
-use crate::core::Element;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::core::{ColorProfile, Element, TerminalBackground};
+use crate::hooks::Store;
 
 use super::app::App;
 
@@ -21,6 +26,24 @@ pub struct AppOptions {
     /// - `true`: Fullscreen mode, like vim or Bubbletea's `WithAltScreen()`.
     ///   Uses alternate screen buffer, content is cleared on exit.
     pub alternate_screen: bool,
+    /// Wrap each frame in synchronized output markers (default: `None`)
+    ///
+    /// Terminals that support DEC mode 2026 then paint whole frames only,
+    /// which removes tearing during fast updates. `None` enables it when
+    /// [`supports_synchronized_output`](crate::runtime::supports_synchronized_output)
+    /// detects a capable terminal.
+    pub synchronized_output: Option<bool>,
+    /// Colors the terminal can display (default: `None`)
+    ///
+    /// Colors beyond the profile are mapped to the nearest one it has.
+    /// `None` detects the profile with [`ColorProfile::detect`].
+    pub color_profile: Option<ColorProfile>,
+    /// Background that adaptive colors resolve against (default: `None`)
+    ///
+    /// `None` asks the terminal with
+    /// [`Terminal::detect_background`](crate::renderer::Terminal::detect_background)
+    /// unless the color profile is [`ColorProfile::NoColor`].
+    pub background: Option<TerminalBackground>,
 }
 
 impl Default for AppOptions {
@@ -29,6 +52,9 @@ impl Default for AppOptions {
             fps: 60, // Bubbletea default
             exit_on_ctrl_c: true,
             alternate_screen: false, // Inline mode by default (like Ink/Bubbletea)
+            synchronized_output: None,
+            color_profile: None,
+            background: None,
         }
     }
 }
@@ -61,6 +87,7 @@ where
 {
     component: F,
     options: AppOptions,
+    stores: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
 }
 
 impl<F> AppBuilder<F>
@@ -72,6 +99,7 @@ where
         Self {
             component,
             options: AppOptions::default(),
+            stores: HashMap::new(),
         }
     }
 
@@ -107,6 +135,41 @@ where
         self
     }
 
+    /// Set whether frames are written as synchronized updates.
+    ///
+    /// Default is to detect whether the terminal supports them.
+    pub fn synchronized_output(mut self, enabled: bool) -> Self {
+        self.options.synchronized_output = Some(enabled);
+        self
+    }
+
+    /// Set the color profile instead of detecting it.
+    ///
+    /// Useful for terminals that misreport their capabilities, or to force
+    /// plain output with [`ColorProfile::NoColor`].
+    pub fn color_profile(mut self, profile: ColorProfile) -> Self {
+        self.options.color_profile = Some(profile);
+        self
+    }
+
+    /// Set the terminal background instead of querying the terminal.
+    ///
+    /// Adaptive colors pick their light or dark variant from it.
+    pub fn background(mut self, background: TerminalBackground) -> Self {
+        self.options.background = Some(background);
+        self
+    }
+
+    /// Register an application-wide store.
+    ///
+    /// Components read it with [`use_selector`](crate::hooks::use_selector)
+    /// and [`use_store`](crate::hooks::use_store). One store is kept per
+    /// state type; registering another store of the same type replaces it.
+    pub fn store<S: Send + Sync + 'static>(mut self, store: Store<S>) -> Self {
+        self.stores.insert(TypeId::of::<S>(), Arc::new(store));
+        self
+    }
+
     /// Get the current options
     pub fn options(&self) -> &AppOptions {
         &self.options
@@ -114,7 +177,9 @@ where
 
     /// Run the application
     pub fn run(self) -> std::io::Result<()> {
-        App::with_options(self.component, self.options).run()
+        App::with_options(self.component, self.options)
+            .with_stores(Arc::new(self.stores))
+            .run()
     }
 }
 
@@ -186,6 +251,9 @@ mod tests {
         assert_eq!(options.fps, 60);
         assert!(options.exit_on_ctrl_c);
         assert!(!options.alternate_screen);
+        assert_eq!(options.synchronized_output, None);
+        assert_eq!(options.color_profile, None);
+        assert_eq!(options.background, None);
     }
 
     #[test]
@@ -224,4 +292,34 @@ mod tests {
         let builder = AppBuilder::new(dummy).fps(30);
         assert_eq!(builder.options().fps, 30);
     }
+
+    #[test]
+    fn test_app_builder_synchronized_output() {
+        fn dummy() -> Element {
+            Text::new("test").into_element()
+        }
+        let builder = AppBuilder::new(dummy).synchronized_output(false);
+        assert_eq!(builder.options().synchronized_output, Some(false));
+    }
+
+    #[test]
+    fn test_app_builder_color_profile() {
+        fn dummy() -> Element {
+            Text::new("test").into_element()
+        }
+        let builder = AppBuilder::new(dummy).color_profile(ColorProfile::Ansi256);
+        assert_eq!(builder.options().color_profile, Some(ColorProfile::Ansi256));
+    }
+
+    #[test]
+    fn test_app_builder_background() {
+        fn dummy() -> Element {
+            Text::new("test").into_element()
+        }
+        let builder = AppBuilder::new(dummy).background(TerminalBackground::Light);
+        assert_eq!(
+            builder.options().background,
+            Some(TerminalBackground::Light)
+        );
+    }
 }
@@ -2,13 +2,53 @@
 //!
 //! This module provides functions for rendering elements to an output buffer.
 
-use crate::components::text::Line;
-use crate::core::Element;
-use crate::layout::LayoutEngine;
+use crate::core::{Element, ElementType};
+use crate::layout::bidi::{is_rtl, reorder_line};
+use crate::layout::{LayoutEngine, layout_text, measure_text_width};
 use crate::renderer::Output;
 use crate::renderer::output::ClipRegion;
 
+/// Paint order of a unit: overlay layer, then its path of stacking contexts
+///
+/// Each step is the `(z_index, tree order)` of a stacking context within its
+/// parent context. Comparing paths keeps a context's whole subtree together
+/// relative to its siblings.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct StackKey {
+    layer: u32,
+    path: Vec<(i32, usize)>,
+}
+
+impl StackKey {
+    /// A stacking context nested in this one
+    fn nested(&self, z_index: i32, order: usize) -> Self {
+        let mut path = self.path.clone();
+        path.push((z_index, order));
+        Self {
+            layer: self.layer,
+            path,
+        }
+    }
+}
+
+/// A subtree painted in one go at a single stacking level
+struct PaintUnit<'a> {
+    element: &'a Element,
+    key: StackKey,
+    offset_x: f32,
+    offset_y: f32,
+    clip: Option<ClipRegion>,
+}
+
 /// Render an element tree to an output buffer
+///
+/// Elements are painted in stacking order: the main tree first, then each
+/// portal layer above it. An element with a nonzero `z_index` starts a
+/// stacking context: it paints with its subtree, ordered by `z_index` among
+/// the other contexts inside its parent's context. Its own content sits at
+/// level 0 of the context, so children with a negative `z_index` paint below
+/// it. Contexts at the same level paint in tree order. Portals are placed
+/// relative to `element` and ignore the clipping of their ancestors.
 pub(crate) fn render_element(
     element: &Element,
     layout_engine: &LayoutEngine,
@@ -16,43 +56,153 @@ pub(crate) fn render_element(
     offset_x: f32,
     offset_y: f32,
 ) {
-    // Skip elements with display: none
-    if element.style.display == crate::core::Display::None {
-        return;
+    let root_layout = layout_engine.get_layout(element.id).unwrap_or_default();
+    let root_origin = (offset_x + root_layout.x, offset_y + root_layout.y);
+
+    let mut units = Vec::new();
+    let root_context = StackKey {
+        layer: 0,
+        path: Vec::new(),
+    };
+    collect_units(
+        element,
+        layout_engine,
+        (offset_x, offset_y),
+        root_context,
+        None,
+        root_origin,
+        &mut units,
+    );
+
+    units.sort_by(|a, b| a.key.cmp(&b.key));
+
+    for unit in units {
+        if let Some(clip) = &unit.clip {
+            output.clip(clip.clone());
+        }
+        paint_element(
+            unit.element,
+            layout_engine,
+            output,
+            unit.offset_x,
+            unit.offset_y,
+        );
+        if unit.clip.is_some() {
+            output.unclip();
+        }
     }
+}
 
-    // Get layout for this element
-    let layout = layout_engine.get_layout(element.id).unwrap_or_default();
-
-    let x = (offset_x + layout.x) as u16;
-    let y = (offset_y + layout.y) as u16;
-    let width = layout.width as u16;
-    let height = layout.height as u16;
+/// Whether a child starts a stacking context rather than painting with its parent
+fn starts_unit(child: &Element) -> bool {
+    child.element_type == ElementType::Portal || child.style.z_index != 0
+}
 
-    // Render background if set
-    if element.style.background_color.is_some() {
-        output.fill_rect(x, y, width, height, ' ', &element.style);
+/// Record `element` as the paint unit of stacking context `context`, then
+/// look for contexts nested inside it
+fn collect_units<'a>(
+    element: &'a Element,
+    layout_engine: &LayoutEngine,
+    offset: (f32, f32),
+    context: StackKey,
+    clip: Option<ClipRegion>,
+    root_origin: (f32, f32),
+    units: &mut Vec<PaintUnit<'a>>,
+) {
+    if element.style.display == crate::core::Display::None {
+        return;
     }
 
-    // Render border if set
-    if element.style.has_border() {
-        render_border(element, output, x, y, width, height);
+    units.push(PaintUnit {
+        element,
+        key: context.nested(0, 0),
+        offset_x: offset.0,
+        offset_y: offset.1,
+        clip: clip.clone(),
+    });
+    collect_nested_units(
+        element,
+        layout_engine,
+        offset,
+        &context,
+        clip,
+        root_origin,
+        units,
+    );
+}
+
+fn collect_nested_units<'a>(
+    element: &'a Element,
+    layout_engine: &LayoutEngine,
+    offset: (f32, f32),
+    context: &StackKey,
+    clip: Option<ClipRegion>,
+    root_origin: (f32, f32),
+    units: &mut Vec<PaintUnit<'a>>,
+) {
+    if element.style.display == crate::core::Display::None {
+        return;
     }
 
-    // Render text content (simple or rich text with spans)
-    let text_x =
-        x + if element.style.has_border() { 1 } else { 0 } + element.style.padding.left as u16;
-    let text_y =
-        y + if element.style.has_border() { 1 } else { 0 } + element.style.padding.top as u16;
+    let layout = layout_engine.get_layout(element.id).unwrap_or_default();
+    let x = (offset.0 + layout.x) as u16;
+    let y = (offset.1 + layout.y) as u16;
+
+    // Children inherit this element's clip intersected with the current one
+    let child_clip = match content_clip(element, x, y, layout.width as u16, layout.height as u16) {
+        Some(region) => Some(match &clip {
+            Some(current) => current.intersect(&region),
+            None => region,
+        }),
+        None => clip,
+    };
+    let child_offset = child_offset(element, offset, layout.x, layout.y);
 
-    if let Some(spans) = &element.spans {
-        // Rich text with multiple spans
-        render_spans(spans, output, text_x, text_y);
-    } else if let Some(text) = &element.text_content {
-        // Simple text
-        output.write(text_x, text_y, text, &element.style);
+    for child in &element.children {
+        // Units are recorded in tree order, so their count orders contexts
+        // at the same level; it never collides with the content step (0, 0)
+        let order = units.len();
+        if child.element_type == ElementType::Portal {
+            // Portals open a context in the next layer, outside their parent's
+            let layer = StackKey {
+                layer: context.layer + 1,
+                path: Vec::new(),
+            };
+            collect_units(
+                child,
+                layout_engine,
+                root_origin,
+                layer.nested(child.style.z_index, order),
+                None,
+                root_origin,
+                units,
+            );
+        } else if starts_unit(child) {
+            collect_units(
+                child,
+                layout_engine,
+                child_offset,
+                context.nested(child.style.z_index, order),
+                child_clip.clone(),
+                root_origin,
+                units,
+            );
+        } else {
+            collect_nested_units(
+                child,
+                layout_engine,
+                child_offset,
+                context,
+                child_clip.clone(),
+                root_origin,
+                units,
+            );
+        }
     }
+}
 
+/// Clip region for an element's children, if its overflow is clipped
+fn content_clip(element: &Element, x: u16, y: u16, width: u16, height: u16) -> Option<ClipRegion> {
     // Check if overflow clipping is needed for children
     let needs_clip = element.style.overflow_x == crate::core::Overflow::Hidden
         || element.style.overflow_x == crate::core::Overflow::Scroll
@@ -65,30 +215,82 @@ pub(crate) fn render_element(
     let clip_width = width.saturating_sub(if element.style.has_border() { 2 } else { 0 });
     let clip_height = height.saturating_sub(if element.style.has_border() { 2 } else { 0 });
 
-    // Apply clip region if overflow is hidden or scroll
     if needs_clip && clip_width > 0 && clip_height > 0 {
-        output.clip(ClipRegion {
+        Some(ClipRegion {
             x1: clip_x,
             y1: clip_y,
             x2: clip_x + clip_width,
             y2: clip_y + clip_height,
-        });
+        })
+    } else {
+        None
     }
+}
 
-    // Render children - Taffy already includes border/padding in child positions
-    // Apply scroll offset if element has scroll_offset set
+/// Offset passed to children - Taffy already includes border/padding in
+/// child positions, so only the scroll offset is applied here
+fn child_offset(element: &Element, offset: (f32, f32), x: f32, y: f32) -> (f32, f32) {
     let scroll_offset_x = element.scroll_offset_x.unwrap_or(0) as f32;
     let scroll_offset_y = element.scroll_offset_y.unwrap_or(0) as f32;
+    (
+        offset.0 + x - scroll_offset_x,
+        offset.1 + y - scroll_offset_y,
+    )
+}
+
+/// Paint an element and the descendants that share its stacking context
+fn paint_element(
+    element: &Element,
+    layout_engine: &LayoutEngine,
+    output: &mut Output,
+    offset_x: f32,
+    offset_y: f32,
+) {
+    // Skip elements with display: none
+    if element.style.display == crate::core::Display::None {
+        return;
+    }
+
+    // Get layout for this element
+    let layout = layout_engine.get_layout(element.id).unwrap_or_default();
+
+    let x = (offset_x + layout.x) as u16;
+    let y = (offset_y + layout.y) as u16;
+    let width = layout.width as u16;
+    let height = layout.height as u16;
+
+    // Render background if set
+    if element.style.background_color.is_some() {
+        output.fill_rect(x, y, width, height, ' ', &element.style);
+    }
+
+    // Render border if set
+    if element.style.has_border() {
+        render_border(element, output, x, y, width, height);
+    }
 
-    let child_offset_x = offset_x + layout.x - scroll_offset_x;
-    let child_offset_y = offset_y + layout.y - scroll_offset_y;
+    // Render text content (simple or rich text with spans)
+    render_text(element, output, x, y, width);
+
+    // Apply clip region if overflow is hidden or scroll
+    let clip = content_clip(element, x, y, width, height);
+    if let Some(region) = &clip {
+        output.clip(region.clone());
+    }
+
+    // Render children, leaving those in their own stacking context for later
+    let (child_offset_x, child_offset_y) =
+        child_offset(element, (offset_x, offset_y), layout.x, layout.y);
 
     for child in &element.children {
-        render_element(child, layout_engine, output, child_offset_x, child_offset_y);
+        if starts_unit(child) {
+            continue;
+        }
+        paint_element(child, layout_engine, output, child_offset_x, child_offset_y);
     }
 
     // Remove clip region
-    if needs_clip && clip_width > 0 && clip_height > 0 {
+    if clip.is_some() {
         output.unclip();
     }
 }
@@ -166,15 +368,46 @@ pub(crate) fn render_border(
     }
 }
 
-/// Render rich text spans
-pub(crate) fn render_spans(lines: &[Line], output: &mut Output, start_x: u16, start_y: u16) {
-    for (line_idx, line) in lines.iter().enumerate() {
-        let y = start_y + line_idx as u16;
-        let mut x = start_x;
+/// Render an element's text into its content box
+///
+/// The text is laid out with [`layout_text`] at the content width, exactly
+/// as the layout engine measured it, so wrapped and truncated rows match the
+/// space the element was given. Span styles carry over to wrapped rows, and
+/// rows with right-to-left text are reordered into visual order.
+pub(crate) fn render_text(element: &Element, output: &mut Output, x: u16, y: u16, width: u16) {
+    let pieces = element.text_pieces();
+    if pieces.is_empty() {
+        return;
+    }
 
-        for span in &line.spans {
-            output.write(x, y, &span.content, &span.style);
-            x += span.width() as u16;
+    let (top, right, _, left) = element.style.content_insets();
+    let text_x = x.saturating_add(left as u16);
+    let text_y = y.saturating_add(top as u16);
+    let content_width = (width as f32 - left - right).max(0.0) as usize;
+
+    let texts: Vec<&str> = pieces.iter().map(|(text, _)| *text).collect();
+    let lines = layout_text(
+        &texts,
+        Some(content_width),
+        element.style.text_wrap,
+        element.style.ellipsis(),
+    );
+
+    let direction = element.style.direction.unwrap_or_default();
+    let rtl = is_rtl(&texts.concat(), direction);
+
+    for (row, line) in lines.iter().enumerate() {
+        let row_y = text_y.saturating_add(row as u16);
+        // Right-to-left rows are reordered for display and aligned right
+        let line = reorder_line(line, rtl);
+        let mut col = if rtl {
+            text_x.saturating_add(content_width.saturating_sub(line.width) as u16)
+        } else {
+            text_x
+        };
+        for fragment in &line.fragments {
+            output.write(col, row_y, &fragment.text, pieces[fragment.span].1);
+            col = col.saturating_add(measure_text_width(&fragment.text) as u16);
         }
     }
 }
@@ -198,6 +431,26 @@ mod tests {
         assert!(rendered.contains("Hello"));
     }
 
+    #[test]
+    fn test_border_under_wide_ambiguous_policy() {
+        use crate::layout::AmbiguousWidth;
+        use crate::layout::measure::with_ambiguous_width;
+        use crate::renderer::render_to_string;
+
+        let element = Box::new()
+            .width(10)
+            .border_style(BorderStyle::Single)
+            .child(Text::new("hi…").into_element())
+            .into_element();
+
+        let rendered = with_ambiguous_width(AmbiguousWidth::Wide, || {
+            crate::testing::strip_ansi_codes(&render_to_string(&element, 20))
+        });
+        let lines: Vec<&str> = rendered.lines().collect();
+        // "…" takes two cells, the border glyphs one each
+        assert_eq!(lines, vec!["┌────────┐", "│hi…    │", "└────────┘"]);
+    }
+
     #[test]
     fn test_render_with_border() {
         let element = Box::new()
@@ -215,4 +468,215 @@ mod tests {
         assert!(rendered.contains("Test"));
         assert!(rendered.contains("─")); // Border character
     }
+
+    fn render_lines(element: &mut Element, width: u16, height: u16) -> Vec<String> {
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(element, width, height);
+
+        let mut output = Output::new(width, height);
+        render_element(element, &engine, &mut output, 0.0, 0.0);
+        crate::testing::strip_ansi_codes(&output.render())
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrapped_rich_text_matches_layout() {
+        use crate::components::text::Span;
+
+        let mut root = Box::new()
+            .width(12)
+            .border_style(BorderStyle::Single)
+            .child(
+                Text::spans(vec![
+                    Span::new("plain "),
+                    Span::new("bold text wraps").bold(),
+                ])
+                .into_element(),
+            )
+            .into_element();
+
+        let mut engine = LayoutEngine::new();
+        engine.compute_incremental(&mut root, 20, 6);
+        let text = root.children.get(0).unwrap();
+        assert_eq!(engine.get_layout(text.id).unwrap().height, 2.0);
+
+        let mut output = Output::new(20, 6);
+        render_element(&root, &engine, &mut output, 0.0, 0.0);
+        let rendered = output.render();
+        let lines: Vec<String> = crate::testing::strip_ansi_codes(&rendered)
+            .lines()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        assert_eq!(
+            lines[..4],
+            [
+                "┌──────────┐",
+                "│plain bold│",
+                "│text wraps│",
+                "└──────────┘"
+            ]
+        );
+        // The bold span keeps its style on the row it wrapped onto
+        let raw: Vec<&str> = rendered.lines().collect();
+        assert!(raw[2].contains("\x1b[1m"));
+    }
+
+    #[test]
+    fn test_truncated_text_uses_ellipsis_option() {
+        let mut root = Box::new()
+            .width(8)
+            .child(
+                Text::new("truncate this line")
+                    .wrap(crate::core::TextWrap::TruncateMiddle)
+                    .ellipsis("..")
+                    .into_element(),
+            )
+            .into_element();
+
+        assert_eq!(render_lines(&mut root, 20, 2)[0].trim_end(), "tru..ine");
+    }
+
+    #[test]
+    fn test_rtl_text_reordered_per_row() {
+        let text = || Text::new("אחת שתיים שלוש").into_element();
+
+        // Measurement is unchanged: rows wrap in logical order, then each row
+        // is reversed for display and aligned right
+        let mut rtl = Box::new()
+            .width(10)
+            .direction(crate::core::TextDirection::Rtl)
+            .child(text())
+            .into_element();
+        let lines = render_lines(&mut rtl, 12, 3);
+        assert_eq!(lines[0].trim_end(), " םייתש תחא");
+        assert_eq!(lines[1].trim_end(), "      שולש");
+
+        // Auto picks up the direction from the text itself
+        let mut auto = Box::new().width(10).child(text()).into_element();
+        assert_eq!(render_lines(&mut auto, 12, 3), lines);
+
+        // With an LTR base the Hebrew run is still reversed, but rows align left
+        let mut ltr = Box::new()
+            .width(10)
+            .direction(crate::core::TextDirection::Ltr)
+            .child(text())
+            .into_element();
+        assert_eq!(render_lines(&mut ltr, 12, 3)[1].trim_end(), "שולש");
+    }
+
+    #[test]
+    fn test_z_index_paint_order() {
+        let overlapping = |z_first: i32| {
+            Box::new()
+                .child(
+                    Box::new()
+                        .position_absolute()
+                        .z_index(z_first)
+                        .child(Text::new("AAAA").into_element())
+                        .into_element(),
+                )
+                .child(
+                    Box::new()
+                        .position_absolute()
+                        .child(Text::new("BB").into_element())
+                        .into_element(),
+                )
+                .into_element()
+        };
+
+        // Same level: later siblings paint on top
+        assert_eq!(render_lines(&mut overlapping(0), 10, 2)[0], "BBAA");
+        // A higher z-index paints on top regardless of tree order
+        assert_eq!(render_lines(&mut overlapping(1), 10, 2)[0], "AAAA");
+        // A negative z-index paints below its siblings
+        assert_eq!(render_lines(&mut overlapping(-1), 10, 2)[0], "BBAA");
+    }
+
+    #[test]
+    fn test_z_index_scoped_to_parent_context() {
+        let nested = |child_z: i32| {
+            let parent = Box::new()
+                .position_absolute()
+                .z_index(5)
+                .child(Text::new("PPPP").into_element())
+                .child(
+                    Box::new()
+                        .position_absolute()
+                        .z_index(child_z)
+                        .child(Text::new("c").into_element())
+                        .into_element(),
+                )
+                .into_element();
+            let sibling = Box::new()
+                .position_absolute()
+                .z_index(3)
+                .child(Text::new("SSSSSS").into_element())
+                .into_element();
+            Box::new().child(parent).child(sibling).into_element()
+        };
+
+        // The child orders against its parent, and with it above the z=3 sibling
+        assert_eq!(render_lines(&mut nested(1), 10, 2)[0], "cPPPSS");
+        // A negative z-index paints below the parent, still above the sibling
+        assert_eq!(render_lines(&mut nested(-1), 10, 2)[0], "PPPPSS");
+        // Zero paints with the parent, in tree order
+        assert_eq!(render_lines(&mut nested(0), 10, 2)[0], "cPPPSS");
+    }
+
+    #[test]
+    fn test_portal_escapes_overflow_hidden() {
+        let mut root = Box::new()
+            .flex_direction(crate::core::FlexDirection::Column)
+            .child(Text::new("header").into_element())
+            .child(
+                Box::new()
+                    .width(4)
+                    .height(1)
+                    .overflow(crate::core::Overflow::Hidden)
+                    .child(Text::new("clipped text").into_element())
+                    .child(
+                        crate::components::Portal::new()
+                            .child(
+                                Box::new()
+                                    .position_absolute()
+                                    .top(2.0)
+                                    .left(3.0)
+                                    .child(Text::new("tooltip").into_element())
+                                    .into_element(),
+                            )
+                            .into_element(),
+                    )
+                    .into_element(),
+            )
+            .child(Text::new("footer line").into_element())
+            .into_element();
+
+        let lines = render_lines(&mut root, 20, 4);
+        assert_eq!(lines[0], "header");
+        assert_eq!(lines[1], "clip");
+        // The portal is laid out against the root and painted over the footer
+        assert_eq!(lines[2], "footooltipe");
+    }
+
+    #[test]
+    fn test_nested_clip_regions_intersect() {
+        let mut root = Box::new()
+            .width(3)
+            .height(1)
+            .overflow(crate::core::Overflow::Hidden)
+            .child(
+                Box::new()
+                    .width(10)
+                    .height(1)
+                    .flex_shrink(0.0)
+                    .overflow(crate::core::Overflow::Hidden)
+                    .child(Text::new("abcdefgh").into_element())
+                    .into_element(),
+            )
+            .into_element();
+
+        assert_eq!(render_lines(&mut root, 20, 2)[0], "abc");
+    }
 }
@@ -33,6 +33,7 @@ mod app;
 mod builder;
 pub(crate) mod element_renderer;
 mod output;
+mod program;
 pub(crate) mod registry;
 pub(crate) mod render_to_string;
 pub(crate) mod runtime;
@@ -45,6 +46,9 @@ pub use app::App;
 // Builder and options
 pub use builder::{AppBuilder, AppOptions, render, render_fullscreen, render_inline};
 
+// Elm-style programs
+pub use program::{Program, program};
+
 // Registry APIs
 pub use registry::{
     AppSink, IntoPrintable, ModeSwitch, Printable, RenderHandle, enter_alt_screen, exit_alt_screen,
@@ -1,13 +1,18 @@
 //! Output buffer for terminal rendering
 
-use crate::core::{Color, Style};
+use crate::core::{Color, Style, color_profile};
+use crate::layout::measure_text_width;
 use std::fmt::Write as FmtWrite;
-use unicode_width::UnicodeWidthChar;
+use unicode_segmentation::UnicodeSegmentation;
 
-/// A styled character in the output grid
+/// A styled cell in the output grid
+///
+/// Each cell holds a whole grapheme cluster, so emoji sequences, flags and
+/// combining accents stay together. A cluster wider than one cell is followed
+/// by placeholder cells with an empty grapheme.
 #[derive(Debug, Clone, Default)]
 pub struct StyledChar {
-    pub ch: char,
+    pub grapheme: String,
     pub fg: Option<Color>,
     pub bg: Option<Color>,
     pub bold: bool,
@@ -21,14 +26,19 @@ pub struct StyledChar {
 impl StyledChar {
     pub fn new(ch: char) -> Self {
         Self {
-            ch,
+            grapheme: ch.to_string(),
             ..Default::default()
         }
     }
 
     pub fn with_style(ch: char, style: &Style) -> Self {
+        Self::grapheme_with_style(ch.encode_utf8(&mut [0; 4]), style)
+    }
+
+    /// Create a cell holding a grapheme cluster
+    pub fn grapheme_with_style(grapheme: &str, style: &Style) -> Self {
         Self {
-            ch,
+            grapheme: grapheme.to_string(),
             fg: style.color,
             bg: style.background_color,
             bold: style.bold,
@@ -40,6 +50,16 @@ impl StyledChar {
         }
     }
 
+    /// The continuation cell of a wide grapheme
+    pub fn placeholder() -> Self {
+        Self::default()
+    }
+
+    /// Check if this cell continues the wide grapheme before it
+    pub fn is_placeholder(&self) -> bool {
+        self.grapheme.is_empty()
+    }
+
     /// Check if this char has any styling
     pub fn has_style(&self) -> bool {
         self.fg.is_some()
@@ -78,6 +98,18 @@ impl ClipRegion {
     pub fn contains(&self, x: u16, y: u16) -> bool {
         x >= self.x1 && x < self.x2 && y >= self.y1 && y < self.y2
     }
+
+    /// Region covered by both `self` and `other`
+    pub fn intersect(&self, other: &ClipRegion) -> ClipRegion {
+        let x1 = self.x1.max(other.x1);
+        let y1 = self.y1.max(other.y1);
+        ClipRegion {
+            x1,
+            y1,
+            x2: self.x2.min(other.x2).max(x1),
+            y2: self.y2.min(other.y2).max(y1),
+        }
+    }
 }
 
 /// Output buffer that collects rendered content
@@ -101,6 +133,10 @@ impl Output {
     }
 
     /// Write text at position with style
+    ///
+    /// Text is split into grapheme clusters that each take as many cells as
+    /// [`measure_text_width`] gives them, so painted text lines up with the
+    /// layout. Writing stops at a newline or the edge of the buffer.
     pub fn write(&mut self, x: u16, y: u16, text: &str, style: &Style) {
         let mut col = x as usize;
         let row = y as usize;
@@ -109,8 +145,8 @@ impl Output {
             return;
         }
 
-        for ch in text.chars() {
-            if ch == '\n' {
+        for grapheme in text.graphemes(true) {
+            if grapheme == "\n" || grapheme == "\r\n" {
                 break;
             }
 
@@ -118,58 +154,7 @@ impl Output {
                 break;
             }
 
-            let char_width = ch.width().unwrap_or(1);
-
-            // Handle wide character at buffer boundary - skip if it won't fit
-            if char_width == 2 && col + 1 >= self.grid[row].len() {
-                // Wide char would extend past buffer, write a space instead
-                self.grid[row][col] = StyledChar::with_style(' ', style);
-                col += 1;
-                continue;
-            }
-
-            // Check clip region
-            if let Some(clip) = self.clip_stack.last()
-                && !clip.contains(col as u16, row as u16)
-            {
-                col += char_width;
-                continue;
-            }
-
-            // Handle overwriting wide character's second half (placeholder)
-            // If current position is a placeholder '\0', we're breaking a wide char
-            if self.grid[row][col].ch == '\0' && col > 0 {
-                // Clear the first half of the broken wide char
-                self.grid[row][col - 1] = StyledChar::new(' ');
-            }
-
-            // Handle overwriting wide character's first half
-            // If current position has a wide char, its placeholder will be orphaned
-            let old_char_width = self.grid[row][col].ch.width().unwrap_or(1);
-            if old_char_width == 2 && col + 1 < self.grid[row].len() {
-                // Clear the orphaned placeholder
-                self.grid[row][col + 1] = StyledChar::new(' ');
-            }
-
-            self.grid[row][col] = StyledChar::with_style(ch, style);
-
-            // For wide characters (width=2), mark the next cell as a placeholder
-            if char_width == 2 && col + 1 < self.grid[row].len() {
-                // Check if we're about to overwrite another wide char's first half
-                if self.grid[row][col + 1].ch == '\0' {
-                    // This shouldn't happen as we just handled it, but be safe
-                } else {
-                    let next_char_width = self.grid[row][col + 1].ch.width().unwrap_or(1);
-                    if next_char_width == 2 && col + 2 < self.grid[row].len() {
-                        // Clear the placeholder of the wide char we're overwriting
-                        self.grid[row][col + 2] = StyledChar::new(' ');
-                    }
-                }
-                // Use a special marker for wide char continuation
-                self.grid[row][col + 1] = StyledChar::new('\0');
-            }
-
-            col += char_width;
+            col += self.put(col, row, grapheme, style);
         }
     }
 
@@ -182,44 +167,75 @@ impl Output {
             return;
         }
 
-        let char_width = ch.width().unwrap_or(1);
+        self.put(col, row, ch.encode_utf8(&mut [0; 4]), style);
+    }
 
-        // Handle wide character at buffer boundary - skip if it won't fit
-        if char_width == 2 && col + 1 >= self.grid[row].len() {
-            // Wide char would extend past buffer, write a space instead
-            self.grid[row][col] = StyledChar::with_style(' ', style);
-            return;
+    /// Place a grapheme cluster at a cell, returning the cells it advances
+    fn put(&mut self, col: usize, row: usize, grapheme: &str, style: &Style) -> usize {
+        let width = measure_text_width(grapheme);
+        // Zero-width clusters don't take a cell of their own
+        if width == 0 {
+            return 0;
         }
 
-        // Check clip region
-        if let Some(clip) = self.clip_stack.last()
-            && !clip.contains(x, y)
-        {
-            return;
+        let line_width = self.grid[row].len();
+        let end = col + width;
+
+        // Handle wide grapheme at buffer boundary - pad with spaces instead
+        if end > line_width {
+            for c in col..line_width {
+                self.set_cell(c, row, StyledChar::with_style(' ', style));
+            }
+            return width;
         }
 
-        // Handle overwriting wide character's second half (placeholder)
-        if self.grid[row][col].ch == '\0' && col > 0 {
-            self.grid[row][col - 1] = StyledChar::new(' ');
+        // Check clip region
+        if let Some(clip) = self.clip_stack.last().cloned() {
+            if !clip.contains(col as u16, row as u16) {
+                return width;
+            }
+            if !clip.contains((end - 1) as u16, row as u16) {
+                // Partly clipped wide grapheme: fill the visible cells instead
+                for c in (col..end).take_while(|&c| clip.contains(c as u16, row as u16)) {
+                    self.set_cell(c, row, StyledChar::with_style(' ', style));
+                }
+                return width;
+            }
         }
 
-        // Handle overwriting wide character's first half
-        let old_char_width = self.grid[row][col].ch.width().unwrap_or(1);
-        if old_char_width == 2 && col + 1 < self.grid[row].len() {
-            self.grid[row][col + 1] = StyledChar::new(' ');
+        self.set_cell(col, row, StyledChar::grapheme_with_style(grapheme, style));
+        // For wide graphemes, mark the following cells as placeholders
+        for c in col + 1..end {
+            self.set_cell(c, row, StyledChar::placeholder());
         }
+        width
+    }
 
-        self.grid[row][col] = StyledChar::with_style(ch, style);
+    /// Replace a cell, breaking any wide grapheme the change cuts through
+    fn set_cell(&mut self, col: usize, row: usize, cell: StyledChar) {
+        let line = &mut self.grid[row];
 
-        // For wide characters (width=2), mark the next cell as a placeholder
-        if char_width == 2 && col + 1 < self.grid[row].len() {
-            // Handle overwriting the next position's wide char if any
-            let next_char_width = self.grid[row][col + 1].ch.width().unwrap_or(1);
-            if next_char_width == 2 && col + 2 < self.grid[row].len() {
-                self.grid[row][col + 2] = StyledChar::new(' ');
+        // Overwriting a placeholder breaks the wide grapheme it belongs to
+        if line[col].is_placeholder() {
+            let mut start = col;
+            while start > 0 && line[start].is_placeholder() {
+                start -= 1;
+            }
+            for broken in &mut line[start..col] {
+                *broken = StyledChar::new(' ');
             }
-            self.grid[row][col + 1] = StyledChar::new('\0');
         }
+
+        // Overwriting a wide grapheme orphans its placeholders
+        if !cell.is_placeholder() || !line[col].is_placeholder() {
+            let mut next = col + 1;
+            while next < line.len() && line[next].is_placeholder() {
+                line[next] = StyledChar::new(' ');
+                next += 1;
+            }
+        }
+
+        line[col] = cell;
     }
 
     /// Fill a rectangle with a character
@@ -232,10 +248,22 @@ impl Output {
     }
 
     /// Push a clip region
+    ///
+    /// Nested regions are intersected with the enclosing one, so content
+    /// never escapes an outer clip.
     pub fn clip(&mut self, region: ClipRegion) {
+        let region = match self.clip_stack.last() {
+            Some(current) => current.intersect(&region),
+            None => region,
+        };
         self.clip_stack.push(region);
     }
 
+    /// The clip region currently in effect, if any
+    pub fn current_clip(&self) -> Option<&ClipRegion> {
+        self.clip_stack.last()
+    }
+
     /// Pop the current clip region
     pub fn unclip(&mut self) {
         self.clip_stack.pop();
@@ -252,7 +280,7 @@ impl Output {
             for (i, cell) in row.iter().enumerate() {
                 // Consider any non-default-space character as content
                 // A space with styling (color, bg, etc) is still content
-                if cell.ch != '\0' && (cell.ch != ' ' || cell.has_style()) {
+                if !cell.is_placeholder() && (cell.grapheme != " " || cell.has_style()) {
                     last_content_idx = i + 1;
                 }
             }
@@ -267,26 +295,13 @@ impl Output {
                 }
 
                 // Skip wide character continuation placeholders
-                if cell.ch == '\0' {
+                if cell.is_placeholder() {
                     continue;
                 }
 
-                // Check if we need to change style
-                let need_style_change = match &current_style {
-                    None => cell.has_style(),
-                    Some(prev) => !cell.same_style(prev),
-                };
-
-                if need_style_change {
-                    // Only reset if we had a previous style (not for first styled char)
-                    if current_style.is_some() {
-                        line.push_str("\x1b[0m");
-                    }
-                    self.apply_style(&mut line, cell);
-                    current_style = Some(cell.clone());
-                }
-
-                line.push(cell.ch);
+                // Only emit the attributes that differ from the last cell
+                self.transition(&mut line, &mut current_style, cell);
+                line.push_str(&cell.grapheme);
             }
 
             // Reset at end of line
@@ -316,7 +331,7 @@ impl Output {
         for row in self.grid.iter() {
             let mut last_content_idx = 0;
             for (i, cell) in row.iter().enumerate() {
-                if cell.ch != '\0' && (cell.ch != ' ' || cell.has_style()) {
+                if !cell.is_placeholder() && (cell.grapheme != " " || cell.has_style()) {
                     last_content_idx = i + 1;
                 }
             }
@@ -329,24 +344,12 @@ impl Output {
                     break;
                 }
 
-                if cell.ch == '\0' {
+                if cell.is_placeholder() {
                     continue;
                 }
 
-                let need_style_change = match &current_style {
-                    None => cell.has_style(),
-                    Some(prev) => !cell.same_style(prev),
-                };
-
-                if need_style_change {
-                    if current_style.is_some() {
-                        line.push_str("\x1b[0m");
-                    }
-                    self.apply_style(&mut line, cell);
-                    current_style = Some(cell.clone());
-                }
-
-                line.push(cell.ch);
+                self.transition(&mut line, &mut current_style, cell);
+                line.push_str(&cell.grapheme);
             }
 
             if current_style.is_some() {
@@ -362,9 +365,214 @@ impl Output {
         lines.join("\r\n")
     }
 
-    fn apply_style(&self, result: &mut String, cell: &StyledChar) {
-        let mut codes: Vec<u8> = Vec::new();
+    /// Escape sequences that turn a screen showing `previous` into this frame
+    ///
+    /// Both frames are anchored at the top-left corner of the screen and
+    /// cells outside a frame count as blank. Cells are compared by grapheme
+    /// and style; only runs of changed cells are written, each reached with
+    /// the shortest cursor movement, and SGR codes are only emitted where the
+    /// style actually changes. A changed row that ends in blanks is finished
+    /// with an erase to end of line.
+    pub fn render_diff(&self, previous: &Output) -> String {
+        let blank = StyledChar::new(' ');
+        let width = self.width.max(previous.width) as usize;
+        let height = self.height.max(previous.height) as usize;
+        let cell = |grid: &[Vec<StyledChar>], row: usize, col: usize| -> StyledChar {
+            grid.get(row)
+                .and_then(|line| line.get(col))
+                .cloned()
+                .unwrap_or_else(|| blank.clone())
+        };
+
+        let mut out = String::new();
+        let mut cursor: Option<(usize, usize)> = None;
+        let mut pen: Option<StyledChar> = None;
+
+        for row in 0..height {
+            let new_row: Vec<StyledChar> = (0..width).map(|c| cell(&self.grid, row, c)).collect();
+            let old_row: Vec<StyledChar> =
+                (0..width).map(|c| cell(&previous.grid, row, c)).collect();
+            let changed = |col: usize| {
+                new_row[col].grapheme != old_row[col].grapheme
+                    || !new_row[col].same_style(&old_row[col])
+            };
+
+            let mut col = 0;
+            while col < width {
+                if !changed(col) {
+                    col += 1;
+                    continue;
+                }
+
+                // A changed continuation cell means its wide grapheme is redrawn
+                let mut start = col;
+                while start > 0 && new_row[start].is_placeholder() {
+                    start -= 1;
+                }
+
+                self.move_cursor(&mut out, &mut cursor, &pen, &new_row, row, start);
+
+                if new_row[start..]
+                    .iter()
+                    .all(|c| c.grapheme == " " && !c.has_style())
+                {
+                    // Erasing uses the current background, so reset first
+                    if pen.take().is_some() {
+                        out.push_str("\x1b[0m");
+                    }
+                    out.push_str("\x1b[K");
+                    break;
+                }
+
+                col = start;
+                while col < width && (col == start || changed(col)) {
+                    let current = &new_row[col];
+                    if current.is_placeholder() {
+                        col += 1;
+                        continue;
+                    }
+                    self.transition(&mut out, &mut pen, current);
+                    out.push_str(&current.grapheme);
+                    col += measure_text_width(&current.grapheme).max(1);
+                }
+
+                // The cursor waits for a wrap after the last column
+                cursor = (col < width).then_some((row, col));
+            }
+        }
+
+        if pen.is_some() {
+            out.push_str("\x1b[0m");
+        }
+        out
+    }
+
+    /// Move the cursor to a cell, choosing the cheapest way to get there
+    ///
+    /// Moving right along the row rewrites the cells in between when they
+    /// are plain, already in the current style and shorter than a cursor
+    /// sequence.
+    fn move_cursor(
+        &self,
+        out: &mut String,
+        cursor: &mut Option<(usize, usize)>,
+        pen: &Option<StyledChar>,
+        line: &[StyledChar],
+        row: usize,
+        col: usize,
+    ) {
+        match *cursor {
+            Some((r, c)) if r == row && c == col => {}
+            Some((r, c)) if r == row && c < col => {
+                let gap = &line[c..col];
+                let jump = format!("\x1b[{}C", col - c);
+                let rewrite_len: usize = gap.iter().map(|cell| cell.grapheme.len()).sum();
+                let plain = gap.iter().all(|cell| {
+                    !cell.is_placeholder()
+                        && measure_text_width(&cell.grapheme) == 1
+                        && match pen {
+                            Some(pen) => cell.same_style(pen),
+                            None => !cell.has_style(),
+                        }
+                });
+                if plain && rewrite_len <= jump.len() {
+                    for cell in gap {
+                        out.push_str(&cell.grapheme);
+                    }
+                } else {
+                    out.push_str(&jump);
+                }
+            }
+            Some((r, _)) if r + 1 == row && col == 0 => out.push_str("\r\n"),
+            _ => {
+                let _ = write!(out, "\x1b[{};{}H", row + 1, col + 1);
+            }
+        }
+        *cursor = Some((row, col));
+    }
+
+    /// Switch the pen to the style of a cell if it differs
+    ///
+    /// `pen` is the SGR state the terminal is in, `None` meaning the default.
+    /// Only the attributes that change are emitted (`39` to drop the
+    /// foreground, `22` to end bold, ...), unless resetting and applying the
+    /// new style from scratch is shorter.
+    fn transition(&self, out: &mut String, pen: &mut Option<StyledChar>, cell: &StyledChar) {
+        let unchanged = match pen {
+            None => !cell.has_style(),
+            Some(prev) => cell.same_style(prev),
+        };
+        if unchanged {
+            return;
+        }
 
+        let mut full = vec![0];
+        self.style_codes(cell, &mut full);
+        let codes = match pen {
+            Some(prev) => {
+                let delta = self.delta_codes(prev, cell);
+                if sgr_len(&delta) < sgr_len(&full) {
+                    delta
+                } else {
+                    full
+                }
+            }
+            None => full.split_off(1),
+        };
+        push_sgr(out, &codes);
+        *pen = cell.has_style().then(|| cell.clone());
+    }
+
+    /// SGR codes that take the terminal from one style to another
+    fn delta_codes(&self, from: &StyledChar, to: &StyledChar) -> Vec<u8> {
+        let mut codes = Vec::new();
+
+        // Bold and dim are both turned off by 22
+        if (from.bold && !to.bold) || (from.dim && !to.dim) {
+            codes.push(22);
+            if to.bold {
+                codes.push(1);
+            }
+            if to.dim {
+                codes.push(2);
+            }
+        } else {
+            if to.bold && !from.bold {
+                codes.push(1);
+            }
+            if to.dim && !from.dim {
+                codes.push(2);
+            }
+        }
+
+        for (was, is, on, off) in [
+            (from.italic, to.italic, 3, 23),
+            (from.underline, to.underline, 4, 24),
+            (from.inverse, to.inverse, 7, 27),
+            (from.strikethrough, to.strikethrough, 9, 29),
+        ] {
+            if was != is {
+                codes.push(if is { on } else { off });
+            }
+        }
+
+        // Compare the colors the terminal actually shows, so an adaptive
+        // color resolving to `Reset` or one the profile drops still resets
+        for (was, is, background) in [(from.fg, to.fg, false), (from.bg, to.bg, true)] {
+            let (was, is) = (shown_color(was), shown_color(is));
+            if was != is {
+                match is {
+                    None => codes.push(if background { 49 } else { 39 }),
+                    Some(color) => self.color_to_ansi(color, background, &mut codes),
+                }
+            }
+        }
+
+        codes
+    }
+
+    /// SGR codes that apply a cell's style on top of the default style
+    fn style_codes(&self, cell: &StyledChar, codes: &mut Vec<u8>) {
         if cell.bold {
             codes.push(1);
         }
@@ -385,30 +593,24 @@ impl Output {
         }
 
         if let Some(fg) = cell.fg {
-            self.color_to_ansi(fg, false, &mut codes);
+            self.color_to_ansi(fg, false, codes);
         }
 
         if let Some(bg) = cell.bg {
-            self.color_to_ansi(bg, true, &mut codes);
-        }
-
-        if !codes.is_empty() {
-            result.push_str("\x1b[");
-            for (i, code) in codes.iter().enumerate() {
-                if i > 0 {
-                    result.push(';');
-                }
-                let _ = write!(result, "{}", code);
-            }
-            result.push('m');
+            self.color_to_ansi(bg, true, codes);
         }
     }
 
+    /// SGR codes for a color, downsampled to the active color profile
     fn color_to_ansi(&self, color: Color, background: bool, codes: &mut Vec<u8>) {
+        let Some(color) = color.downsample(color_profile()) else {
+            return;
+        };
         let base = if background { 40 } else { 30 };
 
         match color {
-            Color::Reset => {}
+            // Adaptive colors are resolved by `downsample`
+            Color::Reset | Color::Adaptive(_) => {}
             Color::Black => codes.push(base),
             Color::Red => codes.push(base + 1),
             Color::Green => codes.push(base + 2),
@@ -441,6 +643,43 @@ impl Output {
     }
 }
 
+/// The color a cell shows under the active color profile, `None` for the
+/// terminal default
+fn shown_color(color: Option<Color>) -> Option<Color> {
+    color
+        .and_then(|color| color.downsample(color_profile()))
+        .filter(|color| *color != Color::Reset)
+}
+
+/// Write an SGR sequence for a list of codes
+fn push_sgr(out: &mut String, codes: &[u8]) {
+    if codes.is_empty() {
+        return;
+    }
+    out.push_str("\x1b[");
+    for (i, code) in codes.iter().enumerate() {
+        if i > 0 {
+            out.push(';');
+        }
+        let _ = write!(out, "{}", code);
+    }
+    out.push('m');
+}
+
+/// Length in bytes of the SGR sequence for a list of codes
+fn sgr_len(codes: &[u8]) -> usize {
+    let digits: usize = codes
+        .iter()
+        .map(|&code| match code {
+            0..=9 => 1,
+            10..=99 => 2,
+            _ => 3,
+        })
+        .sum();
+    // ESC [ ... m plus the separators
+    3 + digits + codes.len().saturating_sub(1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -457,8 +696,8 @@ mod tests {
         let mut output = Output::new(80, 24);
         output.write(0, 0, "Hello", &Style::default());
 
-        assert_eq!(output.grid[0][0].ch, 'H');
-        assert_eq!(output.grid[0][4].ch, 'o');
+        assert_eq!(output.grid[0][0].grapheme, "H");
+        assert_eq!(output.grid[0][4].grapheme, "o");
     }
 
     #[test]
@@ -480,11 +719,11 @@ mod tests {
         output.write(0, 0, "你好", &Style::default());
 
         // '你' at position 0, placeholder at position 1
-        assert_eq!(output.grid[0][0].ch, '你');
-        assert_eq!(output.grid[0][1].ch, '\0');
+        assert_eq!(output.grid[0][0].grapheme, "你");
+        assert_eq!(output.grid[0][1].grapheme, "");
         // '好' at position 2, placeholder at position 3
-        assert_eq!(output.grid[0][2].ch, '好');
-        assert_eq!(output.grid[0][3].ch, '\0');
+        assert_eq!(output.grid[0][2].grapheme, "好");
+        assert_eq!(output.grid[0][3].grapheme, "");
     }
 
     #[test]
@@ -492,15 +731,15 @@ mod tests {
         let mut output = Output::new(80, 24);
         // Write a wide char first
         output.write(0, 0, "你", &Style::default());
-        assert_eq!(output.grid[0][0].ch, '你');
-        assert_eq!(output.grid[0][1].ch, '\0');
+        assert_eq!(output.grid[0][0].grapheme, "你");
+        assert_eq!(output.grid[0][1].grapheme, "");
 
         // Overwrite the placeholder with a narrow char
         output.write_char(1, 0, 'X', &Style::default());
 
         // The wide char should be replaced with space (broken)
-        assert_eq!(output.grid[0][0].ch, ' ');
-        assert_eq!(output.grid[0][1].ch, 'X');
+        assert_eq!(output.grid[0][0].grapheme, " ");
+        assert_eq!(output.grid[0][1].grapheme, "X");
     }
 
     #[test]
@@ -508,15 +747,15 @@ mod tests {
         let mut output = Output::new(80, 24);
         // Write a wide char first
         output.write(0, 0, "你", &Style::default());
-        assert_eq!(output.grid[0][0].ch, '你');
-        assert_eq!(output.grid[0][1].ch, '\0');
+        assert_eq!(output.grid[0][0].grapheme, "你");
+        assert_eq!(output.grid[0][1].grapheme, "");
 
         // Overwrite the first half with a narrow char
         output.write_char(0, 0, 'X', &Style::default());
 
         // The wide char's placeholder should be cleared
-        assert_eq!(output.grid[0][0].ch, 'X');
-        assert_eq!(output.grid[0][1].ch, ' ');
+        assert_eq!(output.grid[0][0].grapheme, "X");
+        assert_eq!(output.grid[0][1].grapheme, " ");
     }
 
     #[test]
@@ -581,15 +820,15 @@ mod tests {
         output.write(3, 0, "你", &Style::default());
 
         // Position 3 should be a space, position 4 is at boundary
-        assert_eq!(output.grid[0][3].ch, '你');
-        assert_eq!(output.grid[0][4].ch, '\0');
+        assert_eq!(output.grid[0][3].grapheme, "你");
+        assert_eq!(output.grid[0][4].grapheme, "");
 
         // Now test when wide char would extend past buffer
         let mut output2 = Output::new(5, 1);
         output2.write(4, 0, "你", &Style::default());
 
         // Should write a space instead since wide char won't fit
-        assert_eq!(output2.grid[0][4].ch, ' ');
+        assert_eq!(output2.grid[0][4].grapheme, " ");
     }
 
     #[test]
@@ -599,7 +838,182 @@ mod tests {
         output.write(2, 0, "你", &Style::default());
 
         // Wide char at position 2-3 should fit exactly
-        assert_eq!(output.grid[0][2].ch, '你');
-        assert_eq!(output.grid[0][3].ch, '\0');
+        assert_eq!(output.grid[0][2].grapheme, "你");
+        assert_eq!(output.grid[0][3].grapheme, "");
+    }
+
+    #[test]
+    fn test_grapheme_clusters_keep_one_cell() {
+        let mut output = Output::new(20, 1);
+        // Combining accent, ZWJ family, flag and skin-tone modifier
+        output.write(0, 0, "e\u{301}|👨‍👩‍👧|🇯🇵|👍🏽|", &Style::default());
+
+        assert_eq!(output.grid[0][0].grapheme, "e\u{301}");
+        assert_eq!(output.grid[0][1].grapheme, "|");
+        assert_eq!(output.grid[0][2].grapheme, "👨‍👩‍👧");
+        assert!(output.grid[0][3].is_placeholder());
+        assert_eq!(output.grid[0][4].grapheme, "|");
+        assert_eq!(output.grid[0][5].grapheme, "🇯🇵");
+        assert_eq!(output.grid[0][7].grapheme, "|");
+        assert_eq!(output.grid[0][8].grapheme, "👍🏽");
+        assert_eq!(output.grid[0][10].grapheme, "|");
+
+        let text = "e\u{301}|👨‍👩‍👧|🇯🇵|👍🏽|";
+        assert_eq!(output.render(), text);
+        assert_eq!(measure_text_width(text), 11);
+    }
+
+    #[test]
+    fn test_emoji_keeps_border_aligned() {
+        let mut output = Output::new(8, 1);
+        output.write(0, 0, "│", &Style::default());
+        output.write(1, 0, "hi 👋🏻", &Style::default());
+        output.write_char(7, 0, '│', &Style::default());
+
+        assert_eq!(output.render(), "│hi 👋🏻 │");
+    }
+
+    #[test]
+    fn test_overwrite_middle_of_cluster() {
+        let mut output = Output::new(10, 1);
+        output.write(0, 0, "🇯🇵", &Style::default());
+        output.write_char(1, 0, 'X', &Style::default());
+
+        // The flag is broken and cleared, not left half-painted
+        assert_eq!(output.grid[0][0].grapheme, " ");
+        assert_eq!(output.render(), " X");
+    }
+
+    #[test]
+    fn test_diff_identical_frames_is_empty() {
+        let mut previous = Output::new(20, 3);
+        previous.write(0, 1, "unchanged", &Style::default());
+        let mut next = Output::new(20, 3);
+        next.write(0, 1, "unchanged", &Style::default());
+
+        assert_eq!(next.render_diff(&previous), "");
+    }
+
+    #[test]
+    fn test_diff_writes_only_changed_cell() {
+        let style = Style {
+            color: Some(Color::Cyan),
+            ..Default::default()
+        };
+
+        let mut previous = Output::new(80, 24);
+        let mut next = Output::new(80, 24);
+        for row in 0..24 {
+            previous.write(0, row, &"x".repeat(80), &Style::default());
+            next.write(0, row, &"x".repeat(80), &Style::default());
+        }
+        previous.write(10, 5, "⠋", &style);
+        next.write(10, 5, "⠙", &style);
+
+        assert_eq!(next.render_diff(&previous), "\x1b[6;11H\x1b[36m⠙\x1b[0m");
+    }
+
+    #[test]
+    fn test_diff_rewrites_short_gaps() {
+        let mut previous = Output::new(10, 1);
+        previous.write(0, 0, "abcdefghij", &Style::default());
+        let mut next = Output::new(10, 1);
+        next.write(0, 0, "AbCdefghiJ", &Style::default());
+
+        // Rewriting "b" is shorter than a cursor move; "defghi" is not
+        assert_eq!(next.render_diff(&previous), "\x1b[1;1HAbC\x1b[6CJ");
+    }
+
+    #[test]
+    fn test_diff_erases_shrunk_content() {
+        let mut previous = Output::new(10, 2);
+        previous.write(0, 0, "long line", &Style::default());
+        previous.write(0, 1, "second", &Style::default());
+        let mut next = Output::new(4, 1);
+        next.write(0, 0, "long", &Style::default());
+
+        // The space after "long" is unchanged, so erasing starts after it
+        assert_eq!(next.render_diff(&previous), "\x1b[1;6H\x1b[K\r\n\x1b[K");
+    }
+
+    #[test]
+    fn test_diff_redraws_whole_wide_grapheme() {
+        let mut previous = Output::new(6, 1);
+        previous.write(0, 0, "a你b", &Style::default());
+        let mut next = Output::new(6, 1);
+        next.write(0, 0, "a好b", &Style::default());
+
+        assert_eq!(next.render_diff(&previous), "\x1b[1;2H好");
+    }
+
+    #[test]
+    fn test_sgr_emits_only_changed_attributes() {
+        let red_bold = Style {
+            color: Some(Color::Red),
+            bold: true,
+            ..Default::default()
+        };
+        let bold = Style {
+            bold: true,
+            ..Default::default()
+        };
+        let mut output = Output::new(10, 1);
+        output.write(0, 0, "ab", &red_bold);
+        output.write(2, 0, "c", &bold);
+
+        assert_eq!(output.render(), "\x1b[1;31mab\x1b[39mc\x1b[0m");
+    }
+
+    #[test]
+    fn test_sgr_bold_off_keeps_dim() {
+        let bold_dim = Style {
+            color: Some(Color::Red),
+            bold: true,
+            dim: true,
+            ..Default::default()
+        };
+        let dim = Style {
+            color: Some(Color::Red),
+            dim: true,
+            ..Default::default()
+        };
+        let mut output = Output::new(10, 1);
+        output.write(0, 0, "a", &bold_dim);
+        output.write(1, 0, "b", &dim);
+
+        assert_eq!(output.render(), "\x1b[1;2;31ma\x1b[22;2mb\x1b[0m");
+    }
+
+    #[test]
+    fn test_sgr_resets_when_shorter() {
+        let styled = Style {
+            color: Some(Color::Red),
+            underline: true,
+            ..Default::default()
+        };
+        let mut output = Output::new(10, 1);
+        output.write(0, 0, "a", &styled);
+        output.write(1, 0, "b", &Style::default());
+
+        assert_eq!(output.render(), "\x1b[4;31ma\x1b[0mb");
+    }
+
+    #[test]
+    fn test_sgr_resets_color_that_resolves_to_default() {
+        let red = Style {
+            color: Some(Color::Red),
+            bold: true,
+            underline: true,
+            ..Default::default()
+        };
+        let reset = Style {
+            color: Some(Color::adaptive(Color::Reset, Color::Reset)),
+            ..red.clone()
+        };
+        let mut output = Output::new(10, 1);
+        output.write(0, 0, "a", &red);
+        output.write(1, 0, "b", &reset);
+
+        assert_eq!(output.render(), "\x1b[1;4;31ma\x1b[39mb\x1b[0m");
     }
 }
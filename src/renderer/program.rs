@@ -0,0 +1,466 @@
+//! Elm-style Model/Update/View applications
+//!
+//! A [`Program`] keeps all application state in a single model. Events and
+//! command results arrive as typed messages, and a pure `update` function
+//! turns each message into a new model plus a follow-up [`TypedCmd`]:
+//!
+//! - `init() -> (Model, TypedCmd<Msg>)` builds the initial model
+//! - `update(&mut Model, Msg) -> TypedCmd<Msg>` handles one message
+//! - `view(&Model) -> Element` renders the model
+//!
+//! Because `update` is a plain function it can be unit tested without a
+//! terminal, hooks or signals.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use rnk::prelude::*;
+//! use rnk::cmd::TypedCmd;
+//!
+//! struct Model {
+//!     count: i32,
+//!     quit: bool,
+//! }
+//!
+//! enum Msg {
+//!     Increment,
+//!     Loaded(i32),
+//!     Quit,
+//! }
+//!
+//! fn init() -> (Model, TypedCmd<Msg>) {
+//!     let load = TypedCmd::perform(|| async { Msg::Loaded(41) });
+//!     (Model { count: 0, quit: false }, load)
+//! }
+//!
+//! fn update(model: &mut Model, msg: Msg) -> TypedCmd<Msg> {
+//!     match msg {
+//!         Msg::Increment => model.count += 1,
+//!         Msg::Loaded(count) => model.count = count,
+//!         Msg::Quit => model.quit = true,
+//!     }
+//!     TypedCmd::none()
+//! }
+//!
+//! fn view(model: &Model) -> Element {
+//!     Text::new(format!("Count: {}", model.count)).into_element()
+//! }
+//!
+//! fn main() -> std::io::Result<()> {
+//!     rnk::program(init, update, view)
+//!         .on_key(|input, _key| match input {
+//!             "+" => Some(Msg::Increment),
+//!             "q" => Some(Msg::Quit),
+//!             _ => None,
+//!         })
+//!         .exit_when(|model| model.quit)
+//!         .run()
+//! }
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::{Arc, mpsc};
+
+use crate::cmd::TypedCmd;
+use crate::core::{ColorProfile, Element, TerminalBackground};
+use crate::hooks::context::current_context;
+use crate::hooks::get_app_context;
+use crate::hooks::use_input::{Key, use_input};
+use crate::hooks::use_mouse::{Mouse, use_mouse};
+
+use super::app::App;
+use super::builder::AppOptions;
+use super::registry::request_render;
+use super::terminal::Terminal;
+
+type Init<Model, Msg> = Box<dyn FnOnce() -> (Model, TypedCmd<Msg>)>;
+type Update<Model, Msg> = Rc<dyn Fn(&mut Model, Msg) -> TypedCmd<Msg>>;
+type View<Model> = Rc<dyn Fn(&Model) -> Element>;
+type KeyMap<Msg> = Rc<dyn Fn(&str, &Key) -> Option<Msg>>;
+type MouseMap<Msg> = Rc<dyn Fn(&Mouse) -> Option<Msg>>;
+type ResizeMap<Msg> = Rc<dyn Fn(u16, u16) -> Option<Msg>>;
+type ExitWhen<Model> = Rc<dyn Fn(&Model) -> bool>;
+
+/// An Elm-style application, created with [`program`]
+///
+/// Configure how terminal events become messages with [`on_key`],
+/// [`on_mouse`] and [`on_resize`], then call [`run`]. Display options
+/// mirror [`AppBuilder`](super::AppBuilder).
+///
+/// [`on_key`]: Program::on_key
+/// [`on_mouse`]: Program::on_mouse
+/// [`on_resize`]: Program::on_resize
+/// [`run`]: Program::run
+pub struct Program<Model, Msg> {
+    init: Init<Model, Msg>,
+    update: Update<Model, Msg>,
+    view: View<Model>,
+    on_key: Option<KeyMap<Msg>>,
+    on_mouse: Option<MouseMap<Msg>>,
+    on_resize: Option<ResizeMap<Msg>>,
+    exit_when: Option<ExitWhen<Model>>,
+    options: AppOptions,
+}
+
+impl<Model, Msg> Program<Model, Msg>
+where
+    Model: 'static,
+    Msg: Send + 'static,
+{
+    /// Turn key presses into messages
+    ///
+    /// The mapper receives the same `(input, key)` pair as
+    /// [`use_input`]; returning `None` ignores the key.
+    pub fn on_key<F>(mut self, map: F) -> Self
+    where
+        F: Fn(&str, &Key) -> Option<Msg> + 'static,
+    {
+        self.on_key = Some(Rc::new(map));
+        self
+    }
+
+    /// Turn mouse events into messages
+    pub fn on_mouse<F>(mut self, map: F) -> Self
+    where
+        F: Fn(&Mouse) -> Option<Msg> + 'static,
+    {
+        self.on_mouse = Some(Rc::new(map));
+        self
+    }
+
+    /// Turn terminal size changes into messages
+    ///
+    /// The mapper is also called with the initial size when the program
+    /// starts.
+    pub fn on_resize<F>(mut self, map: F) -> Self
+    where
+        F: Fn(u16, u16) -> Option<Msg> + 'static,
+    {
+        self.on_resize = Some(Rc::new(map));
+        self
+    }
+
+    /// Exit once the model satisfies a condition
+    ///
+    /// Checked after every batch of messages has been applied.
+    pub fn exit_when<F>(mut self, done: F) -> Self
+    where
+        F: Fn(&Model) -> bool + 'static,
+    {
+        self.exit_when = Some(Rc::new(done));
+        self
+    }
+
+    /// Use fullscreen mode (alternate screen buffer).
+    pub fn fullscreen(mut self) -> Self {
+        self.options.alternate_screen = true;
+        self
+    }
+
+    /// Use inline mode (default).
+    pub fn inline(mut self) -> Self {
+        self.options.alternate_screen = false;
+        self
+    }
+
+    /// Set the target frames per second.
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.options.fps = fps;
+        self
+    }
+
+    /// Set whether to exit on Ctrl+C.
+    pub fn exit_on_ctrl_c(mut self, exit: bool) -> Self {
+        self.options.exit_on_ctrl_c = exit;
+        self
+    }
+
+    /// Set whether frames are written as synchronized updates.
+    pub fn synchronized_output(mut self, enabled: bool) -> Self {
+        self.options.synchronized_output = Some(enabled);
+        self
+    }
+
+    /// Set the color profile instead of detecting it.
+    pub fn color_profile(mut self, profile: ColorProfile) -> Self {
+        self.options.color_profile = Some(profile);
+        self
+    }
+
+    /// Set the terminal background instead of querying the terminal.
+    pub fn background(mut self, background: TerminalBackground) -> Self {
+        self.options.background = Some(background);
+        self
+    }
+
+    /// Get the current options
+    pub fn options(&self) -> &AppOptions {
+        &self.options
+    }
+
+    /// Run the program
+    ///
+    /// Calls `init`, starts its command and runs the event loop until the
+    /// program exits.
+    pub fn run(self) -> std::io::Result<()> {
+        let options = self.options.clone();
+        let state = Rc::new(ProgramState::new(self));
+        App::with_options(move || state.frame(), options).run()
+    }
+}
+
+/// Create an Elm-style application
+///
+/// `init` builds the model and its first command, `update` applies one
+/// message, and `view` renders the model. See [`Program`] for the options.
+pub fn program<Model, Msg, I, U, V>(init: I, update: U, view: V) -> Program<Model, Msg>
+where
+    Model: 'static,
+    Msg: Send + 'static,
+    I: FnOnce() -> (Model, TypedCmd<Msg>) + 'static,
+    U: Fn(&mut Model, Msg) -> TypedCmd<Msg> + 'static,
+    V: Fn(&Model) -> Element + 'static,
+{
+    Program {
+        init: Box::new(init),
+        update: Rc::new(update),
+        view: Rc::new(view),
+        on_key: None,
+        on_mouse: None,
+        on_resize: None,
+        exit_when: None,
+        options: AppOptions::default(),
+    }
+}
+
+/// A running program: the model, its message queue and unstarted commands
+///
+/// Commands run on the app's executor: each frame hands the commands
+/// returned by `init` and `update` to the app, and their messages come back
+/// through the queue.
+struct ProgramState<Model, Msg> {
+    model: RefCell<Model>,
+    update: Update<Model, Msg>,
+    view: View<Model>,
+    on_key: Option<KeyMap<Msg>>,
+    on_mouse: Option<MouseMap<Msg>>,
+    on_resize: Option<ResizeMap<Msg>>,
+    exit_when: Option<ExitWhen<Model>>,
+    sender: mpsc::Sender<Msg>,
+    receiver: mpsc::Receiver<Msg>,
+    cmds: RefCell<Vec<TypedCmd<Msg>>>,
+    last_size: Cell<Option<(u16, u16)>>,
+}
+
+impl<Model, Msg> ProgramState<Model, Msg>
+where
+    Model: 'static,
+    Msg: Send + 'static,
+{
+    fn new(program: Program<Model, Msg>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        let (model, cmd) = (program.init)();
+        Self {
+            model: RefCell::new(model),
+            update: program.update,
+            view: program.view,
+            on_key: program.on_key,
+            on_mouse: program.on_mouse,
+            on_resize: program.on_resize,
+            exit_when: program.exit_when,
+            sender,
+            receiver,
+            cmds: RefCell::new(vec![cmd]),
+            last_size: Cell::new(None),
+        }
+    }
+
+    /// Queue a message for the next frame
+    fn dispatch(&self, msg: Msg) {
+        let _ = self.sender.send(msg);
+    }
+
+    /// Hand this frame's commands to the app, feeding their messages back
+    /// into the queue
+    fn start_cmds(&self) {
+        let Some(ctx) = current_context() else {
+            return;
+        };
+        let render_callback = ctx.borrow().get_sync_render_callback();
+        let sender = self.sender.clone();
+        let deliver: Arc<dyn Fn(Msg) + Send + Sync> = Arc::new(move |msg| {
+            let _ = sender.send(msg);
+            match &render_callback {
+                Some(callback) => callback(),
+                None => request_render(),
+            }
+        });
+
+        for cmd in self.cmds.take() {
+            if !cmd.is_none() {
+                ctx.borrow_mut().queue_cmd(cmd.map_msg(deliver.clone()));
+            }
+        }
+    }
+
+    /// Apply queued messages, then render the model
+    fn frame(&self) -> Element {
+        self.check_resize();
+
+        while let Ok(msg) = self.receiver.try_recv() {
+            let cmd = (self.update)(&mut self.model.borrow_mut(), msg);
+            self.cmds.borrow_mut().push(cmd);
+        }
+        self.start_cmds();
+
+        if let Some(exit_when) = &self.exit_when
+            && exit_when(&self.model.borrow())
+            && let Some(app) = get_app_context()
+        {
+            app.exit();
+        }
+
+        self.register_handlers();
+        (self.view)(&self.model.borrow())
+    }
+
+    fn check_resize(&self) {
+        let Some(on_resize) = &self.on_resize else {
+            return;
+        };
+        let size = Terminal::size().unwrap_or((80, 24));
+        if self.last_size.replace(Some(size)) != Some(size)
+            && let Some(msg) = on_resize(size.0, size.1)
+        {
+            self.dispatch(msg);
+        }
+    }
+
+    /// Register this frame's input handlers, which queue mapped messages
+    ///
+    /// The event loop renders after every input event, so queued messages
+    /// are applied on the next frame.
+    fn register_handlers(&self) {
+        if let Some(on_key) = &self.on_key {
+            let on_key = on_key.clone();
+            let sender = self.sender.clone();
+            use_input(move |input, key| {
+                if let Some(msg) = on_key(input, key) {
+                    let _ = sender.send(msg);
+                }
+            });
+        }
+
+        if let Some(on_mouse) = &self.on_mouse {
+            let on_mouse = on_mouse.clone();
+            let sender = self.sender.clone();
+            use_mouse(move |mouse| {
+                if let Some(msg) = on_mouse(mouse) {
+                    let _ = sender.send(msg);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Text;
+    use crate::hooks::use_input::{clear_input_handlers, dispatch_input};
+
+    #[derive(Debug)]
+    enum Msg {
+        Increment,
+        Loaded(i32),
+        Resized(u16, u16),
+    }
+
+    #[derive(Default)]
+    struct Model {
+        count: i32,
+        resizes: Vec<(u16, u16)>,
+    }
+
+    fn update(model: &mut Model, msg: Msg) -> TypedCmd<Msg> {
+        match msg {
+            Msg::Increment => model.count += 1,
+            Msg::Loaded(count) => {
+                model.count = count;
+                return TypedCmd::perform(|| async { Msg::Increment });
+            }
+            Msg::Resized(width, height) => model.resizes.push((width, height)),
+        }
+        TypedCmd::none()
+    }
+
+    fn view(model: &Model) -> Element {
+        Text::new(format!("{}", model.count)).into_element()
+    }
+
+    /// Run a program's frames inside an app, as `Program::run` does
+    fn start(
+        program: Program<Model, Msg>,
+    ) -> (Rc<ProgramState<Model, Msg>>, App<impl Fn() -> Element>) {
+        let state = Rc::new(ProgramState::new(program));
+        let frame = state.clone();
+        (state, App::new(move || frame.frame()))
+    }
+
+    fn text<F: Fn() -> Element>(app: &App<F>) -> String {
+        app.render_detached().get_text().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_messages_update_model() {
+        let (state, app) = start(program(
+            || (Model::default(), TypedCmd::none()),
+            update,
+            view,
+        ));
+
+        assert_eq!(text(&app), "0");
+        state.dispatch(Msg::Increment);
+        state.dispatch(Msg::Increment);
+        assert_eq!(text(&app), "2");
+    }
+
+    #[test]
+    fn test_commands_resolve_to_messages() {
+        let init = || {
+            let load = TypedCmd::perform(|| async { Msg::Loaded(41) });
+            (Model::default(), load)
+        };
+        let (_state, app) = start(program(init, update, view));
+
+        // `Loaded` arrives first, and its follow-up command increments
+        let mut output = text(&app);
+        while output != "42" {
+            app.wait_for_render_request();
+            output = text(&app);
+        }
+    }
+
+    #[test]
+    fn test_key_and_resize_messages() {
+        clear_input_handlers();
+        let (state, app) = start(
+            program(|| (Model::default(), TypedCmd::none()), update, view)
+                .on_key(|input, _| (input == "+").then_some(Msg::Increment))
+                .on_resize(|w, h| Some(Msg::Resized(w, h))),
+        );
+
+        assert_eq!(text(&app), "0");
+        dispatch_input("+", &Key::default());
+        dispatch_input("x", &Key::default());
+        clear_input_handlers();
+        assert_eq!(text(&app), "1");
+
+        // The initial size is reported once, not on every frame
+        let size = Terminal::size().unwrap_or((80, 24));
+        assert_eq!(state.model.borrow().resizes, vec![size]);
+        clear_input_handlers();
+    }
+}
@@ -4,7 +4,8 @@
 //! outside of the main application runtime.
 
 use crate::core::Element;
-use crate::layout::LayoutEngine;
+use crate::layout::{LayoutEngine, layout_text, measure_text_width};
+use crate::renderer::element_renderer::render_text;
 use crate::renderer::{Output, Terminal};
 
 /// Render an element to a string with specified width.
@@ -119,8 +120,6 @@ impl RenderHelper {
 
     #[allow(dead_code)]
     fn calculate_content_width(&self, element: &Element) -> u16 {
-        use unicode_width::UnicodeWidthStr;
-
         // If element has explicit width set, use it
         if let crate::core::Dimension::Points(w) = element.style.width {
             return w as u16;
@@ -130,7 +129,7 @@ impl RenderHelper {
 
         // Calculate text content width
         if let Some(text) = &element.text_content {
-            width = width.max(text.width() as u16);
+            width = width.max(measure_text_width(text) as u16);
         }
 
         // Calculate spans width
@@ -175,8 +174,6 @@ impl RenderHelper {
         max_width: u16,
         _engine: &mut LayoutEngine,
     ) -> u16 {
-        use crate::layout::measure::wrap_text;
-
         let mut height = 1u16;
 
         // Calculate available width for text
@@ -188,21 +185,20 @@ impl RenderHelper {
         let padding_h = (element.style.padding.left + element.style.padding.right) as u16;
         let available_width = available_width.saturating_sub(padding_h).max(1);
 
-        // Check for multiline spans with wrapping
-        if let Some(lines) = &element.spans {
-            let mut total_lines = 0usize;
-            for line in lines {
-                let line_text: String = line.spans.iter().map(|s| s.content.as_str()).collect();
-                let wrapped = wrap_text(&line_text, available_width as usize);
-                total_lines += wrapped.len();
-            }
-            height = height.max(total_lines as u16);
-        }
-
-        // Check text_content with wrapping
-        if let Some(text) = &element.text_content {
-            let wrapped = wrap_text(text, available_width as usize);
-            height = height.max(wrapped.len() as u16);
+        // Text takes the rows it is painted with
+        let pieces: Vec<&str> = element
+            .text_pieces()
+            .into_iter()
+            .map(|(text, _)| text)
+            .collect();
+        if !pieces.is_empty() {
+            let lines = layout_text(
+                &pieces,
+                Some(available_width as usize),
+                element.style.text_wrap,
+                element.style.ellipsis(),
+            );
+            height = height.max(lines.len() as u16);
         }
 
         // Add border height
@@ -262,15 +258,7 @@ impl RenderHelper {
             self.render_border(element, output, x, y, width, height);
         }
 
-        if let Some(text) = &element.text_content {
-            let text_x = x
-                + if element.style.has_border() { 1 } else { 0 }
-                + element.style.padding.left as u16;
-            let text_y = y
-                + if element.style.has_border() { 1 } else { 0 }
-                + element.style.padding.top as u16;
-            output.write(text_x, text_y, text, &element.style);
-        }
+        render_text(element, output, x, y, width);
 
         let child_offset_x = offset_x + layout.x;
         let child_offset_y = offset_y + layout.y;
@@ -4,6 +4,7 @@
 //! integration with the Command system (CmdExecutor).
 
 use crossterm::event::Event;
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
@@ -20,6 +21,8 @@ pub(crate) struct EventLoop {
     should_exit: Arc<AtomicBool>,
     fps: u32,
     exit_on_ctrl_c: bool,
+    /// Events read before the loop started, handled ahead of new input
+    queued: VecDeque<Event>,
 }
 
 impl EventLoop {
@@ -34,9 +37,15 @@ impl EventLoop {
             should_exit,
             fps,
             exit_on_ctrl_c,
+            queued: VecDeque::new(),
         }
     }
 
+    /// Handle `events` before any new input once the loop runs
+    pub(crate) fn queue_events(&mut self, events: impl IntoIterator<Item = Event>) {
+        self.queued.extend(events);
+    }
+
     /// Run the event loop
     ///
     /// Returns when should_exit is set or an error occurs
@@ -52,7 +61,11 @@ impl EventLoop {
 
         loop {
             // Handle input events
-            if let Some(event) = Terminal::poll_event(Duration::from_millis(10))? {
+            let event = match self.queued.pop_front() {
+                Some(event) => Some(event),
+                None => Terminal::poll_event(Duration::from_millis(10))?,
+            };
+            if let Some(event) = event {
                 self.handle_event(event);
             }
 
@@ -5,6 +5,7 @@
 
 use crate::core::Element;
 use crate::layout::LayoutEngine;
+use crate::renderer::element_renderer::render_text;
 use crate::renderer::{Output, Terminal};
 
 /// Static content renderer for inline mode
@@ -14,6 +15,8 @@ use crate::renderer::{Output, Terminal};
 pub(crate) struct StaticRenderer {
     /// Lines of static content that have been committed
     committed_lines: Vec<String>,
+    /// Layout engine reused for every static item
+    layout_engine: LayoutEngine,
 }
 
 impl StaticRenderer {
@@ -21,6 +24,7 @@ impl StaticRenderer {
     pub(crate) fn new() -> Self {
         Self {
             committed_lines: Vec::new(),
+            layout_engine: LayoutEngine::new(),
         }
     }
 
@@ -28,20 +32,20 @@ impl StaticRenderer {
     ///
     /// Only extracts content from Static elements that have actual children
     /// (new items to render). Empty Static elements are skipped.
-    pub(crate) fn extract_static_content(&self, element: &Element, width: u16) -> Vec<String> {
+    pub(crate) fn extract_static_content(&mut self, element: &Element, width: u16) -> Vec<String> {
         let mut lines = Vec::new();
         self.extract_recursive(element, width, &mut lines);
         lines
     }
 
     /// Recursive helper for extracting static content
-    fn extract_recursive(&self, element: &Element, width: u16, lines: &mut Vec<String>) {
+    fn extract_recursive(&mut self, element: &Element, width: u16, lines: &mut Vec<String>) {
         if element.style.is_static {
             // Only render if the static element has children (new items)
             // Empty Static elements mean all items have already been rendered
             if !element.children.is_empty() {
                 // Render static element to get its content
-                let mut engine = LayoutEngine::new();
+                let engine = &mut self.layout_engine;
                 engine.compute(element, width, 100); // Use large height for static content
 
                 let layout = engine.get_layout(element.id).unwrap_or_default();
@@ -49,7 +53,7 @@ impl StaticRenderer {
                 let render_width = (layout.width as u16).max(1);
                 let render_height = (layout.height as u16).max(1);
                 let mut output = Output::new(render_width, render_height);
-                Self::render_element_to_output(element, &engine, &mut output, 0.0, 0.0);
+                Self::render_element_to_output(element, engine, &mut output, 0.0, 0.0);
 
                 let rendered = output.render();
                 for line in rendered.lines() {
@@ -155,15 +159,7 @@ impl StaticRenderer {
             Self::render_border(element, output, x, y, width, height);
         }
 
-        if let Some(text) = &element.text_content {
-            let text_x = x
-                + if element.style.has_border() { 1 } else { 0 }
-                + element.style.padding.left as u16;
-            let text_y = y
-                + if element.style.has_border() { 1 } else { 0 }
-                + element.style.padding.top as u16;
-            output.write(text_x, text_y, text, &element.style);
-        }
+        render_text(element, output, x, y, width);
 
         let child_offset_x = offset_x + layout.x;
         let child_offset_y = offset_y + layout.y;
@@ -258,7 +254,7 @@ mod tests {
 
     #[test]
     fn test_extract_empty_element() {
-        let renderer = StaticRenderer::new();
+        let mut renderer = StaticRenderer::new();
         let element = Text::new("Hello").into_element();
         let lines = renderer.extract_static_content(&element, 80);
         assert!(lines.is_empty()); // Non-static elements return empty
@@ -288,7 +284,7 @@ mod tests {
 
     #[test]
     fn test_extract_static_with_children() {
-        let renderer = StaticRenderer::new();
+        let mut renderer = StaticRenderer::new();
 
         // Create a static element with children
         let mut static_element = Box::new()
@@ -17,9 +17,12 @@ use crossterm::{
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use std::io::{Write, stdout};
+use std::io::{IsTerminal, Write, stdout};
 use std::time::Duration;
 
+use super::output::Output;
+use crate::core::TerminalBackground;
+
 /// ANSI escape codes for terminal control
 mod ansi {
     /// Move cursor to specific position (1-indexed)
@@ -120,6 +123,21 @@ mod ansi {
     pub fn leave_alt_screen() -> &'static str {
         "\x1b[?1049l"
     }
+
+    /// Ask the terminal for its background color (OSC 11)
+    pub fn query_background() -> &'static str {
+        "\x1b]11;?\x1b\\"
+    }
+
+    /// Begin a synchronized update (terminal holds painting)
+    pub fn begin_synchronized_update() -> &'static str {
+        "\x1b[?2026h"
+    }
+
+    /// End a synchronized update (terminal paints the frame)
+    pub fn end_synchronized_update() -> &'static str {
+        "\x1b[?2026l"
+    }
 }
 
 /// Terminal abstraction with ink-style rendering
@@ -133,6 +151,8 @@ mod ansi {
 pub struct Terminal {
     /// Previous frame's lines for incremental rendering
     previous_lines: Vec<String>,
+    /// Previous fullscreen frame for cell-level diffing
+    previous_frame: Option<Output>,
     /// Whether we're in alternate screen mode
     alternate_screen: bool,
     /// Whether cursor is hidden
@@ -143,6 +163,8 @@ pub struct Terminal {
     mouse_enabled: bool,
     /// Number of lines rendered in inline mode (for cursor positioning)
     inline_lines_rendered: usize,
+    /// Whether frames are wrapped in synchronized update markers
+    synchronized_output: bool,
 }
 
 impl Terminal {
@@ -150,11 +172,13 @@ impl Terminal {
     pub fn new() -> Self {
         Self {
             previous_lines: Vec::new(),
+            previous_frame: None,
             alternate_screen: false,
             cursor_hidden: false,
             raw_mode: false,
             mouse_enabled: false,
             inline_lines_rendered: 0,
+            synchronized_output: false,
         }
     }
 
@@ -163,6 +187,35 @@ impl Terminal {
         self.alternate_screen
     }
 
+    /// Set whether frames are wrapped in synchronized update markers
+    ///
+    /// With DEC mode 2026 the terminal waits for the end marker before
+    /// painting, so a frame never shows up half-written.
+    pub fn set_synchronized_output(&mut self, enabled: bool) {
+        self.synchronized_output = enabled;
+    }
+
+    /// Check if frames are written as synchronized updates
+    pub fn is_synchronized_output(&self) -> bool {
+        self.synchronized_output
+    }
+
+    /// Start a frame, holding painting if synchronized output is on
+    fn begin_frame(&self, out: &mut impl Write) -> std::io::Result<()> {
+        if self.synchronized_output {
+            write!(out, "{}", ansi::begin_synchronized_update())?;
+        }
+        Ok(())
+    }
+
+    /// Finish a frame started with `begin_frame` and flush it
+    fn end_frame(&self, out: &mut impl Write) -> std::io::Result<()> {
+        if self.synchronized_output {
+            write!(out, "{}", ansi::end_synchronized_update())?;
+        }
+        out.flush()
+    }
+
     /// Enter raw mode and alternate screen (fullscreen mode)
     pub fn enter(&mut self) -> std::io::Result<()> {
         enable_raw_mode()?;
@@ -268,6 +321,7 @@ impl Terminal {
 
         self.alternate_screen = true;
         self.previous_lines.clear();
+        self.previous_frame = None;
         self.inline_lines_rendered = 0;
 
         Ok(())
@@ -297,6 +351,7 @@ impl Terminal {
 
         self.alternate_screen = false;
         self.previous_lines.clear();
+        self.previous_frame = None;
         self.inline_lines_rendered = 0;
 
         // Re-hide cursor for rendering
@@ -383,12 +438,43 @@ impl Terminal {
         }
     }
 
+    /// Render an output buffer to the terminal
+    ///
+    /// In fullscreen mode the cell grid is compared with the previous frame
+    /// and only the cells that changed are written, so a spinner ticking in
+    /// a large view costs a few bytes instead of a whole line. Inline mode
+    /// renders the buffer's lines like [`render`](Self::render).
+    pub fn render_output(&mut self, output: Output) -> std::io::Result<()> {
+        if !self.alternate_screen {
+            return self.render_inline(&output.render());
+        }
+
+        let mut stdout = stdout();
+        self.begin_frame(&mut stdout)?;
+        match &self.previous_frame {
+            Some(previous) => write!(stdout, "{}", output.render_diff(previous))?,
+            None => write!(
+                stdout,
+                "{}{}",
+                ansi::erase_screen(),
+                output.render_diff(&Output::new(0, 0))
+            )?,
+        }
+        self.end_frame(&mut stdout)?;
+
+        self.previous_frame = Some(output);
+        Ok(())
+    }
+
     /// Render in fullscreen/alternate screen mode
     fn render_fullscreen(&mut self, output: &str) -> std::io::Result<()> {
         let mut stdout = stdout();
+        // The screen no longer matches the last cell grid
+        self.previous_frame = None;
+        self.begin_frame(&mut stdout)?;
 
         // Move to top-left
-        execute!(stdout, MoveTo(0, 0))?;
+        write!(stdout, "{}", ansi::cursor_home())?;
 
         let new_lines: Vec<&str> = output.lines().collect();
 
@@ -420,7 +506,7 @@ impl Terminal {
             }
         }
 
-        stdout.flush()?;
+        self.end_frame(&mut stdout)?;
 
         // Store current lines for next comparison
         self.previous_lines = new_lines.iter().map(|s| s.to_string()).collect();
@@ -446,6 +532,8 @@ impl Terminal {
         // This is separate from previous_lines which may be cleared by repaint()
         let lines_on_screen = self.inline_lines_rendered;
 
+        self.begin_frame(&mut stdout)?;
+
         // Move cursor to the start of our output area if we have content on screen
         if lines_on_screen > 0 {
             if lines_on_screen > 1 {
@@ -490,7 +578,7 @@ impl Terminal {
         }
         write!(stdout, "{}", ansi::cursor_to_column(0))?;
 
-        stdout.flush()?;
+        self.end_frame(&mut stdout)?;
 
         // Store current lines for next comparison
         self.previous_lines = new_lines.iter().map(|s| s.to_string()).collect();
@@ -501,12 +589,13 @@ impl Terminal {
 
     /// Clear the current output
     pub fn clear(&mut self) -> std::io::Result<()> {
-        if self.previous_lines.is_empty() {
+        let frame_height = self.previous_frame.take().map_or(0, |f| f.height as usize);
+        let line_count = self.previous_lines.len().max(frame_height);
+        if line_count == 0 {
             return Ok(());
         }
 
         let mut stdout = stdout();
-        let line_count = self.previous_lines.len();
 
         if self.alternate_screen {
             execute!(stdout, MoveTo(0, 0))?;
@@ -545,6 +634,7 @@ impl Terminal {
     /// Force a full repaint on next render
     pub fn repaint(&mut self) {
         self.previous_lines.clear();
+        self.previous_frame = None;
     }
 
     /// Get terminal size
@@ -552,6 +642,52 @@ impl Terminal {
         crossterm::terminal::size()
     }
 
+    /// Detect whether the terminal has a light or dark background
+    ///
+    /// Sends an OSC 11 query for the background color followed by a cursor
+    /// position request. Terminals answer in order and every terminal
+    /// answers the position request, so its reply marks the end of the wait
+    /// whether or not OSC 11 is supported (crossterm keeps device attribute
+    /// replies to itself, so the position request plays that role here).
+    /// Falls back to the `COLORFGBG` variable some terminals set, then to a
+    /// dark background. Raw mode must be enabled so the replies aren't echoed.
+    ///
+    /// Input that arrived while waiting is returned in order, so the caller
+    /// can handle it like any other event.
+    pub fn detect_background() -> (TerminalBackground, Vec<Event>) {
+        let (color, typed_ahead) = Self::query_background_color();
+        let background = color
+            .map(|(r, g, b)| TerminalBackground::from_rgb(r, g, b))
+            .or_else(|| {
+                std::env::var("COLORFGBG")
+                    .ok()
+                    .and_then(|value| TerminalBackground::from_colorfgbg(&value))
+            })
+            .unwrap_or_default();
+        (background, typed_ahead)
+    }
+
+    /// Send an OSC 11 query and collect the input received until the
+    /// terminal has answered
+    fn query_background_color() -> (Option<(u8, u8, u8)>, Vec<Event>) {
+        if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+            return (None, Vec::new());
+        }
+
+        let mut stdout = stdout();
+        let sent = write!(stdout, "{}", ansi::query_background()).and_then(|_| stdout.flush());
+        // Waits for the position reply; events read meanwhile stay queued
+        if sent.is_err() || crossterm::cursor::position().is_err() {
+            return (None, Vec::new());
+        }
+
+        let mut events = Vec::new();
+        while let Ok(Some(event)) = Self::poll_event(Duration::ZERO) {
+            events.push(event);
+        }
+        split_background_reply(events)
+    }
+
     /// Poll for input event
     pub fn poll_event(timeout: Duration) -> std::io::Result<Option<Event>> {
         if event::poll(timeout)? {
@@ -602,6 +738,64 @@ impl Terminal {
     }
 }
 
+/// Find an OSC 11 reply among input events and take it out
+///
+/// The event parser turns ESC-prefixed bytes of the reply into Alt key
+/// presses and BEL into Ctrl+G, so the reply is rebuilt from those. All
+/// other events are returned in their original order.
+fn split_background_reply(mut events: Vec<Event>) -> (Option<(u8, u8, u8)>, Vec<Event>) {
+    let pieces: Vec<Option<String>> = events.iter().map(reply_piece).collect();
+    for start in 0..events.len() {
+        if pieces[start].as_deref() != Some("\x1b]") {
+            continue;
+        }
+        let mut reply = String::new();
+        for (end, piece) in pieces.iter().enumerate().skip(start) {
+            let Some(piece) = piece else { break };
+            reply.push_str(piece);
+            if let Some(color) = parse_background_reply(&reply) {
+                events.drain(start..=end);
+                return (Some(color), events);
+            }
+        }
+    }
+    (None, events)
+}
+
+/// Reply text an input event stands for, if it could be part of a reply
+fn reply_piece(event: &Event) -> Option<String> {
+    let Event::Key(key) = event else {
+        return None;
+    };
+    match key.code {
+        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some("\x07".to_string())
+        }
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::ALT) => Some(format!("\x1b{c}")),
+        KeyCode::Char(c) => Some(c.to_string()),
+        _ => None,
+    }
+}
+
+/// Parse an OSC 11 reply such as `ESC ] 11 ; rgb:ffff/ffff/ffff ESC \`
+///
+/// Each channel has one to four hex digits and is scaled to 0-255. Returns
+/// `None` until the reply is complete.
+fn parse_background_reply(reply: &str) -> Option<(u8, u8, u8)> {
+    let start = reply.find("\x1b]11;")? + 5;
+    let body = &reply[start..];
+    let end = body.find(['\x07', '\x1b'])?;
+    let (_, channels) = body[..end].split_once(':')?;
+
+    let mut rgb = channels.split('/').map(|channel| {
+        let digits = channel.len().clamp(1, 4) as u32;
+        let value = u32::from_str_radix(channel.get(..digits as usize)?, 16).ok()?;
+        let max = 16u32.pow(digits) - 1;
+        Some(((value * 255 + max / 2) / max) as u8)
+    });
+    Some((rgb.next()??, rgb.next()??, rgb.next()??))
+}
+
 impl Default for Terminal {
     fn default() -> Self {
         Self::new()
@@ -638,6 +832,77 @@ mod tests {
         assert_eq!(ansi::erase_screen(), "\x1b[2J");
         assert_eq!(ansi::enter_alt_screen(), "\x1b[?1049h");
         assert_eq!(ansi::leave_alt_screen(), "\x1b[?1049l");
+        assert_eq!(ansi::begin_synchronized_update(), "\x1b[?2026h");
+        assert_eq!(ansi::end_synchronized_update(), "\x1b[?2026l");
+    }
+
+    #[test]
+    fn test_synchronized_frame_markers() {
+        let mut terminal = Terminal::new();
+        let mut frame = Vec::new();
+        terminal.begin_frame(&mut frame).unwrap();
+        terminal.end_frame(&mut frame).unwrap();
+        assert!(frame.is_empty());
+
+        terminal.set_synchronized_output(true);
+        assert!(terminal.is_synchronized_output());
+        terminal.begin_frame(&mut frame).unwrap();
+        frame.extend_from_slice(b"frame");
+        terminal.end_frame(&mut frame).unwrap();
+        assert_eq!(frame, b"\x1b[?2026hframe\x1b[?2026l");
+    }
+
+    #[test]
+    fn test_parse_background_reply() {
+        assert_eq!(
+            parse_background_reply("\x1b]11;rgb:ffff/ffff/ffff\x1b\\"),
+            Some((255, 255, 255))
+        );
+        assert_eq!(
+            parse_background_reply("\x1b]11;rgb:1e1e/1e1e/2e2e\x07"),
+            Some((30, 30, 46))
+        );
+        assert_eq!(
+            parse_background_reply("\x1b]11;rgb:f/8/0\x07"),
+            Some((255, 136, 0))
+        );
+        // Incomplete replies wait for more input
+        assert_eq!(parse_background_reply("\x1b]11;rgb:ffff/ff"), None);
+    }
+
+    #[test]
+    fn test_split_background_reply_keeps_typed_input() {
+        use crossterm::event::KeyEvent;
+
+        // Key events the way the event parser reports a reply
+        let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+        let reply = "]11;rgb:ffff/ffff/ffff".chars().enumerate().map(|(i, c)| {
+            let modifiers = if i == 0 {
+                KeyModifiers::ALT
+            } else {
+                KeyModifiers::NONE
+            };
+            key(KeyCode::Char(c), modifiers)
+        });
+
+        let mut events = vec![key(KeyCode::Char('a'), KeyModifiers::NONE)];
+        events.extend(reply);
+        events.push(key(KeyCode::Char('\\'), KeyModifiers::ALT));
+        events.push(key(KeyCode::Enter, KeyModifiers::NONE));
+
+        let (color, rest) = split_background_reply(events);
+        assert_eq!(color, Some((255, 255, 255)));
+        assert_eq!(
+            rest,
+            vec![
+                key(KeyCode::Char('a'), KeyModifiers::NONE),
+                key(KeyCode::Enter, KeyModifiers::NONE),
+            ]
+        );
+
+        // Without a reply every event is kept
+        let typed = vec![key(KeyCode::Char('q'), KeyModifiers::NONE)];
+        assert_eq!(split_background_reply(typed.clone()), (None, typed));
     }
 
     #[test]
@@ -652,7 +917,9 @@ mod tests {
     fn test_repaint_clears_previous_lines() {
         let mut terminal = Terminal::new();
         terminal.previous_lines = vec!["line1".to_string(), "line2".to_string()];
+        terminal.previous_frame = Some(Output::new(4, 2));
         terminal.repaint();
         assert!(terminal.previous_lines.is_empty());
+        assert!(terminal.previous_frame.is_none());
     }
 }
@@ -159,6 +159,16 @@ impl RuntimeContext {
         self.input_handlers.push(Rc::new(handler));
     }
 
+    /// Input handlers registered so far in this frame
+    pub(crate) fn input_handlers(&self) -> &[InputHandlerFn] {
+        &self.input_handlers
+    }
+
+    /// Register an already shared input handler
+    pub(crate) fn push_input_handler(&mut self, handler: InputHandlerFn) {
+        self.input_handlers.push(handler);
+    }
+
     /// Dispatch input to all handlers
     pub fn dispatch_input(&self, input: &str, key: &Key) {
         for handler in &self.input_handlers {
@@ -182,6 +192,17 @@ impl RuntimeContext {
         self.mouse_enabled = true;
     }
 
+    /// Mouse handlers registered so far in this frame
+    pub(crate) fn mouse_handlers(&self) -> &[MouseHandlerFn] {
+        &self.mouse_handlers
+    }
+
+    /// Register an already shared mouse handler
+    pub(crate) fn push_mouse_handler(&mut self, handler: MouseHandlerFn) {
+        self.mouse_handlers.push(handler);
+        self.mouse_enabled = true;
+    }
+
     /// Dispatch mouse event to all handlers
     pub fn dispatch_mouse(&self, mouse: &Mouse) {
         for handler in &self.mouse_handlers {
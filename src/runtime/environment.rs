@@ -127,6 +127,52 @@ pub fn is_tty() -> bool {
     std::io::stdout().is_terminal()
 }
 
+/// Check if the terminal supports synchronized output
+///
+/// Synchronized output (DEC private mode 2026) lets the terminal hold back
+/// painting until a whole frame has been written. Terminals that don't know
+/// the mode ignore it, but some multiplexers and old emulators misbehave, so
+/// it is only reported for emulators known to implement it.
+pub fn supports_synchronized_output() -> bool {
+    is_tty() && synchronized_output_from(|name| std::env::var(name).ok())
+}
+
+/// Synchronized output detection from environment variables
+fn synchronized_output_from(var: impl Fn(&str) -> Option<String>) -> bool {
+    const PROGRAMS: &[&str] = &[
+        "wezterm",
+        "iterm.app",
+        "vscode",
+        "ghostty",
+        "contour",
+        "rio",
+        "tmux",
+    ];
+    const TERMS: &[&str] = &[
+        "kitty",
+        "foot",
+        "alacritty",
+        "ghostty",
+        "contour",
+        "wezterm",
+    ];
+
+    let term = var("TERM").unwrap_or_default().to_lowercase();
+    if term == "dumb" {
+        return false;
+    }
+    // GNU screen passes unknown modes through to the outer terminal badly
+    if term.starts_with("screen") && var("TMUX").is_none() {
+        return false;
+    }
+
+    let program = var("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+    PROGRAMS.contains(&program.as_str())
+        || TERMS.iter().any(|t| term.contains(t))
+        || var("KITTY_WINDOW_ID").is_some()
+        || var("WT_SESSION").is_some()
+}
+
 /// Get the terminal size, with fallback values
 ///
 /// Returns (80, 24) as fallback when size cannot be determined.
@@ -179,6 +225,33 @@ mod tests {
         assert!(h > 0);
     }
 
+    #[test]
+    fn test_synchronized_output_detection() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        assert!(synchronized_output_from(env(&[("TERM", "xterm-kitty")])));
+        assert!(synchronized_output_from(env(&[
+            ("TERM", "xterm-256color"),
+            ("TERM_PROGRAM", "WezTerm"),
+        ])));
+        assert!(synchronized_output_from(env(&[("WT_SESSION", "abc")])));
+        assert!(!synchronized_output_from(env(&[(
+            "TERM",
+            "xterm-256color"
+        )])));
+        assert!(!synchronized_output_from(env(&[
+            ("TERM", "screen-256color"),
+            ("TERM_PROGRAM", "vscode"),
+        ])));
+        assert!(!synchronized_output_from(env(&[("TERM", "dumb")])));
+    }
+
     #[test]
     fn test_should_disable_colors() {
         let env = Environment {
@@ -4,7 +4,7 @@
 //! - Unified runtime context for app state
 //! - Panic hook for terminal restoration
 //! - Signal handling (SIGINT, SIGTERM, SIGHUP)
-//! - Environment detection (CI, TTY)
+//! - Environment detection (CI, TTY, terminal capabilities)
 
 mod context;
 mod environment;
@@ -14,6 +14,6 @@ mod signal_handler;
 pub use context::{
     RuntimeContext, current_runtime, set_current_runtime, with_current_runtime, with_runtime,
 };
-pub use environment::{Environment, is_ci, is_tty};
-pub use panic_handler::{install_panic_hook, restore_terminal};
+pub use environment::{Environment, is_ci, is_tty, supports_synchronized_output};
+pub use panic_handler::{catch_panic, install_panic_hook, restore_terminal};
 pub use signal_handler::{SignalHandler, install_signal_handler};
@@ -2,8 +2,10 @@
 //!
 //! Ensures terminal is restored to a usable state even when the program panics.
 
+use std::any::Any;
+use std::cell::{Cell, RefCell};
 use std::io::Write;
-use std::panic;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::Once;
 
 use crossterm::{
@@ -12,6 +14,19 @@ use crossterm::{
 };
 
 static PANIC_HOOK_INSTALLED: Once = Once::new();
+static CATCH_HOOK_INSTALLED: Once = Once::new();
+
+thread_local! {
+    /// Number of `catch_panic` calls active on this thread
+    static CATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// Location of the last panic caught by `catch_panic`
+    static CAUGHT_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Whether a panic on this thread will be caught by `catch_panic`
+fn is_catching() -> bool {
+    CATCH_DEPTH.with(|depth| depth.get() > 0)
+}
 
 /// Restore terminal to a normal state
 ///
@@ -60,6 +75,12 @@ pub fn install_panic_hook() {
         let original_hook = panic::take_hook();
 
         panic::set_hook(Box::new(move |panic_info| {
+            // Panics caught by `catch_panic` leave the terminal alone
+            if is_catching() {
+                original_hook(panic_info);
+                return;
+            }
+
             // First, restore terminal state
             restore_terminal();
 
@@ -78,6 +99,59 @@ pub fn install_panic_hook() {
     });
 }
 
+/// Decrements the catch depth even if the caught closure unwinds
+struct CatchGuard;
+
+impl Drop for CatchGuard {
+    fn drop(&mut self) {
+        CATCH_DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Run a closure, turning a panic into an error message
+///
+/// Unlike a bare `std::panic::catch_unwind`, a panic caught here neither
+/// restores the terminal (see [`install_panic_hook`]) nor prints to stderr,
+/// so a running app can keep drawing after recovering from it. The error
+/// contains the panic message and, when known, its source location.
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, String> {
+    CATCH_HOOK_INSTALLED.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |panic_info| {
+            if is_catching() {
+                let location = panic_info
+                    .location()
+                    .map(|loc| format!("{}:{}", loc.file(), loc.line()));
+                CAUGHT_LOCATION.with(|caught| *caught.borrow_mut() = location);
+            } else {
+                previous_hook(panic_info);
+            }
+        }));
+    });
+
+    CATCH_DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let _guard = CatchGuard;
+
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let message = panic_message(payload.as_ref());
+        match CAUGHT_LOCATION.with(|caught| caught.borrow_mut().take()) {
+            Some(location) => format!("{} (at {})", message, location),
+            None => message,
+        }
+    })
+}
+
+/// Extract the message from a panic payload
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panic with non-string payload".to_string()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,4 +168,19 @@ mod tests {
         install_panic_hook();
         install_panic_hook();
     }
+
+    #[test]
+    fn test_catch_panic() {
+        assert_eq!(catch_panic(|| 42), Ok(42));
+
+        let error = catch_panic(|| panic!("broken panel")).unwrap_err();
+        assert!(error.starts_with("broken panel (at "));
+        assert!(error.contains("panic_handler.rs"));
+
+        let error = catch_panic(|| std::panic::panic_any(7)).unwrap_err();
+        assert!(error.starts_with("panic with non-string payload"));
+
+        // Depth is restored after a caught panic
+        assert!(!is_catching());
+    }
 }
@@ -4,11 +4,11 @@
 //! layout and rendering output without terminal interaction.
 
 use std::collections::HashMap;
-use unicode_width::UnicodeWidthChar;
 
 use crate::core::{Display, Element, ElementId, Position};
-use crate::layout::{Layout, LayoutEngine};
+use crate::layout::{Layout, LayoutEngine, measure_text_width};
 use crate::renderer::Output;
+use crate::renderer::element_renderer::render_text;
 
 /// Test renderer configuration
 #[derive(Debug, Clone)]
@@ -177,15 +177,7 @@ impl TestRenderer {
         }
 
         // Text content
-        if let Some(text) = &element.text_content {
-            let text_x = x
-                + if element.style.has_border() { 1 } else { 0 }
-                + element.style.padding.left as u16;
-            let text_y = y
-                + if element.style.has_border() { 1 } else { 0 }
-                + element.style.padding.top as u16;
-            output.write(text_x, text_y, text, &element.style);
-        }
+        render_text(element, output, x, y, w);
 
         // Children
         let cx = offset_x + layout.x;
@@ -334,8 +326,11 @@ pub fn strip_ansi_codes(s: &str) -> String {
 }
 
 /// Calculate display width of text accounting for Unicode
+///
+/// Same as [`measure_text_width`]: grapheme clusters are measured whole, the
+/// way the output buffer lays them out.
 pub fn display_width(s: &str) -> usize {
-    s.chars().filter_map(|c| c.width()).sum()
+    measure_text_width(s)
 }
 
 #[cfg(test)]
@@ -392,4 +387,23 @@ mod tests {
         assert_eq!(layout.width, 20.0);
         assert_eq!(layout.height, 5.0);
     }
+
+    #[test]
+    fn test_render_to_ansi_emits_style_deltas() {
+        use crate::components::Span;
+        use crate::core::Color;
+
+        let renderer = TestRenderer::new(20, 1);
+        let element = Text::spans(vec![
+            Span::new("ab").color(Color::Red).bold(),
+            Span::new("cd").color(Color::Green).bold(),
+            Span::new("ef").bold(),
+        ])
+        .into_element();
+
+        // Bold is turned on once; only the foreground changes after that
+        let ansi = renderer.render_to_ansi(&element);
+        assert_eq!(ansi, "\x1b[1;31mab\x1b[32mcd\x1b[39mef\x1b[0m");
+        assert_eq!(ansi.len(), 27);
+    }
 }